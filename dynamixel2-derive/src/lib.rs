@@ -0,0 +1,163 @@
+//! Derive macros for the [`dynamixel2`](https://docs.rs/dynamixel2) crate.
+//!
+//! Do not use this crate directly.
+//! Instead, enable the `derive` feature of `dynamixel2` and use the re-exported macros.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+
+/// Derive the `dynamixel2::bus::Data` trait for a struct.
+///
+/// The fields of the struct are encoded back to back in declaration order,
+/// so the struct can be used to read or write a contiguous block of registers.
+/// All fields must implement `Data` themselves.
+///
+/// Unused registers between fields can be skipped with the `#[dxl(padding = N)]` attribute.
+/// It adds `N` bytes before the field.
+/// The padding is written as zeros, and ignored when decoding.
+///
+/// ```
+/// # use dynamixel2::bus::Data;
+/// #[derive(Data)]
+/// struct PresentState {
+///     present_pwm: i16,      // address 124
+///     present_current: i16,  // address 126
+///     present_velocity: i32, // address 128
+///     present_position: i32, // address 132
+///     #[dxl(padding = 8)]
+///     present_voltage: u16,  // address 144
+///     present_temperature: u8, // address 146
+/// }
+///
+/// assert_eq!(PresentState::ENCODED_SIZE, 23);
+/// ```
+#[proc_macro_derive(Data, attributes(dxl))]
+pub fn derive_data(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = syn::parse_macro_input!(input as syn::DeriveInput);
+	match generate_data_impl(input) {
+		Ok(output) => output.into(),
+		Err(e) => e.into_compile_error().into(),
+	}
+}
+
+/// A field of the struct with its parsed attributes.
+struct Field {
+	/// The name or index of the field.
+	member: syn::Member,
+
+	/// The type of the field.
+	ty: syn::Type,
+
+	/// The number of padding bytes before the field.
+	padding: u16,
+}
+
+fn generate_data_impl(input: syn::DeriveInput) -> syn::Result<TokenStream> {
+	let data = match &input.data {
+		syn::Data::Struct(data) => data,
+		syn::Data::Enum(data) => {
+			return Err(syn::Error::new_spanned(data.enum_token, "#[derive(Data)] only supports structs"));
+		},
+		syn::Data::Union(data) => {
+			return Err(syn::Error::new_spanned(data.union_token, "#[derive(Data)] only supports structs"));
+		},
+	};
+
+	let fields = data.fields.iter()
+		.enumerate()
+		.map(|(index, field)| {
+			let member = match &field.ident {
+				Some(ident) => syn::Member::Named(ident.clone()),
+				None => syn::Member::Unnamed(index.into()),
+			};
+			Ok(Field {
+				member,
+				ty: field.ty.clone(),
+				padding: parse_padding(&field.attrs)?,
+			})
+		})
+		.collect::<syn::Result<Vec<_>>>()?;
+
+	let name = &input.ident;
+	let mut generics = input.generics.clone();
+	{
+		let where_clause = generics.make_where_clause();
+		for field in &fields {
+			let ty = &field.ty;
+			where_clause.predicates.push(syn::parse_quote!(#ty: ::dynamixel2::bus::Data));
+		}
+	}
+	let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+	let sizes = fields.iter().map(|field| {
+		let ty = &field.ty;
+		let padding = field.padding;
+		quote!(#padding + <#ty as ::dynamixel2::bus::Data>::ENCODED_SIZE)
+	});
+
+	let encode_fields = fields.iter().map(|field| {
+		let Field { member, ty, padding } = field;
+		quote! {
+			buffer[offset..][..#padding as usize].fill(0);
+			offset += #padding as usize;
+			::dynamixel2::bus::Data::encode(&self.#member, &mut buffer[offset..][..<#ty as ::dynamixel2::bus::Data>::ENCODED_SIZE as usize])?;
+			offset += <#ty as ::dynamixel2::bus::Data>::ENCODED_SIZE as usize;
+		}
+	});
+
+	let variables: Vec<_> = (0..fields.len()).map(|i| format_ident!("field_{}", i, span = Span::mixed_site())).collect();
+	let decode_fields = fields.iter().zip(&variables).map(|(field, variable)| {
+		let Field { ty, padding, .. } = field;
+		quote! {
+			offset += #padding as usize;
+			let #variable = <#ty as ::dynamixel2::bus::Data>::decode(&buffer[offset..][..<#ty as ::dynamixel2::bus::Data>::ENCODED_SIZE as usize])?;
+			offset += <#ty as ::dynamixel2::bus::Data>::ENCODED_SIZE as usize;
+		}
+	});
+	let members = fields.iter().map(|field| &field.member);
+
+	Ok(quote! {
+		#[automatically_derived]
+		impl #impl_generics ::dynamixel2::bus::Data for #name #type_generics #where_clause {
+			const ENCODED_SIZE: u16 = 0 #(+ #sizes)*;
+
+			#[allow(unused_mut, unused_variables, unused_assignments)]
+			fn encode(&self, buffer: &mut [u8]) -> ::core::result::Result<(), ::dynamixel2::BufferTooSmallError> {
+				::dynamixel2::BufferTooSmallError::check(<Self as ::dynamixel2::bus::Data>::ENCODED_SIZE as usize, buffer.len())?;
+				let mut offset = 0usize;
+				#(#encode_fields)*
+				::core::result::Result::Ok(())
+			}
+
+			#[allow(unused_mut, unused_variables, unused_assignments)]
+			fn decode(buffer: &[u8]) -> ::core::result::Result<Self, ::dynamixel2::InvalidMessage> {
+				::dynamixel2::InvalidParameterCount::check(buffer.len(), <Self as ::dynamixel2::bus::Data>::ENCODED_SIZE as usize)?;
+				let mut offset = 0usize;
+				#(#decode_fields)*
+				::core::result::Result::Ok(Self {
+					#(#members: #variables,)*
+				})
+			}
+		}
+	})
+}
+
+/// Parse the `#[dxl(padding = N)]` attributes of a field.
+fn parse_padding(attrs: &[syn::Attribute]) -> syn::Result<u16> {
+	let mut padding = 0;
+	for attr in attrs {
+		if !attr.path().is_ident("dxl") {
+			continue;
+		}
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("padding") {
+				let value: syn::LitInt = meta.value()?.parse()?;
+				padding += value.base10_parse::<u16>()?;
+				Ok(())
+			} else {
+				Err(meta.error("unknown dxl attribute, expected `padding`"))
+			}
+		})?;
+	}
+	Ok(padding)
+}
@@ -0,0 +1,83 @@
+use assert2::{assert, let_assert};
+use dynamixel2::bus::Data;
+use dynamixel2::InvalidMessage;
+
+#[derive(Data, Debug, PartialEq)]
+struct PresentState {
+	present_pwm: i16,
+	present_current: i16,
+	present_velocity: i32,
+	present_position: i32,
+	#[dxl(padding = 8)]
+	present_input_voltage: u16,
+	present_temperature: u8,
+}
+
+#[derive(Data, Debug, PartialEq)]
+struct Gains(u16, #[dxl(padding = 2)] u16);
+
+#[derive(Data, Debug, PartialEq)]
+struct Wrapper<T> {
+	value: T,
+	array: [u8; 2],
+}
+
+#[test]
+fn encoded_size_includes_padding() {
+	assert!(PresentState::ENCODED_SIZE == 2 + 2 + 4 + 4 + 8 + 2 + 1);
+	assert!(Gains::ENCODED_SIZE == 6);
+	assert!(Wrapper::<u32>::ENCODED_SIZE == 6);
+}
+
+#[test]
+fn encode_and_decode_struct() {
+	let state = PresentState {
+		present_pwm: -2,
+		present_current: 0x0201,
+		present_velocity: 0x06050403,
+		present_position: -1,
+		present_input_voltage: 120,
+		present_temperature: 35,
+	};
+	let mut buffer = [0xAA; 23];
+	let_assert!(Ok(()) = state.encode(&mut buffer));
+	assert!(buffer == [
+		0xFE, 0xFF,
+		0x01, 0x02,
+		0x03, 0x04, 0x05, 0x06,
+		0xFF, 0xFF, 0xFF, 0xFF,
+		0, 0, 0, 0, 0, 0, 0, 0,
+		120, 0,
+		35,
+	]);
+
+	// Padding is ignored when decoding.
+	buffer[12..20].fill(0x55);
+	let_assert!(Ok(decoded) = PresentState::decode(&buffer));
+	assert!(decoded == state);
+}
+
+#[test]
+fn encode_and_decode_tuple_struct() {
+	let mut buffer = [0xAA; 6];
+	let_assert!(Ok(()) = Gains(0x0201, 0x0403).encode(&mut buffer));
+	assert!(buffer == [0x01, 0x02, 0, 0, 0x03, 0x04]);
+	let_assert!(Ok(Gains(0x0201, 0x0403)) = Gains::decode(&buffer));
+}
+
+#[test]
+fn encode_and_decode_generic_struct() {
+	let mut buffer = [0; 6];
+	let value = Wrapper { value: 0x04030201u32, array: [5, 6] };
+	let_assert!(Ok(()) = value.encode(&mut buffer));
+	assert!(buffer == [1, 2, 3, 4, 5, 6]);
+	let_assert!(Ok(decoded) = Wrapper::<u32>::decode(&buffer));
+	assert!(decoded == value);
+}
+
+#[test]
+fn reject_wrong_size() {
+	let mut buffer = [0; 5];
+	assert!(let Err(_) = Gains(1, 2).encode(&mut buffer));
+	let_assert!(Err(InvalidMessage::InvalidParameterCount(_)) = Gains::decode(&buffer));
+}
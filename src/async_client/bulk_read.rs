@@ -0,0 +1,169 @@
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+
+use futures_core::Stream;
+
+use super::AsyncClient;
+use crate::bus::data::{decode_status_packet_bytes, decode_status_packet_bytes_borrow};
+use crate::bus::ExpectedPacket;
+use crate::instructions::{instruction_id, BulkReadData};
+use crate::{ReadError, Response, WriteError};
+
+impl<SerialPort, Buffer> AsyncClient<SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Synchronously read arbitrary data ranges from multiple motors.
+	///
+	/// Unlike the sync read instruction, a bulk read can be used to read a different amount of data from a different address for each motor.
+	/// The returned stream yields the response of each motor.
+	///
+	/// # Panics
+	/// The protocol forbids specifying the same motor ID multiple times.
+	/// This function panics if the same motor ID is used for more than one read.
+	pub async fn bulk_read_bytes<'a, T>(
+		&'a mut self,
+		reads: &'a [BulkReadData],
+	) -> Result<BulkReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	where
+		T: for<'b> From<&'b [u8]>,
+	{
+		self.write_bulk_read_instruction(instruction_id::BULK_READ, reads).await?;
+		Ok(BulkReadBytes::new(self, reads))
+	}
+
+	/// Synchronously read arbitrary data ranges from multiple motors, borrowing the response from the internal read buffer.
+	///
+	/// Use [`BulkReadBytes::read_next_borrow()`] to get the responses.
+	///
+	/// # Panics
+	/// The protocol forbids specifying the same motor ID multiple times.
+	/// This function panics if the same motor ID is used for more than one read.
+	pub async fn bulk_read_bytes_borrow<'a, T>(
+		&'a mut self,
+		reads: &'a [BulkReadData],
+	) -> Result<BulkReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	where
+		T: ?Sized,
+		[u8]: core::borrow::Borrow<T>,
+	{
+		self.write_bulk_read_instruction(instruction_id::BULK_READ, reads).await?;
+		Ok(BulkReadBytes::new(self, reads))
+	}
+}
+
+/// An async bulk read operation that returns unparsed bytes.
+///
+/// Dropping the stream does not wait for the remaining responses.
+pub struct BulkReadBytes<'a, T, SerialPort, Buffer>
+where
+	T: ?Sized,
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	client: &'a mut AsyncClient<SerialPort, Buffer>,
+	bulk_read_data: &'a [BulkReadData],
+	index: usize,
+	deadline: Option<SerialPort::Instant>,
+	data: PhantomData<fn() -> T>,
+}
+
+impl<T, SerialPort, Buffer> core::fmt::Debug for BulkReadBytes<'_, T, SerialPort, Buffer>
+where
+	T: ?Sized,
+	SerialPort: crate::AsyncSerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("BulkReadBytes")
+			.field("serial_port", self.client.serial_port())
+			.field("bulk_read_data", &self.bulk_read_data)
+			.field("index", &self.index)
+			.field("data", &format_args!("{}", core::any::type_name::<T>()))
+			.finish()
+	}
+}
+
+impl<'a, T, SerialPort, Buffer> BulkReadBytes<'a, T, SerialPort, Buffer>
+where
+	T: ?Sized,
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn new(client: &'a mut AsyncClient<SerialPort, Buffer>, bulk_read_data: &'a [BulkReadData]) -> Self {
+		Self {
+			client,
+			bulk_read_data,
+			index: 0,
+			deadline: None,
+			data: PhantomData,
+		}
+	}
+
+	/// Get the number of responses that should still be received.
+	pub fn remaining(&self) -> usize {
+		self.bulk_read_data.len() - self.index
+	}
+
+	/// Read the next motor reply.
+	pub async fn read_next(&mut self) -> Option<Result<Response<T>, ReadError<SerialPort::Error>>>
+	where
+		T: for<'b> From<&'b [u8]> + Sized,
+	{
+		core::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+	}
+
+	/// Read the next motor reply, borrowing the data from the internal read buffer.
+	pub async fn read_next_borrow(&mut self) -> Option<Result<Response<&T>, ReadError<SerialPort::Error>>>
+	where
+		[u8]: core::borrow::Borrow<T>,
+	{
+		let BulkReadData { motor_id, count, .. } = *self.bulk_read_data.get(self.index)?;
+		self.index += 1;
+		self.deadline = None;
+		Some(self.next_response_borrow(motor_id, count).await)
+	}
+
+	async fn next_response_borrow(&mut self, motor_id: u8, count: u16) -> Result<Response<&T>, ReadError<SerialPort::Error>>
+	where
+		[u8]: core::borrow::Borrow<T>,
+	{
+		let response = self.client.read_status_response(count).await?;
+		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
+		crate::InvalidParameterCount::check(response.parameters().len(), count.into())?;
+		Ok(decode_status_packet_bytes_borrow(response)?)
+	}
+}
+
+impl<T, SerialPort, Buffer> Stream for BulkReadBytes<'_, T, SerialPort, Buffer>
+where
+	T: for<'b> From<&'b [u8]>,
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	type Item = Result<Response<T>, ReadError<SerialPort::Error>>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		let Some(&BulkReadData { motor_id, count, .. }) = this.bulk_read_data.get(this.index) else {
+			return Poll::Ready(None);
+		};
+		let timeout = crate::bus::status_response_timeout(count.into(), this.client.baud_rate());
+		this.client.bus.response_motor_id = Some(motor_id);
+		let response = ready!(this.client.poll_status_response(cx, &mut this.deadline, ExpectedPacket::status(count.into(), timeout)));
+		this.index += 1;
+		Poll::Ready(Some(response.and_then(|response| {
+			// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
+			// We need to report a timeout or something for the missed motor though.
+			crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
+			crate::InvalidParameterCount::check(response.parameters().len(), count.into())?;
+			Ok(decode_status_packet_bytes(response)?)
+		})))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.remaining()))
+	}
+}
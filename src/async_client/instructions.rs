@@ -0,0 +1,389 @@
+use super::{read_response_if_expected, AsyncClient};
+use crate::bus::data::{decode_status_packet, decode_status_packet_bytes};
+use crate::bus::endian::write_u16_le;
+use crate::bus::{Data, StatusPacket};
+use crate::instructions::{bulk_read, bulk_write, clear, control_table_backup, fast_bulk_read, fast_sync_read, sync_read, sync_write};
+use crate::instructions::{instruction_id, packet_id, BulkReadData, BulkWriteData, FactoryResetKind, FastBulkRead, FastSyncRead, Ping, SyncWriteData};
+use crate::{ReadError, Response, TransferError, WriteError};
+
+impl<SerialPort, Buffer> AsyncClient<SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Ping a specific motor by ID.
+	///
+	/// This will not work correctly if the motor ID is [`packet_id::BROADCAST`].
+	/// Use [`Self::scan`] instead.
+	pub async fn ping(&mut self, motor_id: u8) -> Result<Response<Ping>, TransferError<SerialPort::Error>> {
+		let response = self.transfer_single(motor_id, instruction_id::PING, 0, 3, |_| Ok(())).await?;
+		Ok(response.try_into()?)
+	}
+
+	async fn read_raw(&mut self, motor_id: u8, address: u16, count: u16) -> Result<StatusPacket<'_>, TransferError<SerialPort::Error>> {
+		let response = self.transfer_single(motor_id, instruction_id::READ, 4, count, |buffer| {
+			write_u16_le(&mut buffer[0..], address);
+			write_u16_le(&mut buffer[2..], count);
+			Ok(())
+		}).await?;
+		crate::error::InvalidParameterCount::check(response.parameters().len(), count.into()).map_err(crate::ReadError::from)?;
+		Ok(response)
+	}
+
+	/// Read an arbitrary number of bytes from a specific motor.
+	///
+	/// See [`crate::Client::read_bytes()`].
+	pub async fn read_bytes<'a, T>(&'a mut self, motor_id: u8, address: u16, count: u16) -> Result<Response<T>, TransferError<SerialPort::Error>>
+	where
+		T: From<&'a [u8]>,
+	{
+		let status = self.read_raw(motor_id, address, count).await?;
+		Ok(decode_status_packet_bytes(status)?)
+	}
+
+	/// Read a value from a specific motor.
+	///
+	/// See [`crate::Client::read()`].
+	pub async fn read<T: Data>(&mut self, motor_id: u8, address: u16) -> Result<Response<T>, TransferError<SerialPort::Error>> {
+		let status = self.read_raw(motor_id, address, T::ENCODED_SIZE).await?;
+		Ok(decode_status_packet(status)?)
+	}
+
+	/// Write a value to a specific motor.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	///
+	/// If the motor does not reply to writes at its [status return level][Self::set_status_return_level], this function does not wait for a reply either.
+	pub async fn write<T: Data>(&mut self, motor_id: u8, address: u16, data: &T) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::WRITE, 2 + T::ENCODED_SIZE as usize, |buffer| {
+			write_u16_le(&mut buffer[0..], address);
+			data.encode(&mut buffer[2..])
+		}).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::WRITE).await?)
+	}
+
+	/// Write an arbitrary number of bytes to a specific motor.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub async fn write_bytes(&mut self, motor_id: u8, address: u16, data: &[u8]) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::WRITE, 2 + data.len(), |buffer| {
+			write_u16_le(&mut buffer[0..], address);
+			buffer[2..].copy_from_slice(data);
+			Ok(())
+		}).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::WRITE).await?)
+	}
+
+	/// Register a write of an arbitrary number of bytes, to be triggered later by an `action` command.
+	///
+	/// See [`crate::Client::reg_write_bytes()`].
+	pub async fn reg_write_bytes(&mut self, motor_id: u8, address: u16, data: &[u8]) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::REG_WRITE, 2 + data.len(), |buffer| {
+			write_u16_le(&mut buffer[0..], address);
+			buffer[2..].copy_from_slice(data);
+			Ok(())
+		}).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::REG_WRITE).await?)
+	}
+
+	/// Register a write command for value to a specific motor.
+	///
+	/// See [`crate::Client::reg_write()`].
+	pub async fn reg_write<T: Data>(&mut self, motor_id: u8, address: u16, value: &T) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::REG_WRITE, 2 + T::ENCODED_SIZE as usize, |buffer| {
+			write_u16_le(&mut buffer[0..], address);
+			value.encode(&mut buffer[2..])
+		}).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::REG_WRITE).await?)
+	}
+
+	/// Send an action command to trigger a previously registered instruction.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub async fn action(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::ACTION, 0, |_| Ok(())).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::ACTION).await?)
+	}
+
+	/// Broadcast an action command to all connected motors to trigger a previously registered instruction.
+	pub async fn broadcast_action(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(packet_id::BROADCAST, instruction_id::ACTION, 0, |_| Ok(())).await
+	}
+
+	/// Reset the settings of a motor to the factory defaults.
+	///
+	/// See [`crate::Client::factory_reset()`] for the caveats of broadcasting this instruction.
+	pub async fn factory_reset(&mut self, motor_id: u8, kind: FactoryResetKind) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::FACTORY_RESET, 1, |buffer| {
+			buffer[0] = kind as u8;
+			Ok(())
+		}).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::FACTORY_RESET).await?)
+	}
+
+	/// Reset the settings of all connected motors to the factory defaults.
+	///
+	/// See [`crate::Client::broadcast_factory_reset()`] for the caveats of this instruction.
+	pub async fn broadcast_factory_reset(&mut self, kind: FactoryResetKind) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(packet_id::BROADCAST, instruction_id::FACTORY_RESET, 1, |buffer| {
+			buffer[0] = kind as u8;
+			Ok(())
+		}).await
+	}
+
+	/// Send a reboot command to a specific motor.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub async fn reboot(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::REBOOT, 0, |_| Ok(())).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::REBOOT).await?)
+	}
+
+	/// Broadcast a reboot command to all connected motors.
+	pub async fn broadcast_reboot(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(packet_id::BROADCAST, instruction_id::REBOOT, 0, |_| Ok(())).await
+	}
+
+	/// Clear the multi-revolution counter of a motor.
+	///
+	/// See [`crate::Client::clear_revolution_counter()`].
+	pub async fn clear_revolution_counter(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(
+			motor_id,
+			instruction_id::CLEAR,
+			clear::CLEAR_REVOLUTION_COUNT.len(),
+			clear::clear_revolution_count_parameters,
+		).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::CLEAR).await?)
+	}
+
+	/// Clear the revolution counter of all connected motors.
+	pub async fn broadcast_clear_revolution_counter(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(
+			packet_id::BROADCAST,
+			instruction_id::CLEAR,
+			clear::CLEAR_REVOLUTION_COUNT.len(),
+			clear::clear_revolution_count_parameters,
+		).await
+	}
+
+	/// Clear the error of a motor.
+	///
+	/// See [`crate::Client::clear_error()`].
+	pub async fn clear_error(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::CLEAR, clear::CLEAR_ERROR.len(), clear::clear_error_parameters).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::CLEAR).await?)
+	}
+
+	/// Try to clear the error of all motors on the bus.
+	pub async fn broadcast_clear_error(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(packet_id::BROADCAST, instruction_id::CLEAR, clear::CLEAR_ERROR.len(), clear::clear_error_parameters).await
+	}
+
+	/// Store the current control table of a motor in its backup area.
+	///
+	/// See [`crate::Client::control_table_backup()`].
+	pub async fn control_table_backup(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(
+			motor_id,
+			instruction_id::CONTROL_TABLE_BACKUP,
+			control_table_backup::BACKUP.len(),
+			control_table_backup::backup_parameters,
+		).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::CONTROL_TABLE_BACKUP).await?)
+	}
+
+	/// Store the current control table of all connected motors in their backup area.
+	pub async fn broadcast_control_table_backup(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(
+			packet_id::BROADCAST,
+			instruction_id::CONTROL_TABLE_BACKUP,
+			control_table_backup::BACKUP.len(),
+			control_table_backup::backup_parameters,
+		).await
+	}
+
+	/// Restore the control table of a motor from its backup area.
+	///
+	/// See [`crate::Client::control_table_restore()`].
+	pub async fn control_table_restore(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(
+			motor_id,
+			instruction_id::CONTROL_TABLE_BACKUP,
+			control_table_backup::RESTORE.len(),
+			control_table_backup::restore_parameters,
+		).await?;
+		Ok(read_response_if_expected(self, motor_id, instruction_id::CONTROL_TABLE_BACKUP).await?)
+	}
+
+	/// Restore the control table of all connected motors from their backup area.
+	pub async fn broadcast_control_table_restore(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(
+			packet_id::BROADCAST,
+			instruction_id::CONTROL_TABLE_BACKUP,
+			control_table_backup::RESTORE.len(),
+			control_table_backup::restore_parameters,
+		).await
+	}
+
+	/// Synchronously write an arbitrary number of bytes to multiple motors.
+	///
+	/// See [`crate::Client::sync_write_bytes()`].
+	///
+	/// # Panics
+	/// The amount of data to write for each motor must be exactly `count` bytes.
+	/// This function panics if that is not the case.
+	pub async fn sync_write_bytes<'a, Iter, Data, Buf>(&mut self, address: u16, count: u16, data: Iter) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		Iter: IntoIterator<Item = Data>,
+		Iter::IntoIter: ExactSizeIterator,
+		Data: AsRef<SyncWriteData<Buf>>,
+		Buf: AsRef<[u8]> + 'a,
+	{
+		let data = data.into_iter();
+		let parameter_count = 4 + data.len() * (1 + usize::from(count));
+		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_WRITE, parameter_count, |buffer| {
+			sync_write::encode_sync_write_bytes_parameters(buffer, address, count, data)
+		}).await
+	}
+
+	/// Synchronously write the same value to multiple motors.
+	///
+	/// See [`crate::Client::sync_write()`].
+	pub async fn sync_write<Iter, Data, T>(&mut self, address: u16, data: Iter) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		Iter: IntoIterator<Item = Data>,
+		Iter::IntoIter: ExactSizeIterator,
+		Data: AsRef<SyncWriteData<T>>,
+		T: crate::bus::Data,
+	{
+		let data = data.into_iter();
+		let parameter_count = 4 + data.len() * (1 + usize::from(T::ENCODED_SIZE));
+		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_WRITE, parameter_count, |buffer| {
+			sync_write::encode_sync_write_parameters(buffer, address, data)
+		}).await
+	}
+
+	/// Synchronously write arbitrary data ranges to multiple motors.
+	///
+	/// See [`crate::Client::bulk_write()`].
+	///
+	/// # Panics
+	/// The protocol forbids specifying the same motor ID multiple times.
+	/// This function panics if the same motor ID is used for more than one write.
+	///
+	/// This function also panics if the data length for a motor exceeds the capacity of a `u16`.
+	pub async fn bulk_write<'a, I, D>(&mut self, writes: &'a I) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		&'a I: IntoIterator,
+		<&'a I as IntoIterator>::IntoIter: Clone,
+		<&'a I as IntoIterator>::Item: core::borrow::Borrow<BulkWriteData<D>>,
+		D: AsRef<[u8]>,
+	{
+		let writes = writes.into_iter();
+		let parameter_count = bulk_write::bulk_write_parameter_count(writes.clone());
+		self.write_instruction(packet_id::BROADCAST, instruction_id::BULK_WRITE, parameter_count, |buffer| {
+			bulk_write::encode_bulk_write_parameters(buffer, writes)
+		}).await
+	}
+
+	/// Synchronously read a number of bytes from multiple motors, with a single combined response.
+	///
+	/// See [`crate::Client::fast_sync_read_bytes()`].
+	pub async fn fast_sync_read_bytes<'a, T>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+		count: u16,
+	) -> Result<FastSyncRead<'a, T, SerialPort::Error>, TransferError<SerialPort::Error>>
+	where
+		T: From<&'a [u8]>,
+	{
+		let packet = self.transfer_fast_sync_read(motor_ids, address, count).await?;
+		Ok(FastSyncRead::new(packet, motor_ids, address, count, |data| Ok(T::from(data))))
+	}
+
+	/// Synchronously read values from multiple motors, with a single combined response.
+	///
+	/// See [`crate::Client::fast_sync_read()`].
+	pub async fn fast_sync_read<'a, T: Data>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+	) -> Result<FastSyncRead<'a, T, SerialPort::Error>, TransferError<SerialPort::Error>> {
+		let packet = self.transfer_fast_sync_read(motor_ids, address, T::ENCODED_SIZE).await?;
+		Ok(FastSyncRead::new(packet, motor_ids, address, T::ENCODED_SIZE, T::decode))
+	}
+
+	/// Write a fast sync read instruction and read the combined response.
+	async fn transfer_fast_sync_read(
+		&mut self,
+		motor_ids: &[u8],
+		address: u16,
+		count: u16,
+	) -> Result<&[u8], TransferError<SerialPort::Error>> {
+		self.write_instruction(packet_id::BROADCAST, instruction_id::FAST_SYNC_READ, 4 + motor_ids.len(), |buffer| {
+			sync_read::encode_sync_read_parameters(buffer, motor_ids, address, count)
+		}).await?;
+
+		if motor_ids.is_empty() {
+			return Ok(&[]);
+		}
+
+		let parameters = fast_sync_read::fast_sync_read_response_parameters(motor_ids, count);
+		self.read_fast_read_response(parameters).await
+	}
+
+	/// Synchronously read arbitrary data ranges from multiple motors, with a single combined response.
+	///
+	/// See [`crate::Client::fast_bulk_read_bytes()`].
+	///
+	/// # Panics
+	/// The protocol forbids specifying the same motor ID multiple times.
+	/// This function panics if the same motor ID is used for more than one read.
+	pub async fn fast_bulk_read_bytes<'a, T>(
+		&'a mut self,
+		reads: &'a [BulkReadData],
+	) -> Result<FastBulkRead<'a, T, SerialPort::Error>, TransferError<SerialPort::Error>>
+	where
+		T: From<&'a [u8]>,
+	{
+		self.write_bulk_read_instruction(instruction_id::FAST_BULK_READ, reads).await?;
+
+		if reads.is_empty() {
+			return Ok(FastBulkRead::new(&[], reads));
+		}
+
+		let parameters = fast_bulk_read::fast_bulk_read_response_parameters(reads);
+		let packet = self.read_fast_read_response(parameters).await?;
+		Ok(FastBulkRead::new(packet, reads))
+	}
+
+	/// Read the combined response of a fast read instruction.
+	///
+	/// Returns the raw bytes of the status packet, without the final CRC.
+	async fn read_fast_read_response(&mut self, parameters: usize) -> Result<&[u8], TransferError<SerialPort::Error>> {
+		let timeout = crate::bus::status_response_timeout(parameters, self.baud_rate());
+		let response = self.read_status_response_timeout_unchecked(crate::bus::ExpectedPacket::fast_read_response(parameters, timeout)).await?;
+		Ok(crate::instructions::check_fast_read_response(response, parameters).map_err(ReadError::from)?)
+	}
+
+	/// Write a bulk read or fast bulk read instruction to the bus.
+	///
+	/// # Panic
+	/// Panics if multiple read operation use the same motor ID.
+	pub(super) async fn write_bulk_read_instruction(
+		&mut self,
+		instruction_id: u8,
+		reads: &[BulkReadData],
+	) -> Result<(), WriteError<SerialPort::Error>> {
+		bulk_read::check_unique_bulk_read_motor_ids(reads);
+		self.write_instruction(packet_id::BROADCAST, instruction_id, 5 * reads.len(), |buffer| {
+			bulk_read::encode_bulk_read_parameters(buffer, reads)
+		}).await
+	}
+}
@@ -0,0 +1,517 @@
+//! An async client for the Dynamixel Protocol 2.0.
+//!
+//! The [`AsyncClient`] mirrors the blocking [`Client`][crate::Client], but it uses an [`AsyncSerialPort`][crate::AsyncSerialPort].
+//! All instructions are `async` functions, and instructions that receive multiple responses return a [`Stream`][futures_core::Stream].
+//! The packets are encoded and decoded exactly like they are for the blocking client,
+//! and the timeouts for the responses are calculated in the same way.
+//!
+//! The blocking iterators wait for the remaining responses when they are dropped.
+//! That is not possible for a stream, so a stream that is dropped early leaves the remaining responses on the bus.
+//! They are discarded when the next instruction is sent,
+//! but responses that arrive after that may cause errors for the next instruction.
+//! So it is best to always exhaust the streams.
+//!
+//! This module is only available if the `async` feature is enabled.
+
+use core::task::{ready, Context, Poll};
+use core::time::Duration;
+#[cfg(feature = "serial2-tokio")]
+use std::path::Path;
+
+use crate::bus::{Bus, ExpectedPacket, StatusPacket};
+use crate::client::StatusReturnLevels;
+use crate::{ReadError, StatusReturnLevel, TransferError, WriteError};
+
+mod bulk_read;
+pub use bulk_read::BulkReadBytes;
+
+mod instructions;
+
+mod scan;
+pub use scan::Scan;
+
+mod sync_read;
+pub use sync_read::{SyncRead, SyncReadBytes};
+
+macro_rules! make_async_client_struct {
+	($($DefaultSerialPort:ty)?) => {
+		/// Async client for the Dynamixel Protocol 2 communication.
+		///
+		/// Used to interact with devices on the bus without blocking the current thread.
+		///
+		/// If the `"serial2-tokio"` feature is enabled, the `SerialPort` generic type argument defaults to [`crate::TokioSerialPort`].
+		/// If it is not enabled, the `SerialPort` argument must always be specified.
+		///
+		/// The `Buffer` generic type argument defaults to `Vec<u8>` if the `"alloc"` feature is enabled,
+		/// and to `&'static mut [u8]` otherwise.
+		/// See the [`crate::static_buffer!()`] macro for a way to safely create a mutable static buffer.
+		pub struct AsyncClient<SerialPort $(= $DefaultSerialPort)?, Buffer = crate::bus::DefaultBuffer>
+		where
+			SerialPort: crate::AsyncSerialPort,
+			Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		{
+			bus: Bus<SerialPort, Buffer>,
+			status_return_levels: StatusReturnLevels,
+		}
+	};
+}
+
+#[cfg(feature = "serial2-tokio")]
+make_async_client_struct!(crate::TokioSerialPort);
+
+#[cfg(not(feature = "serial2-tokio"))]
+make_async_client_struct!();
+
+impl<SerialPort, Buffer> core::fmt::Debug for AsyncClient<SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("AsyncClient")
+			.field("serial_port", &self.bus.serial_port)
+			.field("baud_rate", &self.bus.baud_rate)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(feature = "serial2-tokio")]
+impl AsyncClient<crate::TokioSerialPort, Vec<u8>> {
+	/// Open a serial port with the given baud rate.
+	///
+	/// This must be called from within a tokio runtime.
+	///
+	/// This will allocate a new read and write buffer of 128 bytes each.
+	/// Use [`Self::open_with_buffers()`] if you want to use a custom buffers.
+	pub fn open(path: impl AsRef<Path>, baud_rate: u32) -> std::io::Result<Self> {
+		let serial_port = crate::TokioSerialPort::open(path, baud_rate)?;
+		let bus = Bus::with_buffers_and_baud_rate(
+			serial_port,
+			vec![0; 128],
+			vec![0; 128],
+			baud_rate
+		);
+		Ok(Self { bus, status_return_levels: StatusReturnLevels::default() })
+	}
+}
+
+#[cfg(feature = "serial2-tokio")]
+impl<Buffer> AsyncClient<crate::TokioSerialPort, Buffer>
+where
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Open a serial port with the given baud rate.
+	///
+	/// This must be called from within a tokio runtime.
+	pub fn open_with_buffers(
+		path: impl AsRef<Path>,
+		baud_rate: u32,
+		read_buffer: Buffer,
+		write_buffer: Buffer,
+	) -> std::io::Result<Self> {
+		let serial_port = crate::TokioSerialPort::open(path, baud_rate)?;
+		let bus = Bus::with_buffers_and_baud_rate(
+			serial_port,
+			read_buffer,
+			write_buffer,
+			baud_rate,
+		);
+		Ok(Self { bus, status_return_levels: StatusReturnLevels::default() })
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<SerialPort> AsyncClient<SerialPort, alloc::vec::Vec<u8>>
+where
+	SerialPort: crate::AsyncSerialPort,
+{
+	/// Create a new client using an open serial port.
+	///
+	/// The serial port must already be configured in raw mode with the correct baud rate,
+	/// character size (8), parity (disabled) and stop bits (1).
+	///
+	/// This will allocate a new read and write buffer of 128 bytes each.
+	/// Use [`Self::with_buffers()`] if you want to use a custom buffers.
+	pub fn new(serial_port: SerialPort) -> Result<Self, SerialPort::Error> {
+		let bus = Bus::with_buffers_async(
+			serial_port,
+			alloc::vec![0; 128],
+			alloc::vec![0; 128],
+		)?;
+		Ok(Self { bus, status_return_levels: StatusReturnLevels::default() })
+	}
+}
+
+impl<SerialPort, Buffer> AsyncClient<SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Create a new client using pre-allocated buffers.
+	///
+	/// The serial port must already be configured in raw mode with the correct baud rate,
+	/// character size (8), parity (disabled) and stop bits (1).
+	pub fn with_buffers(
+		serial_port: SerialPort,
+		read_buffer: Buffer,
+		write_buffer: Buffer,
+	) -> Result<Self, SerialPort::Error> {
+		let bus = Bus::with_buffers_async(
+			serial_port,
+			read_buffer,
+			write_buffer,
+		)?;
+		Ok(Self { bus, status_return_levels: StatusReturnLevels::default() })
+	}
+
+	/// Get a reference to the underlying serial port.
+	///
+	/// Note that performing any read or write to the serial port bypasses the read/write buffer of the bus,
+	/// and may disrupt the communication with the motors.
+	/// In general, it should be safe to read and write to the bus manually in between instructions,
+	/// if the response from the motors has already been received.
+	pub fn serial_port(&self) -> &SerialPort {
+		&self.bus.serial_port
+	}
+
+	/// Consume the client to get ownership of the serial port.
+	///
+	/// This discards any data in internal the read buffer of the client.
+	/// This is normally not a problem, since all data in the read buffer is also discarded when transmitting a new command.
+	pub fn into_serial_port(self) -> SerialPort {
+		self.bus.serial_port
+	}
+
+	/// Get the baud rate of the bus.
+	pub fn baud_rate(&self) -> u32 {
+		self.bus.baud_rate
+	}
+
+	/// Get the communication statistics of the client.
+	///
+	/// See [`Stats`][crate::Stats] for the available counters.
+	/// The latency histogram is not used by the async client, since [`AsyncSerialPort`][crate::AsyncSerialPort] can not measure time.
+	pub fn stats(&self) -> &crate::Stats {
+		&self.bus.stats
+	}
+
+	/// Reset all communication statistics of the client to zero.
+	pub fn reset_stats(&mut self) {
+		self.bus.stats = crate::Stats::new();
+	}
+
+	/// Record the status return level of a motor.
+	///
+	/// See [`crate::Client::set_status_return_level()`].
+	pub fn set_status_return_level(&mut self, motor_id: u8, level: StatusReturnLevel) {
+		self.status_return_levels.set(motor_id, level);
+	}
+
+	/// Get the recorded status return level of a motor.
+	///
+	/// See [`crate::Client::set_status_return_level()`] for more details.
+	pub fn status_return_level(&self, motor_id: u8) -> StatusReturnLevel {
+		self.status_return_levels.get(motor_id)
+	}
+
+	/// Set the baud rate of the underlying serial port.
+	pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), SerialPort::Error> {
+		self.bus.set_baud_rate_async(baud_rate)
+	}
+
+	/// Write a raw instruction to a stream, and read a single raw response.
+	///
+	/// This function also checks that the packet ID of the status response matches the one from the instruction.
+	///
+	/// This is not suitable for broadcast instructions.
+	/// For broadcast instructions, each motor sends an individual response or no response is send at all.
+	/// Instead, use [`Self::write_instruction`] and [`Self::read_status_response`].
+	pub async fn transfer_single<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		expected_response_parameters: u16,
+		encode_parameters: F,
+	) -> Result<StatusPacket<'_>, TransferError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		self.write_instruction(packet_id, instruction_id, parameter_count, encode_parameters).await?;
+		let response = self.read_status_response(expected_response_parameters).await?;
+		crate::error::InvalidPacketId::check(response.packet_id(), packet_id).map_err(crate::ReadError::from)?;
+		Ok(response)
+	}
+
+	/// Write an instruction message to the bus.
+	pub async fn write_instruction<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		self.bus
+			.write_packet_async(packet_id, instruction_id, parameter_count, encode_parameters)
+			.await
+	}
+
+	/// Read a raw status response from the bus with the given deadline.
+	pub async fn read_status_response_timeout(
+		&mut self,
+		timeout: Duration,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>>
+	{
+		self.read_status_response_expected(ExpectedPacket::unknown_size(timeout)).await
+	}
+
+	/// Read a raw status response from the bus, waiting for the given expected packet.
+	async fn read_status_response_expected(
+		&mut self,
+		expected: ExpectedPacket,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>>
+	{
+		let status = self.read_status_response_timeout_unchecked(expected).await?;
+		crate::MotorError::check(status.error())?;
+		Ok(status)
+	}
+
+	/// Read a raw status response from the bus with the given deadline, without checking the error field.
+	///
+	/// Used for responses that combine the replies of multiple motors,
+	/// where the error field only belongs to the first motor.
+	pub(crate) async fn read_status_response_timeout_unchecked(
+		&mut self,
+		expected: ExpectedPacket,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>>
+	{
+		let deadline = self.bus.serial_port.make_deadline(expected.timeout);
+		let packet = self.bus.read_packet_deadline_async(deadline, expected).await?;
+		Ok(packet.try_as_status()?)
+	}
+
+	/// Read a raw status response with an automatically calculated timeout.
+	///
+	/// The read timeout is determined by the expected number of response parameters and the baud rate of the bus.
+	pub async fn read_status_response(
+		&mut self,
+		expected_parameters: u16,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>> {
+		let timeout = crate::bus::status_response_timeout(expected_parameters.into(), self.bus.baud_rate);
+		self.read_status_response_expected(ExpectedPacket::status(expected_parameters.into(), timeout)).await
+	}
+
+	/// Poll for the next status response of an instruction with multiple responses.
+	///
+	/// The deadline is created with the timeout of the expected packet on the first poll,
+	/// and it is cleared again when the poll completes.
+	pub(crate) fn poll_status_response(
+		&mut self,
+		cx: &mut Context<'_>,
+		deadline: &mut Option<SerialPort::Instant>,
+		expected: ExpectedPacket,
+	) -> Poll<Result<StatusPacket<'_>, ReadError<SerialPort::Error>>> {
+		let serial_port = &self.bus.serial_port;
+		let current_deadline = *deadline.get_or_insert_with(|| serial_port.make_deadline(expected.timeout));
+		let stuffed_message_len = ready!(self.bus.poll_packet_len(cx, &current_deadline, &expected));
+		*deadline = None;
+		let status = self.bus.take_packet(stuffed_message_len?)?.try_as_status()?;
+		crate::MotorError::check(status.error())?;
+		Poll::Ready(Ok(status))
+	}
+}
+
+/// Read an empty response from the bus if the motor is expected to reply to the instruction.
+///
+/// If the motor ID is the broadcast ID, or if the motor does not reply at its recorded status return level,
+/// return a fake response from the motor ID without waiting.
+async fn read_response_if_expected<SerialPort, Buffer>(
+	client: &mut AsyncClient<SerialPort, Buffer>,
+	motor_id: u8,
+	instruction_id: u8,
+) -> Result<crate::Response<()>, ReadError<SerialPort::Error>>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	if client.status_return_levels.expects_reply(motor_id, instruction_id) {
+		Ok(client.read_status_response(0).await?.try_into()?)
+	} else {
+		Ok(crate::Response {
+			motor_id,
+			alert: false,
+			data: (),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instructions::{instruction_id, packet_id, BulkReadData, Ping};
+	use crate::serial_port::reply::ReplySerial;
+	use crate::Response;
+	use assert2::{assert, let_assert};
+	use core::pin::Pin;
+	use futures_core::Stream;
+
+	/// Build a status packet without byte stuffing.
+	fn status_packet(motor_id: u8, error: u8, parameters: &[u8]) -> Vec<u8> {
+		let mut packet = vec![0xFF, 0xFF, 0xFD, 0x00, motor_id];
+		packet.extend_from_slice(&(parameters.len() as u16 + 4).to_le_bytes());
+		packet.push(instruction_id::STATUS);
+		packet.push(error);
+		packet.extend_from_slice(parameters);
+		let checksum = crate::checksum::calculate_checksum(0, &packet);
+		packet.extend_from_slice(&checksum.to_le_bytes());
+		packet
+	}
+
+	fn make_client(response: Vec<u8>) -> AsyncClient<ReplySerial> {
+		let_assert!(Ok(client) = AsyncClient::new(ReplySerial { written: Vec::new(), response }));
+		client
+	}
+
+	/// Get the next item of a stream using only the [`Stream`] trait.
+	async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+		core::future::poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)).await
+	}
+
+	#[tokio::test]
+	async fn ping() {
+		let mut client = make_client(status_packet(1, 0, &[0xFC, 0x03, 45]));
+		let_assert!(Ok(response) = client.ping(1).await);
+		assert!(response == Response { motor_id: 1, alert: false, data: Ping { model: 1020, firmware: 45 } });
+	}
+
+	#[tokio::test]
+	async fn read_matches_blocking_client() {
+		let response = status_packet(1, 0, &2000u32.to_le_bytes());
+		let mut client = make_client(response.clone());
+		let_assert!(Ok(response) = client.read::<u32>(1, 132).await);
+		assert!(response == Response { motor_id: 1, alert: false, data: 2000 });
+
+		// The instruction must be encoded exactly like the blocking client does.
+		let_assert!(Ok(mut blocking) = crate::Client::new(ReplySerial { written: Vec::new(), response: Vec::new() }));
+		assert!(let Err(_) = blocking.read::<u32>(1, 132));
+		assert!(client.serial_port().written == blocking.serial_port().written);
+	}
+
+	#[tokio::test]
+	async fn broadcast_write_does_not_wait_for_response() {
+		let mut client = make_client(Vec::new());
+		let_assert!(Ok(response) = client.write(packet_id::BROADCAST, 65, &1u8).await);
+		assert!(response.motor_id == packet_id::BROADCAST);
+		let written = &client.serial_port().written;
+		assert!(written[7] == instruction_id::WRITE);
+		assert!(written[8..11] == [65, 0, 1]);
+	}
+
+	#[tokio::test]
+	async fn status_return_level_skips_reply() {
+		let mut client = make_client(Vec::new());
+		client.set_status_return_level(1, StatusReturnLevel::ReadOnly);
+		assert!(client.status_return_level(1) == StatusReturnLevel::ReadOnly);
+		assert!(client.status_return_level(2) == StatusReturnLevel::All);
+
+		let_assert!(Ok(response) = client.write(1, 64, &1u8).await);
+		assert!(response.motor_id == 1);
+		let_assert!(Ok(response) = client.reboot(1).await);
+		assert!(response.motor_id == 1);
+		assert!(client.stats().timeouts == 0);
+
+		let_assert!(Err(e) = client.write(2, 64, &1u8).await);
+		assert!(e.is_timeout());
+
+		client.set_status_return_level(packet_id::BROADCAST, StatusReturnLevel::PingOnly);
+		assert!(client.status_return_level(2) == StatusReturnLevel::PingOnly);
+		let_assert!(Ok(_) = client.reg_write(2, 64, &1u8).await);
+		let_assert!(Err(e) = client.ping(2).await);
+		assert!(e.is_timeout());
+
+		client.set_status_return_level(packet_id::BROADCAST, StatusReturnLevel::All);
+		assert!(client.status_return_level(1) == StatusReturnLevel::All);
+	}
+
+	#[tokio::test]
+	async fn sync_read_is_a_stream() {
+		let mut client = make_client([
+			status_packet(1, 0, &1000u32.to_le_bytes()),
+			status_packet(2, 0x80, &2000u32.to_le_bytes()),
+		].concat());
+		let_assert!(Ok(mut responses) = client.sync_read::<u32>(&[1, 2], 132).await);
+		assert!(responses.size_hint() == (0, Some(2)));
+		let_assert!(Some(Ok(first)) = next(&mut responses).await);
+		let_assert!(Some(Ok(second)) = next(&mut responses).await);
+		assert!(let None = next(&mut responses).await);
+		assert!(first == Response { motor_id: 1, alert: false, data: 1000 });
+		assert!(second == Response { motor_id: 2, alert: true, data: 2000 });
+	}
+
+	#[tokio::test]
+	async fn sync_read_reports_timeout_for_missing_response() {
+		let mut client = make_client(status_packet(1, 0, &[7, 0]));
+		let_assert!(Ok(mut responses) = client.sync_read::<u16>(&[1, 2], 132).await);
+		let_assert!(Some(Ok(first)) = responses.read_next().await);
+		assert!(first.data == 7);
+		let_assert!(Some(Err(ReadError::Timeout { motor_id: Some(2), expected_bytes, .. })) = responses.read_next().await);
+		assert!(expected_bytes == StatusPacket::message_len(2));
+		assert!(let None = responses.read_next().await);
+	}
+
+	#[tokio::test]
+	async fn bulk_read_bytes_is_a_stream() {
+		let mut client = make_client([
+			status_packet(1, 0, &[1, 2, 3, 4]),
+			status_packet(5, 0, &[5]),
+		].concat());
+		let reads = [
+			BulkReadData { motor_id: 1, address: 132, count: 4 },
+			BulkReadData { motor_id: 5, address: 146, count: 1 },
+		];
+		let_assert!(Ok(mut responses) = client.bulk_read_bytes::<Vec<u8>>(&reads).await);
+		let_assert!(Some(Ok(first)) = next(&mut responses).await);
+		let_assert!(Some(Ok(second)) = next(&mut responses).await);
+		assert!(let None = next(&mut responses).await);
+		assert!(first == Response { motor_id: 1, alert: false, data: vec![1, 2, 3, 4] });
+		assert!(second == Response { motor_id: 5, alert: false, data: vec![5] });
+	}
+
+	#[tokio::test]
+	async fn bulk_read_bytes_borrow() {
+		let mut client = make_client(status_packet(1, 0, &[1, 2]));
+		let reads = [BulkReadData { motor_id: 1, address: 132, count: 2 }];
+		let_assert!(Ok(mut responses) = client.bulk_read_bytes_borrow::<[u8]>(&reads).await);
+		let_assert!(Some(Ok(response)) = responses.read_next_borrow().await);
+		assert!(response.data == [1, 2]);
+	}
+
+	#[tokio::test]
+	async fn scan_ends_on_timeout() {
+		let mut client = make_client([
+			status_packet(1, 0, &[0xFC, 0x03, 45]),
+			status_packet(3, 0, &[0xFC, 0x03, 46]),
+		].concat());
+		let_assert!(Ok(mut scan) = client.scan().await);
+		let_assert!(Some(Ok(first)) = next(&mut scan).await);
+		let_assert!(Some(Ok(second)) = next(&mut scan).await);
+		assert!(let None = next(&mut scan).await);
+		assert!(let None = next(&mut scan).await);
+		assert!(first.motor_id == 1);
+		assert!(second.data == Ping { model: 1020, firmware: 46 });
+	}
+
+	/// Ensure that the futures of the client can be spawned on a multi-threaded runtime.
+	///
+	/// This is a compile test. It only tests that the test code compiles.
+	#[cfg(feature = "serial2-tokio")]
+	#[allow(dead_code)]
+	fn futures_are_send(client: &mut AsyncClient) {
+		fn assert_send<T: Send>(_: T) {}
+		assert_send(client.ping(1));
+		assert_send(client.sync_read::<u32>(&[1, 2], 132));
+	}
+}
@@ -0,0 +1,104 @@
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+use core::time::Duration;
+
+use futures_core::Stream;
+
+use super::AsyncClient;
+use crate::bus::ExpectedPacket;
+use crate::instructions::{instruction_id, packet_id, Ping};
+use crate::{ReadError, Response, WriteError};
+
+impl<SerialPort, Buffer> AsyncClient<SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Scan the bus for motors with a broadcast ping.
+	///
+	/// The returned stream yields the response of each motor,
+	/// and it ends when no more responses arrive before the timeout.
+	pub async fn scan(&mut self) -> Result<Scan<'_, SerialPort, Buffer>, WriteError<SerialPort::Error>> {
+		self.write_instruction(packet_id::BROADCAST, instruction_id::PING, 0, |_| Ok(())).await?;
+		Ok(Scan {
+			client: self,
+			deadline: None,
+			done: false,
+		})
+	}
+}
+
+macro_rules! make_scan_struct {
+	($($DefaultSerialPort:ty)?) => {
+		/// An async scan operation that returns [`Response<Ping>`] when polled as [`Stream`].
+		///
+		/// Dropping the stream does not wait for the remaining responses.
+		pub struct Scan<'a, SerialPort $(= $DefaultSerialPort)?, Buffer = crate::bus::DefaultBuffer>
+		where
+			SerialPort: crate::AsyncSerialPort,
+			Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		{
+			client: &'a mut AsyncClient<SerialPort, Buffer>,
+			deadline: Option<SerialPort::Instant>,
+			done: bool,
+		}
+	}
+}
+
+#[cfg(feature = "serial2-tokio")]
+make_scan_struct!(crate::TokioSerialPort);
+
+#[cfg(not(feature = "serial2-tokio"))]
+make_scan_struct!();
+
+impl<SerialPort, Buffer> core::fmt::Debug for Scan<'_, SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("Scan")
+			.field("serial_port", self.client.serial_port())
+			.field("done", &self.done)
+			.finish()
+	}
+}
+
+impl<SerialPort, Buffer> Scan<'_, SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Scan for the next motor reply.
+	pub async fn scan_next(&mut self) -> Option<Result<Response<Ping>, ReadError<SerialPort::Error>>> {
+		core::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+	}
+}
+
+impl<SerialPort, Buffer> Stream for Scan<'_, SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	type Item = Result<Response<Ping>, ReadError<SerialPort::Error>>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		if this.done {
+			return Poll::Ready(None);
+		}
+
+		let response_time = crate::bus::message_transfer_time(14, this.client.baud_rate());
+		let timeout = response_time * 253 + Duration::from_millis(34);
+		let response = ready!(this.client.poll_status_response(cx, &mut this.deadline, ExpectedPacket::status(3, timeout)));
+		match response {
+			Ok(response) => Poll::Ready(Some(response.try_into().map_err(ReadError::from))),
+			Err(ReadError::Timeout { .. }) => {
+				trace!("Ping response timed out.");
+				this.done = true;
+				Poll::Ready(None)
+			},
+			Err(e) => Poll::Ready(Some(Err(e))),
+		}
+	}
+}
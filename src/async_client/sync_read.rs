@@ -0,0 +1,293 @@
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{ready, Context, Poll};
+
+use futures_core::Stream;
+
+use super::AsyncClient;
+use crate::bus::data::{decode_status_packet, decode_status_packet_bytes, decode_status_packet_bytes_borrow, Data};
+use crate::bus::ExpectedPacket;
+use crate::instructions::sync_read::encode_sync_read_parameters;
+use crate::instructions::{instruction_id, packet_id};
+use crate::{ReadError, Response, WriteError};
+
+impl<SerialPort, Buffer> AsyncClient<SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Synchronously read a number of bytes from multiple motors in one command.
+	///
+	/// The returned stream yields the response of each motor.
+	pub async fn sync_read_bytes<'a, T>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+		count: u16,
+	) -> Result<SyncReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	where
+		T: for<'b> From<&'b [u8]>,
+	{
+		self.write_sync_read_instruction(motor_ids, address, count).await?;
+		Ok(SyncReadBytes::new(self, motor_ids, count))
+	}
+
+	/// Synchronously read a number of bytes from multiple motors in one command.
+	///
+	/// Use [`SyncReadBytes::read_next_borrow()`] to get the responses, borrowing the data from the internal read buffer.
+	pub async fn sync_read_bytes_borrow<'a, T>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+		count: u16,
+	) -> Result<SyncReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	where
+		T: ?Sized,
+		[u8]: core::borrow::Borrow<T>,
+	{
+		self.write_sync_read_instruction(motor_ids, address, count).await?;
+		Ok(SyncReadBytes::new(self, motor_ids, count))
+	}
+
+	/// Synchronously read values from multiple motors in one command.
+	///
+	/// The returned stream yields the response of each motor.
+	pub async fn sync_read<'a, T: Data>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+	) -> Result<SyncRead<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>> {
+		self.write_sync_read_instruction(motor_ids, address, T::ENCODED_SIZE).await?;
+		Ok(SyncRead {
+			client: self,
+			motor_ids,
+			index: 0,
+			deadline: None,
+			data: PhantomData,
+		})
+	}
+
+	async fn write_sync_read_instruction(&mut self, motor_ids: &[u8], address: u16, count: u16) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids.len(), |buffer| {
+			encode_sync_read_parameters(buffer, motor_ids, address, count)
+		}).await
+	}
+}
+
+macro_rules! make_sync_read_bytes_struct {
+	($($DefaultSerialPort:ty)?) => {
+		/// An async sync read operation that returns unparsed bytes.
+		///
+		/// Dropping the stream does not wait for the remaining responses.
+		pub struct SyncReadBytes<'a, T, SerialPort $(= $DefaultSerialPort)?, Buffer = crate::bus::DefaultBuffer>
+		where
+			T: ?Sized,
+			SerialPort: crate::AsyncSerialPort,
+			Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		{
+			client: &'a mut AsyncClient<SerialPort, Buffer>,
+			count: u16,
+			motor_ids: &'a [u8],
+			index: usize,
+			deadline: Option<SerialPort::Instant>,
+			data: PhantomData<fn() -> T>,
+		}
+	}
+}
+
+#[cfg(feature = "serial2-tokio")]
+make_sync_read_bytes_struct!(crate::TokioSerialPort);
+
+#[cfg(not(feature = "serial2-tokio"))]
+make_sync_read_bytes_struct!();
+
+impl<T, SerialPort, Buffer> core::fmt::Debug for SyncReadBytes<'_, T, SerialPort, Buffer>
+where
+	T: ?Sized,
+	SerialPort: crate::AsyncSerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("SyncReadBytes")
+			.field("serial_port", self.client.serial_port())
+			.field("motor_ids", &self.motor_ids)
+			.field("count", &self.count)
+			.field("index", &self.index)
+			.field("data", &format_args!("{}", core::any::type_name::<T>()))
+			.finish()
+	}
+}
+
+impl<'a, T, SerialPort, Buffer> SyncReadBytes<'a, T, SerialPort, Buffer>
+where
+	T: ?Sized,
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn new(client: &'a mut AsyncClient<SerialPort, Buffer>, motor_ids: &'a [u8], count: u16) -> Self {
+		Self {
+			client,
+			count,
+			motor_ids,
+			index: 0,
+			deadline: None,
+			data: PhantomData,
+		}
+	}
+
+	/// Get the number of responses that should still be received.
+	pub fn remaining(&self) -> usize {
+		self.motor_ids.len() - self.index
+	}
+
+	/// Read the next motor reply.
+	pub async fn read_next(&mut self) -> Option<Result<Response<T>, ReadError<SerialPort::Error>>>
+	where
+		T: for<'b> From<&'b [u8]> + Sized,
+	{
+		core::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+	}
+
+	/// Read the next motor reply, borrowing the data from the internal read buffer.
+	pub async fn read_next_borrow(&mut self) -> Option<Result<Response<&T>, ReadError<SerialPort::Error>>>
+	where
+		[u8]: core::borrow::Borrow<T>,
+	{
+		let motor_id = *self.motor_ids.get(self.index)?;
+		self.index += 1;
+		self.deadline = None;
+		Some(self.next_response_borrow(motor_id).await)
+	}
+
+	async fn next_response_borrow(&mut self, motor_id: u8) -> Result<Response<&T>, ReadError<SerialPort::Error>>
+	where
+		[u8]: core::borrow::Borrow<T>,
+	{
+		let response = self.client.read_status_response(self.count).await?;
+		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
+		crate::InvalidParameterCount::check(response.parameters().len(), self.count.into())?;
+		Ok(decode_status_packet_bytes_borrow(response)?)
+	}
+}
+
+impl<T, SerialPort, Buffer> Stream for SyncReadBytes<'_, T, SerialPort, Buffer>
+where
+	T: for<'b> From<&'b [u8]>,
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	type Item = Result<Response<T>, ReadError<SerialPort::Error>>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		let Some(&motor_id) = this.motor_ids.get(this.index) else {
+			return Poll::Ready(None);
+		};
+		let timeout = crate::bus::status_response_timeout(this.count.into(), this.client.baud_rate());
+		this.client.bus.response_motor_id = Some(motor_id);
+		let response = ready!(this.client.poll_status_response(cx, &mut this.deadline, ExpectedPacket::status(this.count.into(), timeout)));
+		this.index += 1;
+		Poll::Ready(Some(response.and_then(|response| {
+			// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
+			// We need to report a timeout or something for the missed motor though.
+			crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
+			crate::InvalidParameterCount::check(response.parameters().len(), this.count.into())?;
+			Ok(decode_status_packet_bytes(response)?)
+		})))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.remaining()))
+	}
+}
+
+macro_rules! make_sync_read_struct {
+	($($DefaultSerialPort:ty)?) => {
+		/// An async sync read operation that returns parsed values.
+		///
+		/// Dropping the stream does not wait for the remaining responses.
+		pub struct SyncRead<'a, T, SerialPort $(= $DefaultSerialPort)?, Buffer = crate::bus::DefaultBuffer>
+		where
+			T: Data,
+			SerialPort: crate::AsyncSerialPort,
+			Buffer: AsRef<[u8]> + AsMut<[u8]>,
+		{
+			client: &'a mut AsyncClient<SerialPort, Buffer>,
+			motor_ids: &'a [u8],
+			index: usize,
+			deadline: Option<SerialPort::Instant>,
+			data: PhantomData<fn() -> T>,
+		}
+	}
+}
+
+#[cfg(feature = "serial2-tokio")]
+make_sync_read_struct!(crate::TokioSerialPort);
+
+#[cfg(not(feature = "serial2-tokio"))]
+make_sync_read_struct!();
+
+impl<T, SerialPort, Buffer> core::fmt::Debug for SyncRead<'_, T, SerialPort, Buffer>
+where
+	T: Data,
+	SerialPort: crate::AsyncSerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("SyncRead")
+			.field("serial_port", self.client.serial_port())
+			.field("motor_ids", &self.motor_ids)
+			.field("count", &T::ENCODED_SIZE)
+			.field("index", &self.index)
+			.field("data", &format_args!("{}", core::any::type_name::<T>()))
+			.finish()
+	}
+}
+
+impl<T, SerialPort, Buffer> SyncRead<'_, T, SerialPort, Buffer>
+where
+	T: Data,
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Get the number of responses that should still be received.
+	pub fn remaining(&self) -> usize {
+		self.motor_ids.len() - self.index
+	}
+
+	/// Read the next motor reply.
+	pub async fn read_next(&mut self) -> Option<Result<Response<T>, ReadError<SerialPort::Error>>> {
+		core::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx)).await
+	}
+}
+
+impl<T, SerialPort, Buffer> Stream for SyncRead<'_, T, SerialPort, Buffer>
+where
+	T: Data,
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	type Item = Result<Response<T>, ReadError<SerialPort::Error>>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		let Some(&motor_id) = this.motor_ids.get(this.index) else {
+			return Poll::Ready(None);
+		};
+		let timeout = crate::bus::status_response_timeout(T::ENCODED_SIZE.into(), this.client.baud_rate());
+		this.client.bus.response_motor_id = Some(motor_id);
+		let response = ready!(this.client.poll_status_response(cx, &mut this.deadline, ExpectedPacket::status(T::ENCODED_SIZE.into(), timeout)));
+		this.index += 1;
+		Poll::Ready(Some(response.and_then(|response| {
+			// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
+			// We need to report a timeout or something for the missed motor though.
+			crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
+			crate::InvalidParameterCount::check(response.parameters().len(), T::ENCODED_SIZE.into())?;
+			decode_status_packet(response)
+		})))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.remaining()))
+	}
+}
@@ -0,0 +1,255 @@
+//! Async [`Device`][crate::Device] for use with Embassy.
+
+use crate::bus::{Bus, ExpectedPacket};
+use crate::serial_port::embassy::{EmbassySerialPort, EmbassySerialPortError};
+use crate::serial_port::embedded_io::NoDirectionPin;
+use crate::{Instruction, ReadError, WriteError};
+use core::time::Duration;
+use embassy_time::Instant;
+use embedded_hal::digital::OutputPin;
+
+/// Async Dynamixel device for implementing the device side of the DYNAMIXEL Protocol 2.0.
+///
+/// This works like [`Device`][crate::Device], but it uses an [`EmbassySerialPort`] for the communication.
+/// While waiting for an instruction, other tasks on the same executor can run.
+///
+/// The `Buffer` generic type argument defaults to `Vec<u8>` if the `"alloc"` feature is enabled,
+/// and to `&'static mut [u8]` otherwise.
+/// See the [`crate::static_buffer!()`] macro for a way to safely create a mutable static buffer.
+///
+/// This struct is only available if the `embassy` feature is enabled.
+pub struct AsyncDevice<Io, DirectionPin = NoDirectionPin, Buffer = crate::bus::DefaultBuffer>
+where
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	bus: Bus<EmbassySerialPort<Io, DirectionPin>, Buffer>,
+}
+
+impl<Io, DirectionPin, Buffer> core::fmt::Debug for AsyncDevice<Io, DirectionPin, Buffer>
+where
+	Io: core::fmt::Debug,
+	DirectionPin: core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("AsyncDevice")
+			.field("serial_port", &self.bus.serial_port)
+			.field("baud_rate", &self.bus.baud_rate)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl<Io, DirectionPin> AsyncDevice<Io, DirectionPin, alloc::vec::Vec<u8>>
+where
+	Io: embedded_io_async::Read + embedded_io_async::Write,
+	DirectionPin: OutputPin,
+{
+	/// Create a new device for a serial port.
+	///
+	/// This will allocate a new read and write buffer of 128 bytes each.
+	/// Use [`Self::with_buffers()`] if you want to use a custom buffers.
+	pub fn new(serial_port: EmbassySerialPort<Io, DirectionPin>) -> Self {
+		Self::with_buffers(serial_port, alloc::vec![0; 128], alloc::vec![0; 128])
+	}
+}
+
+impl<Io, DirectionPin, Buffer> AsyncDevice<Io, DirectionPin, Buffer>
+where
+	Io: embedded_io_async::Read + embedded_io_async::Write,
+	DirectionPin: OutputPin,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Create a new device using pre-allocated buffers.
+	pub fn with_buffers(serial_port: EmbassySerialPort<Io, DirectionPin>, read_buffer: Buffer, write_buffer: Buffer) -> Self {
+		let baud_rate = serial_port.baud_rate();
+		let bus = Bus::with_buffers_and_baud_rate(serial_port, read_buffer, write_buffer, baud_rate);
+		Self { bus }
+	}
+
+	/// Get a reference to the underlying serial port.
+	///
+	/// Note that performing any read or write to the serial port bypasses the read/write buffer of the device,
+	/// and may disrupt the communication with the motors.
+	pub fn serial_port(&self) -> &EmbassySerialPort<Io, DirectionPin> {
+		&self.bus.serial_port
+	}
+
+	/// Consume this device object to get ownership of the serial port.
+	///
+	/// This discards any data in internal the read buffer of the device object.
+	pub fn into_serial_port(self) -> EmbassySerialPort<Io, DirectionPin> {
+		self.bus.serial_port
+	}
+
+	/// Get the baud rate of the device.
+	pub fn baud_rate(&self) -> u32 {
+		self.bus.baud_rate
+	}
+
+	/// Get the communication statistics of the device.
+	///
+	/// See [`Stats`][crate::Stats] for the available counters.
+	/// The latency histogram is only used by clients, and is always empty for a device.
+	pub fn stats(&self) -> &crate::Stats {
+		&self.bus.stats
+	}
+
+	/// Reset all communication statistics of the device to zero.
+	pub fn reset_stats(&mut self) {
+		self.bus.stats = crate::Stats::new();
+	}
+
+	/// Read a single [`Instruction`] with borrowed data.
+	///
+	/// Use [`AsyncDevice::read_owned`] to received owned data.
+	pub async fn read(&mut self, timeout: Duration) -> Result<Instruction<&[u8]>, ReadError<EmbassySerialPortError<Io, DirectionPin>>> {
+		let deadline = self.serial_port().make_deadline(timeout);
+		let packet = self.read_raw_instruction(deadline, timeout).await?;
+		let packet = packet.try_into()?;
+		Ok(packet)
+	}
+
+	/// Read a single [`Instruction`] with borrowed data, waiting until the given deadline.
+	///
+	/// If the deadline expires, the reported [`ReadError::Timeout`] has a `waited` time of zero,
+	/// since the time of the call is not known.
+	pub async fn read_deadline(&mut self, deadline: Instant) -> Result<Instruction<&[u8]>, ReadError<EmbassySerialPortError<Io, DirectionPin>>> {
+		let packet = self.read_raw_instruction_deadline(deadline).await?;
+		let packet = packet.try_into()?;
+		Ok(packet)
+	}
+
+	/// Read a single [`Instruction`] with owned data.
+	#[cfg(feature = "alloc")]
+	pub async fn read_owned(
+		&mut self,
+		timeout: Duration,
+	) -> Result<Instruction<alloc::vec::Vec<u8>>, ReadError<EmbassySerialPortError<Io, DirectionPin>>> {
+		let deadline = self.serial_port().make_deadline(timeout);
+		let packet = self.read_raw_instruction(deadline, timeout).await?;
+		let packet = packet.try_into()?;
+		Ok(packet)
+	}
+
+	/// Write a status message to the device.
+	pub async fn write_status<F>(
+		&mut self,
+		packet_id: u8,
+		error: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<EmbassySerialPortError<Io, DirectionPin>>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		self.bus.write_status_embassy(packet_id, error, parameter_count, encode_parameters).await
+	}
+
+	/// Write an empty status message with an error code.
+	pub async fn write_status_error(&mut self, packet_id: u8, error: u8) -> Result<(), WriteError<EmbassySerialPortError<Io, DirectionPin>>> {
+		self.write_status(packet_id, error, 0, |_| Ok(())).await
+	}
+
+	/// Write an empty status message.
+	pub async fn write_status_ok(&mut self, packet_id: u8) -> Result<(), WriteError<EmbassySerialPortError<Io, DirectionPin>>> {
+		self.write_status(packet_id, 0, 0, |_| Ok(())).await
+	}
+
+	/// Read a single [`InstructionPacket`][crate::bus::InstructionPacket].
+	pub async fn read_raw_instruction_deadline(
+		&mut self,
+		deadline: Instant,
+	) -> Result<crate::bus::InstructionPacket<'_>, ReadError<EmbassySerialPortError<Io, DirectionPin>>> {
+		self.read_raw_instruction(deadline, Duration::ZERO).await
+	}
+
+	/// Read a single [`InstructionPacket`][crate::bus::InstructionPacket], reporting the given timeout if the deadline expires.
+	async fn read_raw_instruction(
+		&mut self,
+		deadline: Instant,
+		timeout: Duration,
+	) -> Result<crate::bus::InstructionPacket<'_>, ReadError<EmbassySerialPortError<Io, DirectionPin>>> {
+		loop {
+			// SAFETY: This is a workaround for a limitation in the borrow checker.
+			// See `Device::read_raw_instruction_timeout()` for details.
+			// TODO: Remove this workaround when the borrow checker can validate this.
+			let bus: &mut Bus<EmbassySerialPort<Io, DirectionPin>, Buffer> = unsafe { &mut *(&mut self.bus as *mut _) };
+			let packet = bus.read_packet_deadline_embassy(deadline, ExpectedPacket::unknown_size(timeout)).await?;
+			if let Some(instruction) = packet.as_instruction() {
+				return Ok(instruction);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::Instructions;
+	use assert2::{assert, let_assert};
+	use core::convert::Infallible;
+
+	/// A serial port with pre-loaded read data that records written data.
+	///
+	/// Reading waits forever when no data is left.
+	#[derive(Default)]
+	struct FakeIo {
+		read_data: Vec<u8>,
+		written: Vec<u8>,
+	}
+
+	impl embedded_io_async::ErrorType for FakeIo {
+		type Error = Infallible;
+	}
+
+	impl embedded_io_async::Read for FakeIo {
+		async fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+			if self.read_data.is_empty() {
+				core::future::pending::<()>().await;
+			}
+			let count = buffer.len().min(self.read_data.len());
+			buffer[..count].copy_from_slice(&self.read_data[..count]);
+			self.read_data.drain(..count);
+			Ok(count)
+		}
+	}
+
+	impl embedded_io_async::Write for FakeIo {
+		async fn write(&mut self, buffer: &[u8]) -> Result<usize, Self::Error> {
+			self.written.extend_from_slice(buffer);
+			Ok(buffer.len())
+		}
+	}
+
+	fn make_device(read_data: &[u8]) -> AsyncDevice<FakeIo, NoDirectionPin, Vec<u8>> {
+		let io = FakeIo {
+			read_data: read_data.to_vec(),
+			..Default::default()
+		};
+		AsyncDevice::new(EmbassySerialPort::new(io, 57600))
+	}
+
+	#[tokio::test]
+	async fn read_ping_and_reply() {
+		// Ping instruction for motor 1 from the protocol documentation.
+		let mut device = make_device(&[0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x03, 0x00, 0x01, 0x19, 0x4E]);
+		let_assert!(Ok(instruction) = device.read(Duration::from_millis(100)).await);
+		assert!(instruction.id == 1);
+		assert!(let Instructions::Ping = instruction.instruction);
+
+		let_assert!(Ok(()) = device.write_status(1, 0, 3, |buffer| {
+			buffer.copy_from_slice(&[0x06, 0x04, 0x26]);
+			Ok(())
+		}).await);
+		let (io, _pin) = device.into_serial_port().into_parts();
+		assert!(io.written == [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x55, 0x00, 0x06, 0x04, 0x26, 0x65, 0x5D]);
+	}
+
+	#[tokio::test]
+	async fn read_times_out_at_deadline() {
+		let mut device = make_device(&[]);
+		let_assert!(Err(ReadError::Timeout { motor_id: None, waited, .. }) = device.read(Duration::from_millis(10)).await);
+		assert!(waited == Duration::from_millis(10));
+	}
+}
@@ -0,0 +1,109 @@
+//! Async I/O for the low level bus.
+//!
+//! The packets are encoded and decoded by the same functions as for the blocking implementation.
+//! Only the reading from and writing to the serial port is different.
+
+use core::task::{Context, Poll};
+
+use super::{Bus, ExpectedPacket, Packet};
+use crate::{ReadError, WriteError};
+
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Create a new bus using pre-allocated buffers.
+	///
+	/// The serial port must already be configured in raw mode with the correct baud rate,
+	/// character size (8), parity (disabled) and stop bits (1).
+	pub fn with_buffers_async(
+		serial_port: SerialPort,
+		read_buffer: Buffer,
+		write_buffer: Buffer,
+	) -> Result<Self, SerialPort::Error> {
+		let baud_rate = serial_port.baud_rate()?;
+		Ok(Self::with_buffers_and_baud_rate(serial_port, read_buffer, write_buffer, baud_rate))
+	}
+
+	/// Set the baud rate of the underlying serial port.
+	pub fn set_baud_rate_async(&mut self, baud_rate: u32) -> Result<(), SerialPort::Error> {
+		self.serial_port.set_baud_rate(baud_rate)?;
+		self.baud_rate = baud_rate;
+		Ok(())
+	}
+
+	/// Write a packet to the bus.
+	pub async fn write_packet_async<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		let message_len = self.encode_packet(packet_id, instruction_id, parameter_count, encode_parameters)?;
+
+		// Throw away old data in the read buffer and the kernel read buffer.
+		// We don't do this when reading a reply, because we might receive multiple replies for one instruction,
+		// and read() can potentially read more than one reply per syscall.
+		self.clear_read_buffer();
+		self.serial_port.discard_input_buffer().map_err(WriteError::DiscardBuffer)?;
+
+		// Send message.
+		let stuffed_message = &self.write_buffer.as_ref()[..message_len];
+		trace!("sending packet: {:02X?}", stuffed_message);
+		let mut written = 0;
+		core::future::poll_fn(|cx| {
+			while written < stuffed_message.len() {
+				match self.serial_port.poll_write(cx, &stuffed_message[written..]) {
+					Poll::Ready(Ok(n)) => written += n,
+					Poll::Ready(Err(e)) => return Poll::Ready(Err(WriteError::Write(e))),
+					Poll::Pending => return Poll::Pending,
+				}
+			}
+			Poll::Ready(Ok(()))
+		}).await?;
+		self.record_sent(message_len);
+		Ok(())
+	}
+
+	/// Read a raw packet from the bus with the given deadline.
+	///
+	/// If the serial port reports a timeout, a [`ReadError::Timeout`] is returned.
+	pub async fn read_packet_deadline_async(
+		&mut self,
+		deadline: SerialPort::Instant,
+		expected: ExpectedPacket,
+	) -> Result<Packet<'_>, ReadError<SerialPort::Error>> {
+		let stuffed_message_len = core::future::poll_fn(|cx| self.poll_packet_len(cx, &deadline, &expected)).await?;
+		Ok(self.take_packet(stuffed_message_len)?)
+	}
+
+	/// Read data from the serial port until the read buffer holds a complete packet.
+	///
+	/// Returns the length of the packet, which can then be taken with [`Self::take_packet()`].
+	pub(crate) fn poll_packet_len(
+		&mut self,
+		cx: &mut Context<'_>,
+		deadline: &SerialPort::Instant,
+		expected: &ExpectedPacket,
+	) -> Poll<Result<usize, ReadError<SerialPort::Error>>> {
+		loop {
+			if let Some(stuffed_message_len) = self.buffered_packet_len(expected)? {
+				return Poll::Ready(Ok(stuffed_message_len));
+			}
+
+			// Try to read more data into the buffer.
+			let read_buffer = &mut self.read_buffer.as_mut()[self.read_len..];
+			match self.serial_port.poll_read(cx, read_buffer, deadline) {
+				Poll::Ready(Ok(new_data)) => self.record_read(new_data),
+				Poll::Ready(Err(e)) if SerialPort::is_timeout_error(&e) => return Poll::Ready(Err(self.timeout_error(expected))),
+				Poll::Ready(Err(e)) => return Poll::Ready(Err(ReadError::Io(e))),
+				Poll::Pending => return Poll::Pending,
+			}
+		}
+	}
+}
@@ -51,6 +51,55 @@ pub fn stuffing_required(data: &[u8]) -> usize {
 	count
 }
 
+/// Get the number of bytes of stuffed data that hold the first `len` bytes of unstuffed data.
+///
+/// If the unstuffed data ends with the unstuffed pattern, the stuffing byte that follows it is included.
+/// Returns `None` if `data` does not hold `len` bytes of unstuffed data yet.
+pub fn stuffed_len(data: &[u8], len: usize) -> Option<usize> {
+	let mut state = 0;
+	let mut unstuffed = 0;
+	for (i, &byte) in data.iter().enumerate() {
+		if unstuffed == len && (state != PATTERN.len() - 1 || byte != PATTERN[state]) {
+			return Some(i);
+		}
+		if byte == PATTERN[state] {
+			state += 1;
+		} else {
+			state = 0;
+		}
+		if state == PATTERN.len() {
+			state = 0;
+		} else {
+			unstuffed += 1;
+		}
+	}
+
+	(unstuffed == len && state != PATTERN.len() - 1).then_some(data.len())
+}
+
+/// Calculate the checksum of data as it will be sent after byte-stuffing, without modifying the data.
+///
+/// The checksum continues from `checksum`.
+/// The `state` is the number of bytes of the unstuffed pattern at the end of the preceding data.
+/// It is updated for the new data, so that a packet body can be processed in multiple parts.
+pub fn stuffed_checksum(checksum: u16, state: &mut usize, data: &[u8]) -> u16 {
+	let mut checksum = checksum;
+	for &byte in data {
+		checksum = crate::checksum::calculate_checksum(checksum, &[byte]);
+		if byte == PATTERN[*state] {
+			*state += 1;
+		} else {
+			*state = 0;
+		}
+		if *state == PATTERN.len() - 1 {
+			*state = 0;
+			checksum = crate::checksum::calculate_checksum(checksum, &PATTERN[3..]);
+		}
+	}
+
+	checksum
+}
+
 /// Perform byte-stuffing in-place and return the length of the stuffed data.
 ///
 /// The actual length of the unstuffed data must be passed in through the `len` parameter.
@@ -121,6 +170,34 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn test_stuffed_len() {
+		assert!(stuffed_len(&[0, 1, 2], 2) == Some(2));
+		assert!(stuffed_len(&[0, 1, 2], 3) == Some(3));
+		assert!(stuffed_len(&[0, 1, 2], 4) == None);
+		assert!(stuffed_len(&[0xFF, 0xFF, 0xFD, 0xFD, 0x00], 4) == Some(5));
+		// The stuffing byte after the pattern belongs to the data before it.
+		assert!(stuffed_len(&[0xFF, 0xFF, 0xFD, 0xFD, 0x00], 3) == Some(4));
+		assert!(stuffed_len(&[0xFF, 0xFF, 0xFD], 3) == None);
+	}
+
+	#[test]
+	fn test_stuffed_checksum() {
+		use crate::checksum::calculate_checksum;
+		let data = [0x00, 0xFF, 0xFF, 0xFD, 0x01, 0xFF];
+		let stuffed = stuff(data.to_vec()).unwrap();
+
+		let mut state = 0;
+		assert!(stuffed_checksum(0, &mut state, &data) == calculate_checksum(0, &stuffed));
+		assert!(state == 1);
+
+		// The pattern is also stuffed if it is split over multiple parts.
+		let mut state = 0;
+		let checksum = stuffed_checksum(0, &mut state, &data[..3]);
+		assert!(state == 2);
+		assert!(stuffed_checksum(checksum, &mut state, &data[3..]) == calculate_checksum(0, &stuffed));
+	}
+
 	#[test]
 	fn test_stuff() {
 		assert!(stuff(vec![0, 0, 0]).unwrap() == [0, 0, 0]);
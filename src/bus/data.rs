@@ -81,6 +81,138 @@ impl_data_for_number!(i32);
 impl_data_for_number!(i64);
 impl_data_for_number!(i128);
 
+impl Data for bool {
+	const ENCODED_SIZE: u16 = 1;
+
+	fn encode(&self, buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
+		u8::from(*self).encode(buffer)
+	}
+
+	fn decode(buffer: &[u8]) -> Result<Self, crate::error::InvalidMessage> {
+		match u8::decode(buffer)? {
+			0 => Ok(false),
+			1 => Ok(true),
+			value => Err(crate::InvalidValue {
+				actual: value.into(),
+				type_name: "bool",
+			}.into()),
+		}
+	}
+}
+
+macro_rules! impl_data_for_tuple {
+	($($name:ident),*) => {
+		impl<$($name: Data),*> Data for ($($name,)*) {
+			const ENCODED_SIZE: u16 = 0 $(+ $name::ENCODED_SIZE)*;
+
+			#[allow(non_snake_case)]
+			fn encode(&self, buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
+				crate::BufferTooSmallError::check(Self::ENCODED_SIZE.into(), buffer.len())?;
+				let ($($name,)*) = self;
+				let mut offset = 0;
+				$(
+					$name.encode(&mut buffer[offset..][..$name::ENCODED_SIZE.into()])?;
+					offset += usize::from($name::ENCODED_SIZE);
+				)*
+				let _ = offset;
+				Ok(())
+			}
+
+			fn decode(buffer: &[u8]) -> Result<Self, crate::InvalidMessage> {
+				crate::InvalidParameterCount::check(buffer.len(), Self::ENCODED_SIZE.into())?;
+				let mut offset = 0;
+				let value = ($({
+					let value = $name::decode(&buffer[offset..][..$name::ENCODED_SIZE.into()])?;
+					offset += usize::from($name::ENCODED_SIZE);
+					value
+				},)*);
+				let _ = offset;
+				Ok(value)
+			}
+		}
+	};
+}
+
+impl_data_for_tuple!(A);
+impl_data_for_tuple!(A, B);
+impl_data_for_tuple!(A, B, C);
+impl_data_for_tuple!(A, B, C, D);
+impl_data_for_tuple!(A, B, C, D, E);
+impl_data_for_tuple!(A, B, C, D, E, F);
+impl_data_for_tuple!(A, B, C, D, E, F, G);
+impl_data_for_tuple!(A, B, C, D, E, F, G, H);
+impl_data_for_tuple!(A, B, C, D, E, F, G, H, I);
+impl_data_for_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_data_for_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_data_for_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Define a C-like enum that implements [`Data`].
+///
+/// The enum is encoded as its discriminant, using the given integer type.
+/// The integer type must be losslessly convertible to `i64`.
+/// Decoding a value that does not match any variant gives an [`InvalidValue`][crate::InvalidValue] error.
+///
+/// ```
+/// dynamixel2::data_enum! {
+///     /// The operating mode of an X-series motor.
+///     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+///     pub enum OperatingMode: u8 {
+///         Current = 0,
+///         Velocity = 1,
+///         Position = 3,
+///         ExtendedPosition = 4,
+///         CurrentBasedPosition = 5,
+///         Pwm = 16,
+///     }
+/// }
+///
+/// use dynamixel2::bus::Data;
+/// assert_eq!(OperatingMode::decode(&[3]), Ok(OperatingMode::Position));
+/// assert!(OperatingMode::decode(&[2]).is_err());
+/// ```
+#[macro_export]
+macro_rules! data_enum {
+	(
+		$(#[$meta:meta])*
+		$vis:vis enum $name:ident: $repr:ident {
+			$(
+				$(#[$variant_meta:meta])*
+				$variant:ident = $value:literal
+			),* $(,)?
+		}
+	) => {
+		$(#[$meta])*
+		#[repr($repr)]
+		$vis enum $name {
+			$(
+				$(#[$variant_meta])*
+				$variant = $value,
+			)*
+		}
+
+		impl $crate::bus::Data for $name {
+			const ENCODED_SIZE: u16 = <$repr as $crate::bus::Data>::ENCODED_SIZE;
+
+			fn encode(&self, buffer: &mut [u8]) -> ::core::result::Result<(), $crate::BufferTooSmallError> {
+				let value: $repr = match self {
+					$(Self::$variant => $value,)*
+				};
+				$crate::bus::Data::encode(&value, buffer)
+			}
+
+			fn decode(buffer: &[u8]) -> ::core::result::Result<Self, $crate::InvalidMessage> {
+				match <$repr as $crate::bus::Data>::decode(buffer)? {
+					$($value => ::core::result::Result::Ok(Self::$variant),)*
+					value => ::core::result::Result::Err($crate::InvalidValue {
+						actual: value.into(),
+						type_name: ::core::stringify!($name),
+					}.into()),
+				}
+			}
+		}
+	};
+}
+
 impl<T: Data, const N: usize> Data for [T; N] {
 	const ENCODED_SIZE: u16 = T::ENCODED_SIZE.checked_mul(to_u16(N)).unwrap();
 
@@ -166,3 +298,48 @@ const fn to_u16(input: usize) -> u16 {
 	assert!(input <= u16::MAX as usize);
 	input as u16
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+
+	crate::data_enum! {
+		#[derive(Debug, PartialEq)]
+		enum Mode: u8 {
+			Velocity = 1,
+			Position = 3,
+		}
+	}
+
+	#[test]
+	fn bool_rejects_values_other_than_0_and_1() {
+		assert!(let Ok(false) = bool::decode(&[0]));
+		assert!(let Ok(true) = bool::decode(&[1]));
+		let_assert!(Err(crate::InvalidMessage::InvalidValue(e)) = bool::decode(&[2]));
+		assert!(e.actual == 2);
+		assert!(e.type_name == "bool");
+	}
+
+	#[test]
+	fn tuples_are_packed_back_to_back() {
+		type Tuple = (u8, i16, bool, [u8; 2]);
+		assert!(Tuple::ENCODED_SIZE == 6);
+
+		let mut buffer = [0; 6];
+		let_assert!(Ok(()) = (1u8, -2i16, true, [3u8, 4]).encode(&mut buffer));
+		assert!(buffer == [1, 0xFE, 0xFF, 1, 3, 4]);
+		let_assert!(Ok((1, -2, true, [3, 4])) = Tuple::decode(&buffer));
+		assert!(let Err(_) = Tuple::decode(&buffer[..5]));
+	}
+
+	#[test]
+	fn enum_helper_rejects_unknown_values() {
+		let mut buffer = [0];
+		let_assert!(Ok(()) = Mode::Position.encode(&mut buffer));
+		assert!(buffer == [3]);
+		assert!(let Ok(Mode::Velocity) = Mode::decode(&[1]));
+		let_assert!(Err(crate::InvalidMessage::InvalidValue(e)) = Mode::decode(&[2]));
+		assert!(e.type_name == "Mode");
+	}
+}
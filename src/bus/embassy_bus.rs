@@ -0,0 +1,86 @@
+//! Async I/O for the low level bus using an [`EmbassySerialPort`].
+//!
+//! The packets are encoded and decoded by the same functions as for the blocking implementation.
+//! Only the reading from and writing to the serial port is different.
+
+use embassy_time::Instant;
+use embedded_hal::digital::OutputPin;
+
+use super::{Bus, ExpectedPacket, Packet, StatusPacket};
+use crate::serial_port::embassy::{EmbassySerialPort, EmbassySerialPortError};
+use crate::serial_port::embedded_io::EmbeddedIoError;
+use crate::{ReadError, WriteError};
+
+impl<Io, DirectionPin, Buffer> Bus<EmbassySerialPort<Io, DirectionPin>, Buffer>
+where
+	Io: embedded_io_async::Read + embedded_io_async::Write,
+	DirectionPin: OutputPin,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Write a status message to the bus.
+	pub async fn write_status_embassy<F>(
+		&mut self,
+		packet_id: u8,
+		error: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<EmbassySerialPortError<Io, DirectionPin>>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		crate::error::BufferTooSmallError::check(StatusPacket::message_len(parameter_count), self.write_buffer.as_ref().len())?;
+		self.write_packet_embassy(packet_id, crate::instructions::instruction_id::STATUS, parameter_count + 1, |buffer| {
+			buffer[0] = error;
+			encode_parameters(&mut buffer[1..])
+		}).await
+	}
+
+	/// Write a packet to the bus.
+	///
+	/// Unlike the other serial ports, the input buffer of the serial port is not discarded,
+	/// since the `embedded-io-async` traits have no way to do so.
+	pub async fn write_packet_embassy<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<EmbassySerialPortError<Io, DirectionPin>>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		let message_len = self.encode_packet(packet_id, instruction_id, parameter_count, encode_parameters)?;
+		self.clear_read_buffer();
+
+		// Send message.
+		let stuffed_message = &self.write_buffer.as_ref()[..message_len];
+		trace!("sending packet: {:02X?}", stuffed_message);
+		self.serial_port.write_all(stuffed_message).await.map_err(WriteError::Write)?;
+		self.record_sent(message_len);
+		Ok(())
+	}
+
+	/// Read a raw packet from the bus with the given deadline.
+	///
+	/// If the deadline expires, a [`ReadError::Timeout`] is returned.
+	pub async fn read_packet_deadline_embassy(
+		&mut self,
+		deadline: Instant,
+		expected: ExpectedPacket,
+	) -> Result<Packet<'_>, ReadError<EmbassySerialPortError<Io, DirectionPin>>> {
+		let stuffed_message_len = loop {
+			if let Some(stuffed_message_len) = self.buffered_packet_len(&expected)? {
+				break stuffed_message_len;
+			}
+
+			// Try to read more data into the buffer.
+			match self.serial_port.read(&mut self.read_buffer.as_mut()[self.read_len..], deadline).await {
+				Ok(new_data) => self.record_read(new_data),
+				Err(EmbeddedIoError::Timeout) => return Err(self.timeout_error(&expected)),
+				Err(e) => return Err(ReadError::Io(e)),
+			}
+		};
+
+		Ok(self.take_packet(stuffed_message_len)?)
+	}
+}
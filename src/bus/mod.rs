@@ -9,9 +9,21 @@ pub(crate) mod endian;
 pub(crate) mod data;
 pub use data::Data;
 
+/// Derive the [`Data`] trait for a struct.
+///
+/// This macro is only available if the `derive` feature is enabled.
+#[cfg(feature = "derive")]
+pub use dynamixel2_derive::Data;
+
 mod packet;
 pub use packet::{Packet, InstructionPacket, StatusPacket};
 
+#[cfg(feature = "async")]
+mod async_bus;
+
+#[cfg(feature = "embassy")]
+mod embassy_bus;
+
 /// Prefix of a packet.
 ///
 /// All packets start with this prefix, and they can not contain it in the body.
@@ -22,7 +34,7 @@ const HEADER_PREFIX: [u8; 4] = [0xFF, 0xFF, 0xFD, 0x00];
 /// The size of a message header, including the pre-amble, packet ID and length.
 ///
 /// Excludes the instruction ID, the error field of status packets, the parameters and the CRC.
-const HEADER_SIZE: usize = 7;
+pub(crate) const HEADER_SIZE: usize = 7;
 
 /// Default buffer type.
 ///
@@ -82,7 +94,6 @@ macro_rules! static_buffer {
 /// Used by [`crate::Client`] and [`crate::Device`].
 pub(crate) struct Bus<SerialPort, Buffer>
 where
-	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	/// The underlying stream (normally a serial port).
@@ -102,26 +113,70 @@ where
 
 	/// The buffer for outgoing messages.
 	pub(crate) write_buffer: Buffer,
+
+	/// The ID of the motor that is expected to send the next status packet, if known.
+	///
+	/// Used to report timeouts.
+	pub(crate) response_motor_id: Option<u8>,
+
+	/// Statistics about the communication on the bus.
+	pub(crate) stats: crate::Stats,
 }
 
+/// A packet that is expected to be read from the bus.
+///
+/// Used to report timeouts, and to find the end of a packet if the length field can not be trusted.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExpectedPacket {
+	/// The expected (unstuffed) size of the packet, if known.
+	pub(crate) size: Option<usize>,
+
+	/// If true, the packet ends after `size` bytes of unstuffed data, unless the length field indicates a shorter packet.
+	///
+	/// This is used for the combined response to a fast read instruction.
+	/// The first device writes the length field before the other devices apply their byte-stuffing,
+	/// so the length field does not include all stuffing bytes.
+	pub(crate) exact_size: bool,
+
+	/// The timeout used to compute the read deadline.
+	pub(crate) timeout: Duration,
+}
+
+impl ExpectedPacket {
+	/// An expected packet of unknown size.
+	pub(crate) fn unknown_size(timeout: Duration) -> Self {
+		Self {
+			size: None,
+			exact_size: false,
+			timeout,
+		}
+	}
+
+	/// An expected status packet with the given number of parameters.
+	pub(crate) fn status(parameters: usize, timeout: Duration) -> Self {
+		Self {
+			size: Some(StatusPacket::message_len(parameters)),
+			exact_size: false,
+			timeout,
+		}
+	}
+
+	/// The expected combined response to a fast read instruction, with the given number of parameters.
+	pub(crate) fn fast_read_response(parameters: usize, timeout: Duration) -> Self {
+		Self {
+			exact_size: true,
+			..Self::status(parameters, timeout)
+		}
+	}
+}
+
+/// Buffer handling that does not depend on the type of serial port.
+///
+/// This is shared by the blocking and the async implementation of the bus.
 impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
 where
-	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	/// Create a new bus using pre-allocated buffers.
-	///
-	/// The serial port must already be configured in raw mode with the correct baud rate,
-	/// character size (8), parity (disabled) and stop bits (1).
-	pub fn with_buffers(
-		serial_port: SerialPort,
-		read_buffer: Buffer,
-		write_buffer: Buffer,
-	) -> Result<Self, SerialPort::Error> {
-		let baud_rate = serial_port.baud_rate()?;
-		Ok(Self::with_buffers_and_baud_rate(serial_port, read_buffer, write_buffer, baud_rate))
-	}
-
 	/// Create a new bus using pre-allocated buffers.
 	pub fn with_buffers_and_baud_rate(
 		serial_port: SerialPort,
@@ -129,12 +184,8 @@ where
 		write_buffer: Buffer,
 		baud_rate: u32,
 	) -> Self {
-		let mut write_buffer = write_buffer;
-
-		// Pre-fill write buffer with the header prefix.
 		// TODO: return Err instead of panicking.
-		assert!(write_buffer.as_mut().len() >= HEADER_SIZE + 3);
-		write_buffer.as_mut()[..4].copy_from_slice(&HEADER_PREFIX);
+		assert!(write_buffer.as_ref().len() >= HEADER_SIZE + 3);
 
 		Self {
 			serial_port,
@@ -143,65 +194,40 @@ where
 			read_len: 0,
 			used_bytes: 0,
 			write_buffer,
+			response_motor_id: None,
+			stats: crate::Stats::new(),
 		}
 	}
 
-	/// Set the baud rate of the underlying serial port.
-	pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), SerialPort::Error> {
-		self.serial_port.set_baud_rate(baud_rate)?;
-		self.baud_rate = baud_rate;
-		Ok(())
-	}
-
-	/// Write a status message to the bus.
-	pub fn write_status<F>(
-		&mut self,
-		packet_id: u8,
-		error: u8,
-		parameter_count: usize,
-		encode_parameters: F,
-	) -> Result<(), WriteError<SerialPort::Error>>
-	where
-		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
-	{
-		crate::error::BufferTooSmallError::check(StatusPacket::message_len(parameter_count), self.write_buffer.as_ref().len())?;
-		self.write_packet(packet_id, crate::instructions::instruction_id::STATUS, parameter_count + 1, |buffer| {
-			buffer[0] = error;
-			encode_parameters(&mut buffer[1..])
-		})
-	}
-
-	/// Write an instruction message to the bus.
-	pub fn write_instruction<F>(
+	/// Encode a packet in the write buffer.
+	///
+	/// Returns the length of the encoded (stuffed) message, including the header and CRC.
+	pub(crate) fn encode_packet<F>(
 		&mut self,
 		packet_id: u8,
 		instruction_id: u8,
 		parameter_count: usize,
 		encode_parameters: F,
-	) -> Result<(), WriteError<SerialPort::Error>>
+	) -> Result<usize, crate::error::BufferTooSmallError>
 	where
 		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
 	{
-		self.write_packet(packet_id, instruction_id, parameter_count, encode_parameters)
-	}
+		// Only a single motor responds to an instruction for a specific motor.
+		// Status packets are not answered at all.
+		self.response_motor_id = if instruction_id == crate::instructions::instruction_id::STATUS || packet_id == crate::instructions::packet_id::BROADCAST {
+			None
+		} else {
+			Some(packet_id)
+		};
 
-	/// Write a packet to the bus.
-	pub fn write_packet<F>(
-		&mut self,
-		packet_id: u8,
-		instruction_id: u8,
-		parameter_count: usize,
-		encode_parameters: F,
-	) -> Result<(), WriteError<SerialPort::Error>>
-	where
-		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
-	{
 		let buffer = self.write_buffer.as_mut();
 
 		// Check if the buffer can hold the unstuffed message.
 		crate::error::BufferTooSmallError::check(InstructionPacket::message_len(parameter_count), buffer.len())?;
 
 		// Add the header, with a placeholder for the length field.
+		// The header prefix is written every time, since the write buffer may be shared with a Protocol 1.0 client.
+		buffer[..4].copy_from_slice(&HEADER_PREFIX);
 		buffer[4] = packet_id;
 		buffer[5] = 0;
 		buffer[6] = 0;
@@ -220,55 +246,132 @@ where
 		let checksum = checksum::calculate_checksum(0, &buffer[..checksum_index]);
 		endian::write_u16_le(&mut buffer[checksum_index..], checksum);
 
-		// Throw away old data in the read buffer and the kernel read buffer.
-		// We don't do this when reading a reply, because we might receive multiple replies for one instruction,
-		// and read() can potentially read more than one reply per syscall.
+		Ok(checksum_index + 2)
+	}
+
+	/// Create the error for a read that timed out while waiting for a packet.
+	///
+	/// If the header of the packet has already been received, the size from the header is reported.
+	///
+	/// The timeout is also recorded in the statistics of the bus.
+	pub(crate) fn timeout_error<E>(&mut self, expected: &ExpectedPacket) -> ReadError<E> {
+		self.stats.timeouts += 1;
+		if let Some(motor_id) = self.response_motor_id {
+			self.stats.record_motor_error(motor_id);
+		}
+
+		let read_buffer = &self.read_buffer.as_ref()[..self.read_len];
+		let expected_bytes = if read_buffer.len() > HEADER_SIZE && read_buffer.starts_with(&HEADER_PREFIX) {
+			HEADER_SIZE + usize::from(endian::read_u16_le(&read_buffer[5..]))
+		} else {
+			expected.size.unwrap_or(HEADER_SIZE + 3)
+		};
+		ReadError::Timeout {
+			motor_id: self.response_motor_id,
+			expected_bytes,
+			waited: expected.timeout,
+		}
+	}
+
+	/// Record that a message was written to the bus.
+	pub(crate) fn record_sent(&mut self, message_len: usize) {
+		self.stats.packets_sent += 1;
+		self.stats.bytes_sent += message_len as u64;
+	}
+
+	/// Record that new data was read into the read buffer.
+	pub(crate) fn record_read(&mut self, new_data: usize) {
+		self.read_len += new_data;
+		self.stats.bytes_received += new_data as u64;
+	}
+
+	/// Discard all data in the read buffer.
+	pub(crate) fn clear_read_buffer(&mut self) {
 		self.read_len = 0;
 		self.used_bytes = 0;
-		self.serial_port.discard_input_buffer().map_err(WriteError::DiscardBuffer)?;
-
-		// Send message.
-		let stuffed_message = &buffer[..checksum_index + 2];
-		trace!("sending packet: {:02X?}", stuffed_message);
-		self.serial_port.write_all(stuffed_message).map_err(WriteError::Write)?;
-		Ok(())
 	}
 
-	/// Read a raw packet from the bus with the given deadline.
-	pub fn read_packet_deadline(
-		&mut self,
-		deadline: SerialPort::Instant,
-	) -> Result<Packet<'_>, ReadError<SerialPort::Error>>
-	{
+	/// Get the length of the first complete packet in the read buffer, if there is one.
+	///
+	/// Leading garbage is removed from the read buffer.
+	/// If this returns `None`, more data needs to be read into the read buffer.
+	pub(crate) fn buffered_packet_len(&mut self, expected: &ExpectedPacket) -> Result<Option<usize>, crate::error::BufferTooSmallError> {
 		// Check that the read buffer is large enough to hold atleast a instruction packet with 0 parameters.
 		crate::error::BufferTooSmallError::check(HEADER_SIZE + 3, self.read_buffer.as_mut().len())?;
 
-		let stuffed_message_len = loop {
-			self.remove_garbage();
-
-			// The call to remove_garbage() removes all leading bytes that don't match a packet header.
-			// So if there's enough bytes left, it's a packet header.
-			if self.read_len > HEADER_SIZE {
-				let read_buffer = &self.read_buffer.as_mut()[..self.read_len];
-				let body_len = endian::read_u16_le(&read_buffer[5..]) as usize;
+		self.remove_garbage();
 
-				// Check if the read buffer is large enough for the entire message.
-				crate::error::BufferTooSmallError::check(HEADER_SIZE + body_len, self.read_buffer.as_mut().len()).inspect_err(|_| {
-					self.consume_read_bytes(HEADER_SIZE);
-				})?;
+		// The call to remove_garbage() removes all leading bytes that don't match a packet header.
+		// So if there's enough bytes left, it's a packet header.
+		if self.read_len > HEADER_SIZE {
+			let read_buffer = &self.read_buffer.as_mut()[..self.read_len];
+			let body_len = endian::read_u16_le(&read_buffer[5..]) as usize;
 
-				if self.read_len >= HEADER_SIZE + body_len {
-					break HEADER_SIZE + body_len;
+			// A length field that is too short can not be explained by missing stuffing bytes.
+			if let (true, Some(size)) = (expected.exact_size, expected.size) {
+				if HEADER_SIZE + body_len >= size {
+					return self.buffered_packet_len_exact(size);
 				}
 			}
 
-			// Try to read more data into the buffer.
-			let new_data = self.serial_port.read(&mut self.read_buffer.as_mut()[self.read_len..], &deadline)
-				.map_err(ReadError::Io)?;
+			// Check if the read buffer is large enough for the entire message.
+			crate::error::BufferTooSmallError::check(HEADER_SIZE + body_len, self.read_buffer.as_mut().len()).inspect_err(|_| {
+				self.consume_read_bytes(HEADER_SIZE);
+			})?;
 
-			self.read_len += new_data;
-		};
+			if self.read_len >= HEADER_SIZE + body_len {
+				return Ok(Some(HEADER_SIZE + body_len));
+			}
+		}
+
+		Ok(None)
+	}
+
+	/// Get the length of the first packet in the read buffer, if it holds `size` bytes of unstuffed data.
+	///
+	/// The length field of the packet is ignored.
+	fn buffered_packet_len_exact(&mut self, size: usize) -> Result<Option<usize>, crate::error::BufferTooSmallError> {
+		let buffer_len = self.read_buffer.as_mut().len();
+		crate::error::BufferTooSmallError::check(size, buffer_len)?;
+
+		// Only the body of the packet is byte-stuffed, not the header and the CRC.
+		let read_buffer = &self.read_buffer.as_ref()[..self.read_len];
+		let body_len = size - HEADER_SIZE - 2;
+		if let Some(stuffed_body_len) = read_buffer
+			.get(HEADER_SIZE..)
+			.and_then(|body| bytestuff::stuffed_len(body, body_len))
+		{
+			let stuffed_len = HEADER_SIZE + stuffed_body_len + 2;
+			if stuffed_len <= self.read_len {
+				return Ok(Some(stuffed_len));
+			}
+			crate::error::BufferTooSmallError::check(stuffed_len, buffer_len).inspect_err(|_| {
+				self.consume_read_bytes(HEADER_SIZE);
+			})?;
+		} else if self.read_len == buffer_len {
+			self.consume_read_bytes(HEADER_SIZE);
+			return Err(crate::error::BufferTooSmallError {
+				required_size: buffer_len + 1,
+				total_size: buffer_len,
+			});
+		}
+		Ok(None)
+	}
+
+	/// Check and take the packet at the start of the read buffer.
+	///
+	/// The `stuffed_message_len` must be the value returned by [`Self::buffered_packet_len()`].
+	pub(crate) fn take_packet(&mut self, stuffed_message_len: usize) -> Result<Packet<'_>, crate::InvalidMessage> {
+		let packet_len = self.check_packet(stuffed_message_len)?;
+		Ok(self.checked_packet(packet_len))
+	}
 
+	/// Check the packet at the start of the read buffer and remove the byte-stuffing.
+	///
+	/// The `stuffed_message_len` must be the value returned by [`Self::buffered_packet_len()`].
+	/// Returns the length of the packet without byte-stuffing and checksum,
+	/// which can be passed to [`Self::checked_packet()`] to get the packet.
+	pub(crate) fn check_packet(&mut self, stuffed_message_len: usize) -> Result<usize, crate::InvalidMessage> {
 		let buffer = self.read_buffer.as_mut();
 		let parameters_end = stuffed_message_len - 2;
 		trace!("read packet: {:02X?}", &buffer[..parameters_end]);
@@ -276,6 +379,7 @@ where
 		let checksum_message = endian::read_u16_le(&buffer[parameters_end..]);
 		let checksum_computed = checksum::calculate_checksum(0, &buffer[..parameters_end]);
 		if checksum_message != checksum_computed {
+			self.stats.checksum_errors += 1;
 			self.consume_read_bytes(stuffed_message_len);
 			return Err(crate::InvalidChecksum {
 				message: checksum_message,
@@ -286,23 +390,35 @@ where
 
 		// Mark the whole message as "used_bytes", so that the next call to `remove_garbage()` removes it.
 		self.used_bytes += stuffed_message_len;
+		self.stats.packets_received += 1;
 
 		// Remove byte-stuffing from the everything from instruction ID to the parameters.
 		let parameter_count = bytestuff::unstuff_inplace(&mut buffer[HEADER_SIZE..parameters_end]);
-
-		// Wrap the data in a `Packet`.
-		let data = &self.read_buffer.as_ref()[..HEADER_SIZE + parameter_count];
-		let packet = packet::Packet { data };
+		let packet_len = HEADER_SIZE + parameter_count;
 
 		// Ensure that status packets have an error field (included in parameter_count here).
-		if packet.instruction_id() == crate::instructions::instruction_id::STATUS && parameter_count < 1 {
-			return Err(crate::InvalidMessage::InvalidParameterCount(crate::InvalidParameterCount {
-				actual: 0,
-				expected: crate::ExpectedCount::Min(1),
-			}).into());
+		let packet = self.checked_packet(packet_len);
+		if packet.instruction_id() == crate::instructions::instruction_id::STATUS {
+			if parameter_count < 1 {
+				return Err(crate::InvalidMessage::InvalidParameterCount(crate::InvalidParameterCount {
+					actual: 0,
+					expected: crate::ExpectedCount::Min(1),
+				}));
+			}
+			if crate::MotorError::check(packet.data[HEADER_SIZE + 1]).is_err() {
+				let motor_id = packet.packet_id();
+				self.stats.record_motor_error(motor_id);
+			}
 		}
 
-		Ok(packet)
+		Ok(packet_len)
+	}
+
+	/// Get the packet at the start of the read buffer that was checked by [`Self::check_packet()`].
+	///
+	/// The packet stays available until more data is read from the bus.
+	pub(crate) fn checked_packet(&self, packet_len: usize) -> Packet<'_> {
+		packet::Packet { data: &self.read_buffer.as_ref()[..packet_len] }
 	}
 
 	/// Remove leading garbage data from the read buffer.
@@ -310,6 +426,7 @@ where
 		let read_buffer = self.read_buffer.as_mut();
 		let garbage_len = find_header(&read_buffer[..self.read_len][self.used_bytes..]);
 		if garbage_len > 0 {
+			self.stats.garbage_bytes += garbage_len as u64;
 			debug!("skipping {} bytes of leading garbage.", garbage_len);
 			trace!("skipped garbage: {:02X?}", &read_buffer[..garbage_len]);
 		}
@@ -317,7 +434,7 @@ where
 		debug_assert_eq!(self.used_bytes, 0);
 	}
 
-	fn consume_read_bytes(&mut self, len: usize) {
+	pub(crate) fn consume_read_bytes(&mut self, len: usize) {
 		debug_assert!(len <= self.read_len);
 		self.read_buffer.as_mut().copy_within(len..self.read_len, 0);
 		// Decrease both used_bytes and read_len together.
@@ -328,12 +445,353 @@ where
 	}
 }
 
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Create a new bus using pre-allocated buffers.
+	///
+	/// The serial port must already be configured in raw mode with the correct baud rate,
+	/// character size (8), parity (disabled) and stop bits (1).
+	pub fn with_buffers(
+		serial_port: SerialPort,
+		read_buffer: Buffer,
+		write_buffer: Buffer,
+	) -> Result<Self, SerialPort::Error> {
+		let baud_rate = serial_port.baud_rate()?;
+		Ok(Self::with_buffers_and_baud_rate(serial_port, read_buffer, write_buffer, baud_rate))
+	}
+
+	/// Set the baud rate of the underlying serial port.
+	pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), SerialPort::Error> {
+		self.serial_port.set_baud_rate(baud_rate)?;
+		self.baud_rate = baud_rate;
+		Ok(())
+	}
+
+	/// Write a status message to the bus.
+	pub fn write_status<F>(
+		&mut self,
+		packet_id: u8,
+		error: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		crate::error::BufferTooSmallError::check(StatusPacket::message_len(parameter_count), self.write_buffer.as_ref().len())?;
+		self.write_packet(packet_id, crate::instructions::instruction_id::STATUS, parameter_count + 1, |buffer| {
+			buffer[0] = error;
+			encode_parameters(&mut buffer[1..])
+		})
+	}
+
+	/// Write an instruction message to the bus.
+	pub fn write_instruction<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		self.write_packet(packet_id, instruction_id, parameter_count, encode_parameters)
+	}
+
+	/// Write a packet to the bus.
+	pub fn write_packet<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		let message_len = self.encode_packet(packet_id, instruction_id, parameter_count, encode_parameters)?;
+
+		// Throw away old data in the read buffer and the kernel read buffer.
+		// We don't do this when reading a reply, because we might receive multiple replies for one instruction,
+		// and read() can potentially read more than one reply per syscall.
+		self.clear_read_buffer();
+		self.serial_port.discard_input_buffer().map_err(WriteError::DiscardBuffer)?;
+
+		// Send message.
+		let stuffed_message = &self.write_buffer.as_ref()[..message_len];
+		trace!("sending packet: {:02X?}", stuffed_message);
+		self.serial_port.write_all(stuffed_message).map_err(WriteError::Write)?;
+		self.record_sent(message_len);
+		Ok(())
+	}
+
+	/// Read a raw packet from the bus with the given deadline.
+	///
+	/// If the serial port reports a timeout, a [`ReadError::Timeout`] is returned.
+	pub fn read_packet_deadline(
+		&mut self,
+		deadline: SerialPort::Instant,
+		expected: ExpectedPacket,
+	) -> Result<Packet<'_>, ReadError<SerialPort::Error>>
+	{
+		let stuffed_message_len = self.wait_packet_deadline(deadline, expected)?;
+		Ok(self.take_packet(stuffed_message_len)?)
+	}
+
+	/// Read data from the serial port until the read buffer holds a complete packet.
+	///
+	/// Returns the length of the packet, which can then be taken with [`Self::take_packet()`].
+	pub(crate) fn wait_packet_deadline(
+		&mut self,
+		deadline: SerialPort::Instant,
+		expected: ExpectedPacket,
+	) -> Result<usize, ReadError<SerialPort::Error>>
+	{
+		loop {
+			if let Some(stuffed_message_len) = self.buffered_packet_len(&expected)? {
+				return Ok(stuffed_message_len);
+			}
+
+			// Try to read more data into the buffer.
+			self.read_more(&deadline, &expected)?;
+		}
+	}
+
+	/// Read more data from the serial port into the read buffer.
+	///
+	/// Timeouts of the serial port are reported as [`ReadError::Timeout`].
+	pub(crate) fn read_more(&mut self, deadline: &SerialPort::Instant, expected: &ExpectedPacket) -> Result<(), ReadError<SerialPort::Error>> {
+		match self.serial_port.read(&mut self.read_buffer.as_mut()[self.read_len..], deadline) {
+			Ok(new_data) => {
+				self.record_read(new_data);
+				Ok(())
+			},
+			Err(e) if SerialPort::is_timeout_error(&e) => Err(self.timeout_error(expected)),
+			Err(e) => Err(ReadError::Io(e)),
+		}
+	}
+
+	/// Read and discard all data that arrives on the bus before the deadline.
+	///
+	/// The discarded data is counted as received garbage in the statistics of the bus.
+	/// Reaching the deadline is not reported as a timeout.
+	pub(crate) fn discard_until(&mut self, deadline: &SerialPort::Instant) -> Result<(), ReadError<SerialPort::Error>> {
+		loop {
+			self.clear_read_buffer();
+			match self.serial_port.read(self.read_buffer.as_mut(), deadline) {
+				Ok(new_data) => {
+					self.record_read(new_data);
+					self.stats.garbage_bytes += new_data as u64;
+				},
+				Err(e) if SerialPort::is_timeout_error(&e) => break,
+				Err(e) => return Err(ReadError::Io(e)),
+			}
+		}
+		self.clear_read_buffer();
+		Ok(())
+	}
+
+	/// Read and consume the first `len` bytes of the next status packet on the bus.
+	///
+	/// This is used to wait for the preceding segments of a combined fast read response.
+	/// The `len` bytes are counted without byte-stuffing, but the stuffing bytes are consumed too.
+	/// Their CRC is not checked.
+	///
+	/// Returns the running checksum of the consumed bytes as they were sent on the bus,
+	/// and the byte-stuffing state at the end of the consumed bytes (see [`bytestuff::stuffed_checksum()`]).
+	pub(crate) fn read_status_prefix_deadline(
+		&mut self,
+		len: usize,
+		deadline: SerialPort::Instant,
+		timeout: Duration,
+	) -> Result<(u16, usize), ReadError<SerialPort::Error>> {
+		crate::error::BufferTooSmallError::check(HEADER_SIZE + 1, self.read_buffer.as_mut().len())?;
+		let expected = ExpectedPacket {
+			size: Some(len),
+			exact_size: false,
+			timeout,
+		};
+
+		// Wait for the header of the status packet.
+		loop {
+			self.remove_garbage();
+			if self.read_len > HEADER_SIZE {
+				break;
+			}
+			self.read_more(&deadline, &expected)?;
+		}
+
+		let header = &self.read_buffer.as_ref()[..HEADER_SIZE + 1];
+		let packet_id = header[4];
+		let instruction_id = header[7];
+		if let Err(e) = crate::InvalidInstruction::check(instruction_id, crate::instructions::instruction_id::STATUS) {
+			self.consume_read_bytes(HEADER_SIZE);
+			return Err(e.into());
+		}
+		if let Err(e) = crate::InvalidPacketId::check(packet_id, crate::instructions::packet_id::BROADCAST) {
+			self.consume_read_bytes(HEADER_SIZE);
+			return Err(e.into());
+		}
+
+		// The header is not byte-stuffed.
+		let checksum = checksum::calculate_checksum(0, &self.read_buffer.as_ref()[..HEADER_SIZE]);
+		self.consume_read_bytes(HEADER_SIZE);
+
+		// Consume the body as it comes in, so the read buffer does not need to hold all of it at once.
+		let mut checksum = checksum;
+		let mut remaining = len - HEADER_SIZE;
+		let mut state = 0;
+		loop {
+			let available = &self.read_buffer.as_ref()[..self.read_len];
+			let mut consumed = 0;
+			for &byte in available {
+				if remaining == 0 {
+					// The stuffing byte after a pattern at the end of the prefix is part of the prefix.
+					if state == bytestuff::PATTERN.len() - 1 {
+						if byte == bytestuff::PATTERN[state] {
+							consumed += 1;
+						}
+						state = 0;
+					}
+					break;
+				}
+				consumed += 1;
+				if byte == bytestuff::PATTERN[state] {
+					state += 1;
+				} else {
+					state = 0;
+				}
+				if state == bytestuff::PATTERN.len() {
+					state = 0;
+				} else {
+					remaining -= 1;
+				}
+			}
+			checksum = checksum::calculate_checksum(checksum, &available[..consumed]);
+			self.consume_read_bytes(consumed);
+			if remaining == 0 && state != bytestuff::PATTERN.len() - 1 {
+				break;
+			}
+			self.read_more(&deadline, &expected)?;
+		}
+
+		trace!("read {} bytes of status packet prefix", len);
+		Ok((checksum, state))
+	}
+
+	/// Write the segment of a device in a combined fast read response.
+	///
+	/// The segment consists of the error field, the packet ID, the parameters and the running CRC.
+	/// If `segment.prefix` is `None`, this is the first segment and the status packet header is written too.
+	///
+	/// The segment is byte-stuffed as part of the body of the status packet, and the CRC covers the stuffed bytes.
+	/// The CRC of the last segment is the CRC of the whole packet, which is not stuffed.
+	///
+	/// The length field is written by the first device, which does not know the stuffing bytes of the other devices.
+	/// So the length field counts the unstuffed segments of the other devices.
+	pub(crate) fn write_fast_read_segment<F>(
+		&mut self,
+		packet_id: u8,
+		error: u8,
+		segment: FastReadSegment,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		let FastReadSegment {
+			total_length,
+			prefix,
+			last,
+		} = segment;
+		let buffer = self.write_buffer.as_mut();
+
+		// A continued segment starts with the bytes of the stuffing pattern that ended the preceding segments,
+		// so that a pattern spanning both segments is stuffed too.
+		// Those bytes were already sent by the preceding devices.
+		let (body_start, pattern_len, checksum) = match prefix {
+			None => (HEADER_SIZE, 0, None),
+			Some((checksum, state)) => (0, state, Some(checksum)),
+		};
+		let segment_start = body_start + pattern_len + if prefix.is_none() { 1 } else { 0 };
+		let data_end = segment_start + 2 + parameter_count;
+		crate::error::BufferTooSmallError::check(data_end + 2, buffer.len())?;
+
+		if prefix.is_none() {
+			let total_length = u16::try_from(total_length).map_err(|_| crate::error::BufferTooSmallError {
+				required_size: HEADER_SIZE + total_length,
+				total_size: HEADER_SIZE + usize::from(u16::MAX),
+			})?;
+			buffer[..4].copy_from_slice(&HEADER_PREFIX);
+			buffer[4] = crate::instructions::packet_id::BROADCAST;
+			endian::write_u16_le(&mut buffer[5..], total_length);
+			buffer[7] = crate::instructions::instruction_id::STATUS;
+		}
+		buffer[body_start..][..pattern_len].copy_from_slice(&bytestuff::PATTERN[..pattern_len]);
+		buffer[segment_start] = error;
+		buffer[segment_start + 1] = packet_id;
+		encode_parameters(&mut buffer[segment_start + 2..data_end])?;
+
+		// Compute the CRC over the stuffed bytes, before they are stuffed in place.
+		let (checksum, mut state) = match checksum {
+			Some(checksum) => (checksum, pattern_len),
+			None => (checksum::calculate_checksum(0, &buffer[..HEADER_SIZE]), 0),
+		};
+		let checksum = bytestuff::stuffed_checksum(checksum, &mut state, &buffer[body_start + pattern_len..data_end]);
+
+		// The CRC of the last segment is the packet CRC, which is not part of the stuffed body.
+		let message_end = if last {
+			let stuffed_body_len = bytestuff::stuff_inplace(&mut buffer[body_start..], data_end - body_start)?;
+			let checksum_index = body_start + stuffed_body_len;
+			crate::error::BufferTooSmallError::check(checksum_index + 2, buffer.len())?;
+			endian::write_u16_le(&mut buffer[checksum_index..], checksum);
+			checksum_index + 2
+		} else {
+			endian::write_u16_le(&mut buffer[data_end..], checksum);
+			body_start + bytestuff::stuff_inplace(&mut buffer[body_start..], data_end + 2 - body_start)?
+		};
+
+		let start = if prefix.is_none() { 0 } else { pattern_len };
+		let message = &buffer[start..message_end];
+		trace!("sending fast read segment: {:02X?}", message);
+		self.serial_port.write_all(message).map_err(WriteError::Write)?;
+
+		// Only count the first segment as a packet, since all segments together form one status packet.
+		let message_len = message.len();
+		if prefix.is_none() {
+			self.stats.packets_sent += 1;
+		}
+		self.stats.bytes_sent += message_len as u64;
+		Ok(())
+	}
+}
+
+/// The position of a device segment in a combined fast read response.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FastReadSegment {
+	/// The value of the length field of the status packet.
+	pub total_length: usize,
+
+	/// The running checksum and byte-stuffing state of the preceding bytes of the response.
+	///
+	/// This is `None` for the first segment, and otherwise the value returned by [`Bus::read_status_prefix_deadline()`].
+	pub prefix: Option<(u16, usize)>,
+
+	/// If true, this is the last segment of the response.
+	pub last: bool,
+}
+
 /// Find the potential starting position of a header.
 ///
 /// This will return the first possible position of the header prefix.
 /// Note that if the buffer ends with a partial header prefix,
 /// the start position of the partial header prefix is returned.
-fn find_header(buffer: &[u8]) -> usize {
+pub(crate) fn find_header(buffer: &[u8]) -> usize {
 	for i in 0..buffer.len() {
 		let possible_prefix = HEADER_PREFIX.len().min(buffer.len() - i);
 		if buffer[i..].starts_with(&HEADER_PREFIX[..possible_prefix]) {
@@ -344,6 +802,19 @@ fn find_header(buffer: &[u8]) -> usize {
 	buffer.len()
 }
 
+/// Calculate the timeout for a status response with the given number of parameters.
+pub(crate) fn status_response_timeout(parameters: usize, baud_rate: u32) -> Duration {
+	response_timeout(StatusPacket::message_len(parameters), baud_rate)
+}
+
+/// Calculate the timeout for a status response with the given total size in bytes.
+///
+/// This is also used for Protocol 1.0 status packets, which have a smaller header.
+pub(crate) fn response_timeout(message_size: usize, baud_rate: u32) -> Duration {
+	// Official SDK adds a flat 34 milliseconds, so lets just mimick that.
+	message_transfer_time(message_size as u32, baud_rate) + Duration::from_millis(34)
+}
+
 /// Calculate the required time to transfer a message of a given size.
 ///
 /// The size must include any headers and footers of the message.
@@ -359,7 +830,53 @@ pub(crate) fn message_transfer_time(message_size: u32, baud_rate: u32) -> Durati
 #[cfg(test)]
 mod test {
 	use super::*;
-	use assert2::assert;
+	use crate::serial_port::reply::ReplySerial;
+	use assert2::{assert, let_assert};
+
+	fn make_bus() -> Bus<ReplySerial, Vec<u8>> {
+		let serial_port = ReplySerial { written: Vec::new(), response: Vec::new() };
+		Bus::with_buffers_and_baud_rate(serial_port, vec![0; 64], vec![0; 64], 1_000_000)
+	}
+
+	fn segment(prefix: Option<(u16, usize)>, last: bool) -> FastReadSegment {
+		FastReadSegment { total_length: 100, prefix, last }
+	}
+
+	#[test]
+	fn fast_read_segment_continues_byte_stuffing() {
+		// The preceding segments ended with [0xFF, 0xFF], so an error field of 0xFD must be stuffed.
+		let mut bus = make_bus();
+		let_assert!(Ok(()) = bus.write_fast_read_segment(2, 0xFD, segment(Some((0x1234, 2)), true), 1, |buffer| {
+			buffer[0] = 7;
+			Ok(())
+		}));
+		let checksum = bytestuff::stuffed_checksum(0x1234, &mut 2, &[0xFD, 2, 7]);
+		let written = &bus.serial_port.written;
+		assert!(written[..4] == [0xFD, 0xFD, 2, 7]);
+		assert!(written[4..] == checksum.to_le_bytes());
+	}
+
+	#[test]
+	fn fast_read_segment_stuffs_segment_crc() {
+		let mut bus = make_bus();
+		let_assert!(Ok(()) = bus.write_fast_read_segment(1, 0, segment(None, false), 4, |buffer| {
+			buffer.copy_from_slice(&[0xFF, 0xFF, 0xFD, 0x00]);
+			Ok(())
+		}));
+		let written = &bus.serial_port.written;
+		assert!(written[..HEADER_SIZE] == [0xFF, 0xFF, 0xFD, 0x00, 0xFE, 100, 0]);
+		assert!(written[HEADER_SIZE..][..8] == [0x55, 0, 1, 0xFF, 0xFF, 0xFD, 0xFD, 0x00]);
+		let checksum = checksum::calculate_checksum(0, &written[..HEADER_SIZE + 8]);
+		assert!(written[HEADER_SIZE + 8..] == checksum.to_le_bytes());
+	}
+
+	#[test]
+	fn fast_read_segment_checks_total_length() {
+		let mut bus = make_bus();
+		let segment = FastReadSegment { total_length: 0x1_0000, prefix: None, last: false };
+		let_assert!(Err(WriteError::BufferTooSmall(_)) = bus.write_fast_read_segment(1, 0, segment, 0, |_| Ok(())));
+		assert!(bus.serial_port.written.is_empty());
+	}
 
 	#[test]
 	fn test_message_transfer_time() {
@@ -431,14 +948,14 @@ mod test {
 	fn test_static_buffer() {
 		let buffer1 = static_buffer!(128);
 		assert!(buffer1.len() == 128);
-		for i in 0..buffer1.len() {
-			assert!(buffer1[i] == 0);
+		for byte in buffer1.iter() {
+			assert!(*byte == 0);
 		}
 
 		let buffer2 = static_buffer!(64);
 		assert!(buffer2.len() == 64);
-		for i in 0..buffer2.len() {
-			assert!(buffer2[i] == 0);
+		for byte in buffer2.iter() {
+			assert!(*byte == 0);
 		}
 	}
 
@@ -528,11 +1045,37 @@ mod test {
 
 		// Read the corrupt package
 		let deadline = std::time::Instant::now() + Duration::from_secs(1);
-		let result = bus.read_packet_deadline(deadline);
+		let result = bus.read_packet_deadline(deadline, ExpectedPacket::unknown_size(Duration::from_secs(1)));
 		assert!(matches!(result.unwrap_err(), crate::ReadError::BufferFull(_)));
 
 		// Check that the next read works normally again (buffer is partially flushed)
-		let result = bus.read_packet_deadline(deadline);
+		let result = bus.read_packet_deadline(deadline, ExpectedPacket::unknown_size(Duration::from_secs(1)));
 		assert!(result.is_ok());
 	}
+
+	#[test]
+	fn test_timeout_error() {
+		use crate::serial_port::reply::ReplySerial;
+		use assert2::let_assert;
+
+		let serial_port = ReplySerial { written: Vec::new(), response: Vec::new() };
+		let mut bus = Bus::with_buffers(serial_port, vec![0; 64], vec![0; 64]).unwrap();
+		let_assert!(Ok(()) = bus.write_instruction(3, crate::instructions::instruction_id::PING, 0, |_| Ok(())));
+
+		// Nothing received: the expected size is reported.
+		let deadline = std::time::Instant::now();
+		let expected = ExpectedPacket::status(3, Duration::from_millis(5));
+		let_assert!(Err(error) = bus.read_packet_deadline(deadline, expected));
+		assert!(error.is_timeout());
+		let_assert!(crate::ReadError::Timeout { motor_id: Some(3), expected_bytes: 14, waited } = error);
+		assert!(waited == Duration::from_millis(5));
+
+		// Partial message received: the size from the header is reported.
+		bus.serial_port.response = vec![0xFF, 0xFF, 0xFD, 0x00, 3, 20, 0, 0x55];
+		let_assert!(Err(crate::ReadError::Timeout { expected_bytes: 27, .. }) = bus.read_packet_deadline(deadline, expected));
+
+		// Broadcast instructions do not have a single responding motor.
+		let_assert!(Ok(()) = bus.write_instruction(crate::instructions::packet_id::BROADCAST, crate::instructions::instruction_id::PING, 0, |_| Ok(())));
+		let_assert!(Err(crate::ReadError::Timeout { motor_id: None, .. }) = bus.read_packet_deadline(deadline, expected));
+	}
 }
@@ -27,6 +27,22 @@ impl<'a> Packet<'a> {
 		}
 	}
 
+	/// Get the packet as a [`StatusPacket`], without checking the instruction ID.
+	///
+	/// Only use this for packets that have already been checked to be status packets.
+	pub(crate) fn as_status_unchecked(self) -> StatusPacket<'a> {
+		debug_assert_eq!(self.instruction_id(), crate::instructions::instruction_id::STATUS);
+		StatusPacket { packet: self }
+	}
+
+	/// Get the packet as a [`StatusPacket`], or report an invalid instruction ID.
+	pub(crate) fn try_as_status(self) -> Result<StatusPacket<'a>, crate::InvalidInstruction> {
+		self.as_status().ok_or(crate::InvalidInstruction {
+			actual: self.instruction_id(),
+			expected: crate::instructions::instruction_id::STATUS,
+		})
+	}
+
 	/// Get the packet as a [`InstructionPacket`], if it is one.
 	pub fn as_instruction(self) -> Option<InstructionPacket<'a>> {
 		if self.instruction_id() != crate::instructions::instruction_id::STATUS {
@@ -35,6 +51,14 @@ impl<'a> Packet<'a> {
 			None
 		}
 	}
+
+	/// Get the packet as a [`InstructionPacket`], without checking the instruction ID.
+	///
+	/// Only use this for packets that have already been checked to be instruction packets.
+	pub(crate) fn as_instruction_unchecked(self) -> InstructionPacket<'a> {
+		debug_assert_ne!(self.instruction_id(), crate::instructions::instruction_id::STATUS);
+		InstructionPacket { packet: self }
+	}
 }
 
 /// A [`StatusPacket`] contains an error byte and response parameters to an [`InstructionPacket`]
@@ -84,6 +108,13 @@ impl<'a> StatusPacket<'a> {
 		&self.packet.data[super::HEADER_SIZE + 2..]
 	}
 
+	/// The raw (unstuffed) bytes of the packet, from the header prefix up to the CRC.
+	///
+	/// Does not include the CRC itself.
+	pub(crate) fn as_bytes(self) -> &'a [u8] {
+		self.packet.data
+	}
+
 	/// Calculate the size of a (unstuffed) status message with the given number of parameters.
 	pub(crate) const fn message_len(parameters: usize) -> usize {
 		super::HEADER_SIZE + 2 + parameters + 2
@@ -2,9 +2,10 @@ use core::time::Duration;
 #[cfg(feature = "serial2")]
 use std::path::Path;
 
-use crate::bus::{Bus, StatusPacket};
-use crate::instructions::instruction_id;
-use crate::{ReadError, TransferError, WriteError};
+use crate::bus::{Bus, ExpectedPacket, StatusPacket};
+use crate::id_set::IdSet;
+use crate::instructions::packet_id;
+use crate::{ClientError, ErrorContext, ReadError, RetryPolicy, Stats, StatusReturnLevel, TransferError, WriteError};
 
 macro_rules! make_client_struct {
 	($($DefaultSerialPort:ty)?) => {
@@ -23,7 +24,10 @@ macro_rules! make_client_struct {
 			SerialPort: crate::SerialPort,
 			Buffer: AsRef<[u8]> + AsMut<[u8]>,
 		{
-			bus: Bus<SerialPort, Buffer>,
+			pub(crate) bus: Bus<SerialPort, Buffer>,
+			pub(crate) retry_policy: RetryPolicy,
+			pub(crate) sent_at: Option<SerialPort::Instant>,
+			pub(crate) status_return_levels: StatusReturnLevels,
 		}
 	};
 }
@@ -43,6 +47,7 @@ where
 		f.debug_struct("Client")
 			.field("serial_port", &self.bus.serial_port)
 			.field("baud_rate", &self.bus.baud_rate)
+			.field("retry_policy", &self.retry_policy)
 			.finish_non_exhaustive()
 	}
 }
@@ -61,7 +66,7 @@ impl Client<serial2::SerialPort, Vec<u8>> {
 			vec![0; 128],
 			baud_rate
 		);
-		Ok(Self { bus })
+		Ok(Self { bus, retry_policy: RetryPolicy::NEVER, sent_at: None, status_return_levels: StatusReturnLevels::default() })
 	}
 }
 
@@ -86,7 +91,7 @@ where
 			write_buffer,
 			baud_rate,
 		);
-		Ok(Self { bus })
+		Ok(Self { bus, retry_policy: RetryPolicy::NEVER, sent_at: None, status_return_levels: StatusReturnLevels::default() })
 	}
 }
 
@@ -109,7 +114,7 @@ where
 			vec![0; 128],
 			vec![0; 128],
 		)?;
-		Ok(Self { bus })
+		Ok(Self { bus, retry_policy: RetryPolicy::NEVER, sent_at: None, status_return_levels: StatusReturnLevels::default() })
 	}
 }
 
@@ -132,7 +137,7 @@ where
 			read_buffer,
 			write_buffer,
 		)?;
-		Ok(Self { bus })
+		Ok(Self { bus, retry_policy: RetryPolicy::NEVER, sent_at: None, status_return_levels: StatusReturnLevels::default() })
 	}
 
 	/// Get a reference to the underlying serial port.
@@ -163,6 +168,60 @@ where
 		self.bus.set_baud_rate(baud_rate)
 	}
 
+	/// Get the communication statistics of the client.
+	///
+	/// See [`Stats`] for the available counters.
+	pub fn stats(&self) -> &Stats {
+		&self.bus.stats
+	}
+
+	/// Reset all communication statistics of the client to zero.
+	pub fn reset_stats(&mut self) {
+		self.bus.stats = Stats::new();
+	}
+
+	/// Get the retry policy of the client.
+	pub fn retry_policy(&self) -> &RetryPolicy {
+		&self.retry_policy
+	}
+
+	/// Set the retry policy of the client.
+	///
+	/// The retry policy only applies to idempotent instructions.
+	/// See [`RetryPolicy`] for more details.
+	pub fn set_retry_policy(&mut self, retry_policy: RetryPolicy) {
+		self.retry_policy = retry_policy;
+	}
+
+	/// Record the status return level of a motor.
+	///
+	/// Instructions that are not answered at this level, like [`Self::write()`] for [`StatusReturnLevel::ReadOnly`],
+	/// no longer wait for a status packet from the motor.
+	/// Instead, they return a response with the motor ID as soon as the instruction has been sent.
+	///
+	/// This does not change the Status Return Level register of the motor: it only tells the client what to expect.
+	/// If the motor ID is [`crate::instructions::packet_id::BROADCAST`], the level is recorded for all motors.
+	///
+	/// By default, all motors are assumed to use [`StatusReturnLevel::All`].
+	pub fn set_status_return_level(&mut self, motor_id: u8, level: StatusReturnLevel) {
+		self.status_return_levels.set(motor_id, level);
+	}
+
+	/// Get the recorded status return level of a motor.
+	///
+	/// See [`Self::set_status_return_level()`] for more details.
+	pub fn status_return_level(&self, motor_id: u8) -> StatusReturnLevel {
+		self.status_return_levels.get(motor_id)
+	}
+
+	/// Check if a motor is expected to reply to an instruction.
+	///
+	/// Motors never reply to broadcast instructions handled by this function,
+	/// or to instructions that are not answered at their recorded status return level.
+	pub(crate) fn expects_reply(&self, motor_id: u8, instruction_id: u8) -> bool {
+		self.status_return_levels.expects_reply(motor_id, instruction_id)
+	}
+
 	/// Write a raw instruction to a stream, and read a single raw response.
 	///
 	/// This function also checks that the packet ID of the status response matches the one from the instruction.
@@ -187,6 +246,104 @@ where
 		Ok(response)
 	}
 
+	/// Perform an idempotent transfer, retrying it according to the retry policy of the client.
+	///
+	/// This checks the packet ID and the exact number of parameters of the response for every attempt,
+	/// so that an unexpected response can be retried too.
+	///
+	/// Returns the length of the status packet, which can be passed to [`Self::received_status_packet()`].
+	pub(crate) fn transfer_single_with_retry<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		expected_response_parameters: u16,
+		encode_parameters: F,
+	) -> Result<usize, TransferError<SerialPort::Error>>
+	where
+		F: Fn(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		let retry_policy = self.retry_policy;
+		let mut attempts = 0;
+		loop {
+			attempts += 1;
+			let error = match self.transfer_single_attempt(packet_id, instruction_id, parameter_count, expected_response_parameters, &encode_parameters) {
+				Ok(packet_len) => return Ok(packet_len),
+				Err(TransferError::ReadError(e)) => e,
+				Err(e) => return Err(e),
+			};
+
+			if !retry_policy.should_retry(attempts, &error) {
+				if attempts == 1 {
+					return Err(error.into());
+				} else {
+					return Err(TransferError::RetriesFailed { attempts, last_error: error });
+				}
+			}
+			debug!("attempt {} of instruction {:#02X} for motor {} failed, retrying", attempts, instruction_id, packet_id);
+			self.retry_backoff(retry_policy.backoff)?;
+		}
+	}
+
+	/// Perform a single attempt of an idempotent transfer, checking the packet ID and the exact number of parameters of the response.
+	///
+	/// Returns the length of the status packet, which can be passed to [`Self::received_status_packet()`].
+	fn transfer_single_attempt<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		expected_response_parameters: u16,
+		encode_parameters: F,
+	) -> Result<usize, TransferError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		self.write_instruction(packet_id, instruction_id, parameter_count, encode_parameters)?;
+		let packet_len = self.receive_status_response(expected_response_parameters)?;
+		let response = self.received_status_packet(packet_len);
+		crate::error::InvalidPacketId::check(response.packet_id(), packet_id).map_err(crate::ReadError::from)?;
+		crate::InvalidParameterCount::check(response.parameters().len(), expected_response_parameters.into()).map_err(crate::ReadError::from)?;
+		Ok(packet_len)
+	}
+
+	/// Wait for the backoff time of a retry, discarding all data received in the mean time.
+	pub(crate) fn retry_backoff(&mut self, backoff: Duration) -> Result<(), ReadError<SerialPort::Error>> {
+		if backoff.is_zero() {
+			return Ok(());
+		}
+		let deadline = self.serial_port().make_deadline(backoff);
+		self.bus.discard_until(&deadline)
+	}
+
+	/// Run an instruction, attaching context to any error it returns.
+	///
+	/// The time since the instruction was last sent is added to the context, if the serial port can measure it.
+	///
+	/// The result of `run` can not borrow from the client.
+	/// Instructions that return borrowed data should return the length of the received packet instead,
+	/// and get the packet with [`Self::received_status_packet()`] afterwards.
+	pub(crate) fn with_error_context<T>(
+		&mut self,
+		context: ErrorContext,
+		run: impl FnOnce(&mut Self) -> Result<T, TransferError<SerialPort::Error>>,
+	) -> Result<T, ClientError<SerialPort::Error>>
+	{
+		self.sent_at = None;
+		run(self).map_err(|error| self.error_with_context(error, context))
+	}
+
+	/// Attach context to an error of the last sent instruction.
+	///
+	/// The time since the instruction was sent is added to the context, if the serial port can measure it.
+	pub(crate) fn error_with_context(&self, error: impl Into<TransferError<SerialPort::Error>>, context: ErrorContext) -> ClientError<SerialPort::Error> {
+		let elapsed = self.sent_at.as_ref().and_then(|sent_at| self.bus.serial_port.elapsed_since(sent_at));
+		ClientError {
+			error: error.into(),
+			context: Some(ErrorContext { elapsed, ..context }),
+		}
+	}
+
 	/// Write an instruction message to the bus.
 	pub fn write_instruction<F>(
 		&mut self,
@@ -199,39 +356,182 @@ where
 		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
 	{
 		self.bus
-			.write_instruction(packet_id, instruction_id, parameter_count, encode_parameters)
+			.write_instruction(packet_id, instruction_id, parameter_count, encode_parameters)?;
+		self.sent_at = self.bus.serial_port.now();
+		Ok(())
 	}
 
 	/// Read a raw status response from the bus with the given deadline.
 	pub fn read_status_response_timeout(
 		&mut self,
 		timeout: Duration,
-	) -> Result<StatusPacket, ReadError<SerialPort::Error>>
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>>
 	{
-		let deadline = self.serial_port().make_deadline(timeout);
-		let packet = self.bus.read_packet_deadline(deadline)?;
-		let status = match packet.as_status() {
-			Some(status) => status,
-			None => return Err(crate::InvalidInstruction {
-				actual: packet.instruction_id(),
-				expected: instruction_id::STATUS,
-			}.into()),
-		};
+		self.read_status_response_expected(ExpectedPacket::unknown_size(timeout))
+	}
 
+	/// Read a raw status response from the bus, waiting for the given expected packet.
+	fn read_status_response_expected(
+		&mut self,
+		expected: ExpectedPacket,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>>
+	{
+		let status = self.read_status_response_timeout_unchecked(expected)?;
 		crate::MotorError::check(status.error())?;
 		Ok(status)
 	}
 
+	/// Read a raw status response from the bus with the given deadline, without checking the error field.
+	///
+	/// Used for responses that combine the replies of multiple motors,
+	/// where the error field only belongs to the first motor.
+	pub(crate) fn read_status_response_timeout_unchecked(
+		&mut self,
+		expected: ExpectedPacket,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>>
+	{
+		let packet_len = self.receive_status_response_unchecked(expected)?;
+		Ok(self.received_status_packet(packet_len))
+	}
+
+	/// Read a status response into the read buffer, without checking the error field.
+	///
+	/// Returns the length of the status packet, which can be passed to [`Self::received_status_packet()`].
+	/// Unlike [`Self::read_status_response_timeout_unchecked()`], the result does not borrow the client,
+	/// so the caller can still use the client when the read fails.
+	pub(crate) fn receive_status_response_unchecked(
+		&mut self,
+		expected: ExpectedPacket,
+	) -> Result<usize, ReadError<SerialPort::Error>>
+	{
+		let deadline = self.serial_port().make_deadline(expected.timeout);
+		let stuffed_message_len = self.bus.wait_packet_deadline(deadline, expected)?;
+		self.record_latency();
+		let packet_len = self.bus.check_packet(stuffed_message_len)?;
+		self.bus.checked_packet(packet_len).try_as_status()?;
+		Ok(packet_len)
+	}
+
+	/// Read a status response into the read buffer with an automatically calculated timeout.
+	///
+	/// Returns the length of the status packet, which can be passed to [`Self::received_status_packet()`].
+	pub(crate) fn receive_status_response(
+		&mut self,
+		expected_parameters: u16,
+	) -> Result<usize, ReadError<SerialPort::Error>>
+	{
+		let timeout = crate::bus::status_response_timeout(expected_parameters.into(), self.bus.baud_rate);
+		let packet_len = self.receive_status_response_unchecked(ExpectedPacket::status(expected_parameters.into(), timeout))?;
+		crate::MotorError::check(self.received_status_packet(packet_len).error())?;
+		Ok(packet_len)
+	}
+
+	/// Get the status packet that was last received by [`Self::receive_status_response()`].
+	///
+	/// The `packet_len` must be the value returned by that function,
+	/// and no other data may have been read from the bus since.
+	pub(crate) fn received_status_packet(&self, packet_len: usize) -> StatusPacket<'_> {
+		self.bus.checked_packet(packet_len).as_status_unchecked()
+	}
+
+	/// Record the time since the last instruction was sent in the latency histogram.
+	fn record_latency(&mut self) {
+		if let Some(sent_at) = self.sent_at {
+			if let Some(latency) = self.bus.serial_port.elapsed_since(&sent_at) {
+				self.bus.stats.latency.record(latency);
+			}
+		}
+	}
+
 	/// Read a raw status response with an automatically calculated timeout.
 	///
 	/// The read timeout is determined by the expected number of response parameters and the baud rate of the bus.
 	pub fn read_status_response(
 		&mut self,
 		expected_parameters: u16,
-	) -> Result<StatusPacket, ReadError<SerialPort::Error>> {
-		// Official SDK adds a flat 34 milliseconds, so lets just mimick that.
-		let message_size = crate::bus::StatusPacket::message_len(expected_parameters as usize) as u32;
-		let timeout = crate::bus::message_transfer_time(message_size, self.bus.baud_rate) + Duration::from_millis(34);
-		self.read_status_response_timeout(timeout)
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>> {
+		let timeout = crate::bus::status_response_timeout(expected_parameters.into(), self.bus.baud_rate);
+		self.read_status_response_expected(ExpectedPacket::status(expected_parameters.into(), timeout))
+	}
+}
+
+/// The status return levels recorded by [`Client::set_status_return_level()`] or its async equivalent.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StatusReturnLevels {
+	/// The motors that only reply to ping instructions.
+	ping_only: IdSet,
+
+	/// The motors that only reply to ping and read instructions.
+	read_only: IdSet,
+}
+
+impl StatusReturnLevels {
+	/// Record the status return level of a motor, or of all motors for the broadcast ID.
+	pub(crate) fn set(&mut self, motor_id: u8, level: StatusReturnLevel) {
+		if motor_id == packet_id::BROADCAST {
+			*self = Self::default();
+			if level != StatusReturnLevel::All {
+				(0..packet_id::BROADCAST).for_each(|motor_id| self.set(motor_id, level));
+			}
+			return;
+		}
+		self.ping_only.remove(motor_id);
+		self.read_only.remove(motor_id);
+		match level {
+			StatusReturnLevel::PingOnly => self.ping_only.insert(motor_id),
+			StatusReturnLevel::ReadOnly => self.read_only.insert(motor_id),
+			StatusReturnLevel::All => (),
+		}
+	}
+
+	/// Get the recorded status return level of a motor.
+	pub(crate) fn get(&self, motor_id: u8) -> StatusReturnLevel {
+		if self.ping_only.contains(motor_id) {
+			StatusReturnLevel::PingOnly
+		} else if self.read_only.contains(motor_id) {
+			StatusReturnLevel::ReadOnly
+		} else {
+			StatusReturnLevel::All
+		}
+	}
+
+	/// Check if a motor is expected to reply to an instruction.
+	pub(crate) fn expects_reply(&self, motor_id: u8, instruction_id: u8) -> bool {
+		motor_id != packet_id::BROADCAST && self.get(motor_id).replies_to(instruction_id)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::serial_port::reply::ReplySerial;
+	use crate::Client;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn status_return_level_skips_reply() {
+		let_assert!(Ok(mut client) = Client::new(ReplySerial { written: Vec::new(), response: Vec::new() }));
+		client.set_status_return_level(1, StatusReturnLevel::ReadOnly);
+		assert!(client.status_return_level(1) == StatusReturnLevel::ReadOnly);
+		assert!(client.status_return_level(2) == StatusReturnLevel::All);
+
+		let_assert!(Ok(response) = client.write(1, 64, &1u8));
+		assert!(response.motor_id == 1);
+		let_assert!(Ok(response) = client.reboot(1));
+		assert!(response.motor_id == 1);
+		assert!(client.stats().timeouts == 0);
+
+		let_assert!(Err(e) = client.write(2, 64, &1u8));
+		assert!(e.is_timeout());
+
+		client.set_status_return_level(packet_id::BROADCAST, StatusReturnLevel::PingOnly);
+		assert!(client.status_return_level(1) == StatusReturnLevel::PingOnly);
+		assert!(client.status_return_level(2) == StatusReturnLevel::PingOnly);
+		let_assert!(Ok(_) = client.reg_write(2, 64, &1u8));
+		let_assert!(Err(e) = client.ping(2));
+		assert!(e.is_timeout());
+
+		client.set_status_return_level(packet_id::BROADCAST, StatusReturnLevel::All);
+		assert!(client.status_return_level(1) == StatusReturnLevel::All);
 	}
 }
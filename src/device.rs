@@ -1,7 +1,8 @@
 use crate::bus::endian::read_u16_le;
 use crate::instructions::instruction_id;
 use crate::bus::{Bus, InstructionPacket};
-use crate::{InvalidParameterCount, ReadError, WriteError};
+use crate::id_set::IdSet;
+use crate::{InvalidParameterCount, ReadError, TransferError};
 use core::time::Duration;
 
 macro_rules! make_device_struct {
@@ -20,6 +21,22 @@ macro_rules! make_device_struct {
 			Buffer: AsRef<[u8]> + AsMut<[u8]>,
 		{
 			bus: Bus<SerialPort, Buffer>,
+
+			/// The IDs of the device, or `None` to accept instructions for all IDs.
+			ids: Option<IdSet>,
+
+			/// The instructions that are answered with a status packet.
+			status_return_level: StatusReturnLevel,
+
+			/// The time to wait before sending a status packet, in units of 2 microseconds.
+			return_delay_time: u8,
+
+			/// Set if the reply to the last instruction is suppressed,
+			/// because it was a broadcast instruction that does not expect a reply or by the status return level.
+			reply_suppressed: bool,
+
+			/// The time at which the return delay time after the last instruction expires.
+			reply_deadline: Option<SerialPort::Instant>,
 		}
 	};
 }
@@ -57,7 +74,7 @@ impl Device<serial2::SerialPort, Vec<u8>> {
 			vec![0; 128],
 			baud_rate,
 		);
-		Ok(Self { bus })
+		Ok(Self::from_bus(bus))
 	}
 }
 
@@ -82,7 +99,7 @@ where
 			write_buffer,
 			baud_rate,
 		);
-		Ok(Self { bus })
+		Ok(Self::from_bus(bus))
 	}
 }
 
@@ -104,7 +121,7 @@ where
 			vec![0; 128],
 			vec![0; 128],
 		)?;
-		Ok(Self { bus })
+		Ok(Self::from_bus(bus))
 	}
 }
 
@@ -124,7 +141,81 @@ where
 			read_buffer,
 			write_buffer,
 		)?;
-		Ok(Device { bus })
+		Ok(Self::from_bus(bus))
+	}
+
+	/// Create a device that accepts instructions for all IDs.
+	fn from_bus(bus: Bus<SerialPort, Buffer>) -> Self {
+		Self {
+			bus,
+			ids: None,
+			status_return_level: StatusReturnLevel::All,
+			return_delay_time: 0,
+			reply_suppressed: false,
+			reply_deadline: None,
+		}
+	}
+
+	/// Set the ID of the device.
+	///
+	/// After this, [`Self::read()`] skips all instructions that are not sent to this ID or the broadcast ID.
+	pub fn set_id(&mut self, id: u8) {
+		self.set_ids([id]);
+	}
+
+	/// Set multiple IDs for the device, for a device that simulates multiple motors.
+	///
+	/// After this, [`Self::read()`] skips all instructions that are not sent to one of the IDs or the broadcast ID.
+	pub fn set_ids(&mut self, ids: impl IntoIterator<Item = u8>) {
+		let mut set = IdSet::default();
+		for id in ids {
+			set.insert(id);
+		}
+		self.ids = Some(set);
+	}
+
+	/// Accept instructions for all IDs.
+	///
+	/// This is the default for a new device.
+	pub fn accept_all_ids(&mut self) {
+		self.ids = None;
+	}
+
+	/// Check if the device accepts instructions sent to the given ID.
+	///
+	/// Instructions sent to the broadcast ID are always accepted.
+	pub fn accepts_id(&self, id: u8) -> bool {
+		id == crate::instructions::packet_id::BROADCAST || self.ids.as_ref().is_none_or(|ids| ids.contains(id))
+	}
+
+	/// Set the status return level of the device.
+	///
+	/// Status packets for instructions that are not answered at this level are silently dropped by [`Self::write_status()`],
+	/// just like a motor does according to its Status Return Level register.
+	///
+	/// The default is [`StatusReturnLevel::All`].
+	pub fn set_status_return_level(&mut self, level: StatusReturnLevel) {
+		self.status_return_level = level;
+	}
+
+	/// Get the status return level of the device.
+	pub fn status_return_level(&self) -> StatusReturnLevel {
+		self.status_return_level
+	}
+
+	/// Set the return delay time of the device, in units of 2 microseconds.
+	///
+	/// Status packets are not sent until this time has passed since the instruction was received,
+	/// just like a motor does according to its Return Delay Time register.
+	///
+	/// The default is 0, which means status packets are sent without delay.
+	pub fn set_return_delay_time(&mut self, return_delay_time: u8) {
+		self.return_delay_time = return_delay_time;
+	}
+
+	/// Get the return delay time of the device, in units of 2 microseconds.
+	pub fn return_delay_time(&self) -> u8 {
+		self.return_delay_time
 	}
 
 	/// Get a reference to the underlying serial port.
@@ -137,6 +228,12 @@ where
 		&self.bus.serial_port
 	}
 
+	/// Get a mutable reference to the underlying serial port, to script its input in tests.
+	#[cfg(test)]
+	pub(crate) fn serial_port_mut(&mut self) -> &mut SerialPort {
+		&mut self.bus.serial_port
+	}
+
 	/// Consume this device object to get ownership of the serial port.
 	///
 	/// This discards any data in internal the read buffer of the device object.
@@ -150,6 +247,19 @@ where
 		self.bus.baud_rate
 	}
 
+	/// Get the communication statistics of the device.
+	///
+	/// See [`Stats`][crate::Stats] for the available counters.
+	/// The latency histogram is only used by clients, and is always empty for a device.
+	pub fn stats(&self) -> &crate::Stats {
+		&self.bus.stats
+	}
+
+	/// Reset all communication statistics of the device to zero.
+	pub fn reset_stats(&mut self) {
+		self.bus.stats = crate::Stats::new();
+	}
+
 	/// Set the baud rate of the underlying serial port.
 	pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), SerialPort::Error> {
 		self.bus.set_baud_rate(baud_rate)?;
@@ -178,18 +288,45 @@ where
 	}
 
 	/// Write a status message to the device.
+	///
+	/// If the last instruction read by the device was a broadcast instruction that does not expect a reply,
+	/// or if the reply is suppressed by the [status return level][Self::set_status_return_level()], nothing is written.
+	///
+	/// The status packet is not sent before the [return delay time][Self::set_return_delay_time()] has passed.
 	pub fn write_status<F>(
 		&mut self,
 		packet_id: u8,
 		error: u8,
 		parameter_count: usize,
 		encode_parameters: F,
-	) -> Result<(), WriteError<SerialPort::Error>>
+	) -> Result<(), TransferError<SerialPort::Error>>
 	where
 		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
 	{
-		self.bus
-			.write_status(packet_id, error, parameter_count, encode_parameters)
+		if self.reply_suppressed {
+			trace!("status packet for motor {} suppressed", packet_id);
+			return Ok(());
+		}
+		self.wait_return_delay()?;
+		self.bus.write_status(packet_id, error, parameter_count, encode_parameters)?;
+		Ok(())
+	}
+
+	/// Delay the next status packet by `delay` on top of the return delay time.
+	#[cfg(feature = "alloc")]
+	pub(crate) fn delay_reply(&mut self, delay: Duration) {
+		let return_delay = Duration::from_micros(u64::from(self.return_delay_time) * 2);
+		self.reply_deadline = Some(self.serial_port().make_deadline(return_delay + delay));
+	}
+
+	/// Wait until the return delay time after the last instruction has passed.
+	///
+	/// Any data received in the mean time is discarded, just like it would be when writing the status packet.
+	fn wait_return_delay(&mut self) -> Result<(), ReadError<SerialPort::Error>> {
+		match self.reply_deadline.take() {
+			Some(deadline) => self.bus.discard_until(&deadline),
+			None => Ok(()),
+		}
 	}
 
 	/// Write an empty status message with an error code.
@@ -197,7 +334,7 @@ where
 		&mut self,
 		packet_id: u8,
 		error: u8,
-	) -> Result<(), WriteError<SerialPort::Error>> {
+	) -> Result<(), TransferError<SerialPort::Error>> {
 		self.write_status(packet_id, error, 0, |_| Ok(()))
 	}
 
@@ -205,10 +342,223 @@ where
 	pub fn write_status_ok(
 		&mut self,
 		packet_id: u8,
-	) -> Result<(), WriteError<SerialPort::Error>> {
+	) -> Result<(), TransferError<SerialPort::Error>> {
 		self.write_status(packet_id, 0, 0, |_| Ok(()))
 	}
 
+	/// Reply to a Sync Read instruction, after the devices listed before this one have replied.
+	///
+	/// All devices listed in a Sync Read instruction reply with their own status packet, in the order of the IDs in the instruction.
+	/// This function looks up the position of `motor_id` in the instruction,
+	/// watches the bus for the status packets of the preceding devices and then writes a status packet with `error` and `data`.
+	///
+	/// The device waits for each preceding device for at most `timeout` plus the transfer time of its status packet.
+	/// If a preceding device does not reply in time, it is assumed to be missing from the bus and the device stops waiting for it.
+	///
+	/// If the instruction is not a Sync Read instruction or if `motor_id` is not listed in it, nothing is written.
+	pub fn reply_sync_read<T: AsRef<[u8]>>(
+		&mut self,
+		motor_id: u8,
+		instruction: &Instruction<T>,
+		error: u8,
+		data: &[u8],
+		timeout: Duration,
+	) -> Result<(), TransferError<SerialPort::Error>> {
+		let Instructions::SyncRead { length, ids, .. } = &instruction.instruction else {
+			return Ok(());
+		};
+		let ids = ids.as_ref();
+		let Some(index) = ids.iter().position(|&id| id == motor_id) else {
+			return Ok(());
+		};
+		let preceding = ids[..index].iter().map(|&id| (id, usize::from(*length)));
+		self.reply_after_preceding(motor_id, preceding, error, data, timeout)
+	}
+
+	/// Reply to a Bulk Read instruction, after the devices listed before this one have replied.
+	///
+	/// This works like [`Self::reply_sync_read()`],
+	/// except that each preceding device may read a different number of bytes, which is taken into account for the transfer time of its status packet.
+	///
+	/// If the instruction is not a Bulk Read instruction or if `motor_id` is not listed in it, nothing is written.
+	pub fn reply_bulk_read<T: AsRef<[u8]>>(
+		&mut self,
+		motor_id: u8,
+		instruction: &Instruction<T>,
+		error: u8,
+		data: &[u8],
+		timeout: Duration,
+	) -> Result<(), TransferError<SerialPort::Error>> {
+		let Instructions::BulkRead { parameters } = &instruction.instruction else {
+			return Ok(());
+		};
+		let reads = parameters.as_ref().chunks_exact(5);
+		let Some(index) = reads.clone().position(|read| read[0] == motor_id) else {
+			return Ok(());
+		};
+		let preceding = reads.take(index).map(|read| (read[0], usize::from(read_u16_le(&read[3..]))));
+		self.reply_after_preceding(motor_id, preceding, error, data, timeout)
+	}
+
+	/// Wait for the status packets of the preceding devices in a sync or bulk read, and then write a status packet.
+	///
+	/// The `preceding` iterator yields the ID of each preceding device and the number of data bytes in its status packet.
+	fn reply_after_preceding(
+		&mut self,
+		motor_id: u8,
+		preceding: impl Iterator<Item = (u8, usize)>,
+		error: u8,
+		data: &[u8],
+		timeout: Duration,
+	) -> Result<(), TransferError<SerialPort::Error>> {
+		if self.reply_suppressed {
+			return Ok(());
+		}
+		// Status packets can arrive while waiting for an earlier device that is missing, so remember all of them.
+		let mut replied = IdSet::default();
+		for (preceding_id, length) in preceding {
+			if replied.contains(preceding_id) {
+				continue;
+			}
+			let message_len = crate::bus::StatusPacket::message_len(length) as u32;
+			let timeout = timeout + crate::bus::message_transfer_time(message_len, self.baud_rate());
+			let deadline = self.serial_port().make_deadline(timeout);
+			loop {
+				match self.bus.read_packet_deadline(deadline, crate::bus::ExpectedPacket::status(length, timeout)) {
+					Ok(packet) => {
+						let Some(status) = packet.as_status() else {
+							continue;
+						};
+						replied.insert(status.packet_id());
+						if status.packet_id() == preceding_id {
+							break;
+						}
+					},
+					Err(ReadError::Timeout { .. }) => {
+						debug!("no status packet from motor {} received, assuming it is missing", preceding_id);
+						break;
+					},
+					Err(ReadError::InvalidMessage(e)) => {
+						debug!("ignoring invalid packet while waiting for motor {}: {}", preceding_id, e);
+					},
+					Err(e) => return Err(e.into()),
+				}
+			}
+		}
+		self.write_status(motor_id, error, data.len(), |buffer| {
+			buffer.copy_from_slice(data);
+			Ok(())
+		})?;
+		Ok(())
+	}
+
+	/// Write the segment of this device in the combined response to a Fast Sync Read instruction.
+	///
+	/// All devices listed in a Fast Sync Read instruction reply together with a single status packet.
+	/// Each device appends its own segment, consisting of the error field, its ID, `length` bytes of data and a running CRC.
+	///
+	/// The first device in `ids` writes the header of the status packet followed by its segment.
+	/// Every other device first waits for the segments of the preceding devices on the bus (for at most `timeout`),
+	/// and continues the CRC of those bytes in its own segment.
+	///
+	/// If `motor_id` is not in `ids`, the device is not addressed by the instruction and nothing is written.
+	pub fn write_fast_sync_read_segment<F>(
+		&mut self,
+		motor_id: u8,
+		ids: &[u8],
+		length: u16,
+		error: u8,
+		timeout: Duration,
+		encode_parameters: F,
+	) -> Result<(), TransferError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		let Some(index) = ids.iter().position(|&id| id == motor_id) else {
+			return Ok(());
+		};
+		let segment_len = usize::from(length) + 4;
+		let layout = FastReadLayout {
+			preceding_len: index * segment_len,
+			total_len: ids.len() * segment_len,
+			count: length,
+		};
+		self.write_fast_read_segment(motor_id, layout, error, timeout, encode_parameters)
+	}
+
+	/// Write the segment of this device in the combined response to a Fast Bulk Read instruction.
+	///
+	/// This works like [`Self::write_fast_sync_read_segment()`],
+	/// except that the segments are taken from the raw `parameters` of a [`Instructions::FastBulkRead`].
+	/// Each device may read a different number of bytes, so the size of each segment is taken from the parameters.
+	///
+	/// If `motor_id` is not in the parameters, the device is not addressed by the instruction and nothing is written.
+	pub fn write_fast_bulk_read_segment<F>(
+		&mut self,
+		motor_id: u8,
+		parameters: &[u8],
+		error: u8,
+		timeout: Duration,
+		encode_parameters: F,
+	) -> Result<(), TransferError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		let mut layout = None;
+		let mut total_len = 0;
+		for read in parameters.chunks_exact(5) {
+			let count = read_u16_le(&read[3..]);
+			if read[0] == motor_id {
+				layout = Some(FastReadLayout {
+					preceding_len: total_len,
+					total_len: 0,
+					count,
+				});
+			}
+			total_len += usize::from(count) + 4;
+		}
+		let Some(mut layout) = layout else {
+			return Ok(());
+		};
+		layout.total_len = total_len;
+		self.write_fast_read_segment(motor_id, layout, error, timeout, encode_parameters)
+	}
+
+	fn write_fast_read_segment<F>(
+		&mut self,
+		motor_id: u8,
+		layout: FastReadLayout,
+		error: u8,
+		timeout: Duration,
+		encode_parameters: F,
+	) -> Result<(), TransferError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		if self.reply_suppressed {
+			return Ok(());
+		}
+		let prefix = if layout.preceding_len == 0 {
+			self.wait_return_delay()?;
+			None
+		} else {
+			let deadline = self.serial_port().make_deadline(timeout);
+			// The preceding bytes include the header and the instruction field of the status packet.
+			let prefix_len = crate::bus::HEADER_SIZE + 1 + layout.preceding_len;
+			Some(self.bus.read_status_prefix_deadline(prefix_len, deadline, timeout)?)
+		};
+		// The length field counts the instruction field and all segments, including the final CRC.
+		let total_length = 1 + layout.total_len;
+		let segment_len = usize::from(layout.count) + 4;
+		let segment = crate::bus::FastReadSegment {
+			total_length,
+			prefix,
+			last: layout.preceding_len + segment_len == layout.total_len,
+		};
+		self.bus.write_fast_read_segment(motor_id, error, segment, layout.count.into(), encode_parameters)?;
+		Ok(())
+	}
+
 	/// Read a single [`InstructionPacket`].
 	pub fn read_raw_instruction_timeout(
 		&mut self,
@@ -217,16 +567,99 @@ where
 	{
 		let deadline = SerialPort::make_deadline(self.serial_port(), timeout);
 		loop {
-			// SAFETY: This is a workaround for a limitation in the borrow checker.
-			// Even though `packet` is dropped inside the loop body, it has lifetime 'a, which outlives the current function.
-			// So each loop iteration tries to borrow the same field mutably, with the a lifetime that outlives the current function.
-			// Borrow checker says no.
-			// TODO: Remove this workaround when the borrow checker can validate this.
-			let bus: &mut Bus<SerialPort, Buffer> = unsafe { &mut * (&mut self.bus as *mut _) };
-			let packet = bus.read_packet_deadline(deadline)?;
-			if let Some(instruction) = packet.as_instruction() {
-				return Ok(instruction)
+			// Only keep the length of the packet while updating the device state,
+			// and borrow the packet from the read buffer again when returning it.
+			let stuffed_message_len = self.bus.wait_packet_deadline(deadline, crate::bus::ExpectedPacket::unknown_size(timeout))?;
+			let packet_len = self.bus.check_packet(stuffed_message_len)?;
+			let Some(instruction) = self.bus.checked_packet(packet_len).as_instruction() else {
+				continue;
+			};
+			let packet_id = instruction.packet_id();
+			let instruction_id = instruction.instruction_id();
+			if !self.accepts_id(packet_id) {
+				trace!("skipping instruction for motor {}", packet_id);
+				continue;
 			}
+			self.reply_suppressed = !expects_reply(packet_id, instruction_id) || !self.status_return_level.replies_to(instruction_id);
+			self.reply_deadline = match self.return_delay_time {
+				0 => None,
+				delay => Some(self.serial_port().make_deadline(Duration::from_micros(u64::from(delay) * 2))),
+			};
+			return Ok(self.bus.checked_packet(packet_len).as_instruction_unchecked());
+		}
+	}
+}
+
+/// Check if an instruction packet expects a status packet in reply.
+fn expects_reply(packet_id: u8, instruction_id: u8) -> bool {
+	packet_id != crate::instructions::packet_id::BROADCAST || matches!(
+		instruction_id,
+		instruction_id::PING
+			| instruction_id::SYNC_READ
+			| instruction_id::FAST_SYNC_READ
+			| instruction_id::BULK_READ
+			| instruction_id::FAST_BULK_READ
+	)
+}
+
+/// The position of the segment of a device in a combined fast read response.
+#[derive(Debug, Copy, Clone)]
+struct FastReadLayout {
+	/// The total size of the segments before the segment of the device.
+	preceding_len: usize,
+
+	/// The total size of all segments.
+	total_len: usize,
+
+	/// The number of data bytes in the segment of the device.
+	count: u16,
+}
+
+/// The [Status Return Level](https://emanual.robotis.com/docs/en/dxl/x/xm430-w350/#status-return-level) of a device.
+///
+/// This determines which instructions are answered with a status packet.
+/// Instructions sent to the broadcast ID are never answered, except for ping, sync read and bulk read instructions.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub enum StatusReturnLevel {
+	/// Only reply to ping instructions.
+	PingOnly = 0,
+
+	/// Only reply to ping and read instructions, including sync and bulk reads.
+	ReadOnly = 1,
+
+	/// Reply to all instructions.
+	#[default]
+	All = 2,
+}
+
+impl StatusReturnLevel {
+	/// Get the status return level for the value of the Status Return Level register.
+	///
+	/// Returns `None` if the value is not a valid status return level.
+	pub fn from_u8(value: u8) -> Option<Self> {
+		match value {
+			0 => Some(Self::PingOnly),
+			1 => Some(Self::ReadOnly),
+			2 => Some(Self::All),
+			_ => None,
+		}
+	}
+
+	/// Check if an instruction is answered with a status packet at this level.
+	pub fn replies_to(self, instruction_id: u8) -> bool {
+		match self {
+			Self::PingOnly => instruction_id == instruction_id::PING,
+			Self::ReadOnly => matches!(
+				instruction_id,
+				instruction_id::PING
+					| instruction_id::READ
+					| instruction_id::SYNC_READ
+					| instruction_id::FAST_SYNC_READ
+					| instruction_id::BULK_READ
+					| instruction_id::FAST_BULK_READ
+			),
+			Self::All => true,
 		}
 	}
 }
@@ -257,6 +690,17 @@ pub enum Clear {
 	Reserved(u8),
 }
 
+/// The options for the [Control Table Backup](https://emanual.robotis.com/docs/en/dxl/protocol2/#control-table-backup-0x20) instruction.
+#[derive(Debug)]
+pub enum ControlTableBackup {
+	/// Store the current control table in the backup area.
+	Backup,
+	/// Restore the control table from the backup area.
+	Restore,
+	/// Reserved for future use.
+	Reserved(u8),
+}
+
 /// [`InstructionPacket`] can be converted into an [`Instruction`] with borrowed or owned data.
 /// It contains the ID and parameters.
 /// The owned data variant requires the `alloc` feature.
@@ -269,6 +713,18 @@ pub struct Instruction<T> {
 	pub instruction: Instructions<T>,
 }
 
+impl<T> Instruction<T> {
+	/// Check if the device should reply to the instruction with a status packet.
+	///
+	/// Devices always reply to instructions sent to their own ID.
+	/// Broadcast instructions are only answered for ping, and by the devices listed in a sync or bulk read.
+	/// Use [`Device::reply_sync_read()`] and [`Device::reply_bulk_read()`] to answer sync and bulk reads,
+	/// and [`Device::write_fast_sync_read_segment()`] and [`Device::write_fast_bulk_read_segment()`] to answer fast sync and bulk reads.
+	pub fn expects_reply(&self) -> bool {
+		expects_reply(self.id, self.instruction.instruction_id())
+	}
+}
+
 /// Instructions as defined in the [Dynamixel Protocol 2.0](https://emanual.robotis.com/docs/en/dxl/protocol2/#instruction-details).
 ///
 /// The parameters are stored as a `&[u8]` slice or a `Vec<u8>`.
@@ -283,13 +739,40 @@ pub enum Instructions<T> {
 	FactoryReset(FactoryReset),
 	Reboot,
 	Clear(Clear),
+	ControlTableBackup(ControlTableBackup),
 	SyncRead { address: u16, length: u16, ids: T },
 	SyncWrite { address: u16, length: u16, parameters: T },
+	FastSyncRead { address: u16, length: u16, ids: T },
 	BulkRead { parameters: T },
 	BulkWrite { parameters: T },
+	FastBulkRead { parameters: T },
 	Unknown { instruction: u8, parameters: T },
 }
 
+impl<T> Instructions<T> {
+	/// Get the raw instruction ID of the instruction.
+	pub fn instruction_id(&self) -> u8 {
+		match self {
+			Self::Ping => instruction_id::PING,
+			Self::Read { .. } => instruction_id::READ,
+			Self::Write { .. } => instruction_id::WRITE,
+			Self::RegWrite { .. } => instruction_id::REG_WRITE,
+			Self::Action => instruction_id::ACTION,
+			Self::FactoryReset(_) => instruction_id::FACTORY_RESET,
+			Self::Reboot => instruction_id::REBOOT,
+			Self::Clear(_) => instruction_id::CLEAR,
+			Self::ControlTableBackup(_) => instruction_id::CONTROL_TABLE_BACKUP,
+			Self::SyncRead { .. } => instruction_id::SYNC_READ,
+			Self::SyncWrite { .. } => instruction_id::SYNC_WRITE,
+			Self::FastSyncRead { .. } => instruction_id::FAST_SYNC_READ,
+			Self::BulkRead { .. } => instruction_id::BULK_READ,
+			Self::BulkWrite { .. } => instruction_id::BULK_WRITE,
+			Self::FastBulkRead { .. } => instruction_id::FAST_BULK_READ,
+			Self::Unknown { instruction, .. } => *instruction,
+		}
+	}
+}
+
 impl<'a> TryFrom<InstructionPacket<'a>> for Instruction<&'a [u8]> {
 	type Error = InvalidParameterCount;
 
@@ -339,7 +822,14 @@ impl<'a> TryFrom<InstructionPacket<'a>> for Instruction<&'a [u8]> {
 					p => Instructions::Clear(Clear::Reserved(p)),
 				}
 			},
-			// todo: instruction_id::ControlTableBackup
+			instruction_id::CONTROL_TABLE_BACKUP => {
+				InvalidParameterCount::check_min(parameters.len(), 1)?;
+				match parameters[0] {
+					0x01 => Instructions::ControlTableBackup(ControlTableBackup::Backup),
+					0x02 => Instructions::ControlTableBackup(ControlTableBackup::Restore),
+					p => Instructions::ControlTableBackup(ControlTableBackup::Reserved(p)),
+				}
+			},
 			instruction_id::SYNC_READ => {
 				InvalidParameterCount::check_min(parameters.len(), 4)?;
 				Instructions::SyncRead {
@@ -356,8 +846,17 @@ impl<'a> TryFrom<InstructionPacket<'a>> for Instruction<&'a [u8]> {
 					parameters: &parameters[4..],
 				}
 			},
+			instruction_id::FAST_SYNC_READ => {
+				InvalidParameterCount::check_min(parameters.len(), 4)?;
+				Instructions::FastSyncRead {
+					address: read_u16_le(&parameters[..2]),
+					length: read_u16_le(&parameters[2..4]),
+					ids: &parameters[4..],
+				}
+			},
 			instruction_id::BULK_READ => Instructions::BulkRead { parameters },
 			instruction_id::BULK_WRITE => Instructions::BulkWrite { parameters },
+			instruction_id::FAST_BULK_READ => Instructions::FastBulkRead { parameters },
 
 			instruction => Instructions::Unknown { instruction, parameters },
 		};
@@ -388,6 +887,7 @@ impl<'a> TryFrom<InstructionPacket<'a>> for Instruction<Vec<u8>> {
 			Instructions::FactoryReset(f) => Instructions::FactoryReset(f),
 			Instructions::Reboot => Instructions::Reboot,
 			Instructions::Clear(c) => Instructions::Clear(c),
+			Instructions::ControlTableBackup(c) => Instructions::ControlTableBackup(c),
 			Instructions::SyncRead { address, length, ids } => Instructions::SyncRead {
 				address,
 				length,
@@ -402,12 +902,20 @@ impl<'a> TryFrom<InstructionPacket<'a>> for Instruction<Vec<u8>> {
 				length,
 				parameters: parameters.to_owned(),
 			},
+			Instructions::FastSyncRead { address, length, ids } => Instructions::FastSyncRead {
+				address,
+				length,
+				ids: ids.to_owned(),
+			},
 			Instructions::BulkRead { parameters } => Instructions::BulkRead {
 				parameters: parameters.to_owned(),
 			},
 			Instructions::BulkWrite { parameters } => Instructions::BulkRead {
 				parameters: parameters.to_owned(),
 			},
+			Instructions::FastBulkRead { parameters } => Instructions::FastBulkRead {
+				parameters: parameters.to_owned(),
+			},
 			Instructions::Unknown { instruction, parameters } => Instructions::Unknown {
 				instruction,
 				parameters: parameters.to_owned(),
@@ -416,3 +924,164 @@ impl<'a> TryFrom<InstructionPacket<'a>> for Instruction<Vec<u8>> {
 		Ok(Instruction { id, instruction })
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::instructions::packet_id::BROADCAST;
+	use crate::serial_port::reply::ReplySerial;
+	use assert2::{assert, let_assert};
+
+	/// Encode an instruction packet without byte stuffing.
+	fn instruction(id: u8, instruction_id: u8, parameters: &[u8]) -> Vec<u8> {
+		let length = (parameters.len() + 3) as u16;
+		let mut packet = vec![0xFF, 0xFF, 0xFD, 0x00, id, length as u8, (length >> 8) as u8, instruction_id];
+		packet.extend_from_slice(parameters);
+		let checksum = crate::checksum::calculate_checksum(0, &packet);
+		packet.extend_from_slice(&checksum.to_le_bytes());
+		packet
+	}
+
+	fn device(input: Vec<u8>) -> Device<ReplySerial, Vec<u8>> {
+		let_assert!(Ok(device) = Device::new(ReplySerial { written: Vec::new(), response: input }));
+		device
+	}
+
+	#[test]
+	fn read_skips_other_ids() {
+		let input = [
+			instruction(2, instruction_id::PING, &[]),
+			instruction(3, instruction_id::PING, &[]),
+			instruction(BROADCAST, instruction_id::PING, &[]),
+		].concat();
+		let mut device = device(input);
+		device.set_ids([1, 3]);
+		assert!(!device.accepts_id(2));
+
+		let_assert!(Ok(packet) = device.read(Duration::from_millis(10)));
+		assert!(packet.id == 3);
+		assert!(packet.expects_reply());
+		let_assert!(Ok(packet) = device.read(Duration::from_millis(10)));
+		assert!(packet.id == BROADCAST);
+		assert!(packet.expects_reply());
+		let_assert!(Err(ReadError::Timeout { .. }) = device.read(Duration::from_millis(10)));
+	}
+
+	#[test]
+	fn drop_reply_to_broadcast_write() {
+		let mut device = device(instruction(BROADCAST, instruction_id::WRITE, &[64, 0, 1]));
+		device.set_id(1);
+		let_assert!(Ok(packet) = device.read(Duration::from_millis(10)));
+		assert!(!packet.expects_reply());
+		assert!(packet.instruction.instruction_id() == instruction_id::WRITE);
+		let_assert!(Ok(()) = device.write_status_ok(1));
+		assert!(device.serial_port().written.is_empty());
+
+		device.serial_port_mut().response = instruction(1, instruction_id::WRITE, &[64, 0, 1]);
+		let_assert!(Ok(packet) = device.read(Duration::from_millis(10)));
+		assert!(packet.expects_reply());
+		let_assert!(Ok(()) = device.write_status_ok(1));
+	}
+
+	#[test]
+	fn reply_sync_read_after_preceding_devices() {
+		let input = [
+			instruction(BROADCAST, instruction_id::SYNC_READ, &[132, 0, 1, 0, 1, 2, 3]),
+			instruction(1, instruction_id::STATUS, &[0, 10]),
+			instruction(2, instruction_id::STATUS, &[0, 20]),
+		].concat();
+		let mut device = device(input);
+		device.set_id(3);
+		let_assert!(Ok(packet) = device.read_owned(Duration::from_millis(10)));
+		let_assert!(Ok(()) = device.reply_sync_read(3, &packet, 0, &[30], Duration::from_millis(10)));
+		assert!(device.serial_port().written == instruction(3, instruction_id::STATUS, &[0, 30]));
+
+		// Device 4 is not in the ID list.
+		device.serial_port_mut().written.clear();
+		let_assert!(Ok(()) = device.reply_sync_read(4, &packet, 0, &[40], Duration::from_millis(10)));
+		assert!(device.serial_port().written.is_empty());
+	}
+
+	#[test]
+	fn reply_bulk_read_skips_missing_device() {
+		let input = [
+			instruction(BROADCAST, instruction_id::BULK_READ, &[1, 132, 0, 4, 0, 2, 132, 0, 1, 0, 3, 64, 0, 1, 0]),
+			instruction(2, instruction_id::STATUS, &[0, 20]),
+		].concat();
+		let mut device = device(input);
+		device.set_id(3);
+		let_assert!(Ok(packet) = device.read_owned(Duration::from_millis(10)));
+		let_assert!(Ok(()) = device.reply_bulk_read(3, &packet, 0, &[30], Duration::from_millis(10)));
+		assert!(device.serial_port().written == instruction(3, instruction_id::STATUS, &[0, 30]));
+		assert!(device.stats().timeouts == 1);
+	}
+
+	#[test]
+	fn status_return_level_suppresses_replies() {
+		let input = [
+			instruction(1, instruction_id::WRITE, &[64, 0, 1]),
+			instruction(1, instruction_id::READ, &[64, 0, 1, 0]),
+			instruction(1, instruction_id::PING, &[]),
+		].concat();
+		let mut device = device(input);
+		device.set_status_return_level(StatusReturnLevel::PingOnly);
+
+		let_assert!(Ok(_) = device.read(Duration::from_millis(10)));
+		let_assert!(Ok(()) = device.write_status_ok(1));
+		let_assert!(Ok(_) = device.read(Duration::from_millis(10)));
+		let_assert!(Ok(()) = device.write_status(1, 0, 1, |buffer| {
+			buffer[0] = 1;
+			Ok(())
+		}));
+		assert!(device.serial_port().written.is_empty());
+		let_assert!(Ok(_) = device.read(Duration::from_millis(10)));
+		let_assert!(Ok(()) = device.write_status_ok(1));
+		assert!(device.serial_port().written == instruction(1, instruction_id::STATUS, &[0]));
+
+		assert!(StatusReturnLevel::from_u8(1) == Some(StatusReturnLevel::ReadOnly));
+		assert!(StatusReturnLevel::from_u8(3).is_none());
+		assert!(StatusReturnLevel::ReadOnly.replies_to(instruction_id::SYNC_READ));
+		assert!(!StatusReturnLevel::ReadOnly.replies_to(instruction_id::WRITE));
+	}
+
+	#[test]
+	fn return_delay_time_discards_input() {
+		let mut device = device(instruction(1, instruction_id::PING, &[]));
+		device.set_return_delay_time(10);
+		let_assert!(Ok(_) = device.read(Duration::from_millis(10)));
+		let received = device.stats().bytes_received;
+
+		device.serial_port_mut().response = vec![0xAA; 5];
+		let_assert!(Ok(()) = device.write_status_ok(1));
+		assert!(device.serial_port().response.is_empty());
+		assert!(device.serial_port().written == instruction(1, instruction_id::STATUS, &[0]));
+		assert!(device.stats().bytes_received == received + 5);
+		assert!(device.stats().garbage_bytes == 5);
+		assert!(device.stats().timeouts == 0);
+	}
+
+	#[test]
+	#[cfg(feature = "testing")]
+	fn return_delay_time() {
+		use crate::testing::MockSerial;
+		use crate::SerialPort;
+
+		let mut client = MockSerial::new("client");
+		let mut serial_port = MockSerial::new("device");
+		MockSerial::connect(&mut [&mut client, &mut serial_port]);
+		let_assert!(Ok(mut device) = Device::new(serial_port));
+		device.set_return_delay_time(254);
+		assert!(device.return_delay_time() == 254);
+
+		let start = std::time::Instant::now();
+		let_assert!(Ok(()) = client.write_all(&instruction(1, instruction_id::PING, &[])));
+		let_assert!(Ok(_) = device.read(Duration::from_millis(10)));
+		let_assert!(Ok(()) = device.write_status_ok(1));
+		assert!(start.elapsed() >= Duration::from_micros(508));
+
+		let mut buffer = [0; 32];
+		let deadline = client.make_deadline(Duration::from_millis(10));
+		let_assert!(Ok(len) = client.read(&mut buffer, &deadline));
+		assert!(buffer[..len] == instruction(1, instruction_id::STATUS, &[0]));
+	}
+}
@@ -0,0 +1,712 @@
+//! High level device implementation backed by a control table.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::time::Duration;
+
+use crate::instructions::packet_id::BROADCAST;
+use crate::{Clear, ControlTableBackup, Device, FactoryReset, Instruction, Instructions, MotorErrorKind, TransferError};
+
+/// How long to wait for each device listed before this one in a sync or bulk read, on top of the transfer time of its reply.
+///
+/// This is the same margin as used by the official SDK for status packets.
+const PRECEDING_REPLY_TIMEOUT: Duration = Duration::from_millis(34);
+
+/// The size of a status packet in reply to a ping, used to delay the reply to a broadcast ping.
+const PING_REPLY_SIZE: u32 = 14;
+
+/// The control table of a device served by a [`DeviceServer`].
+///
+/// Errors are reported to the client in the status packet.
+/// Use [`MotorErrorKind::AccessError`] for registers that can not be accessed,
+/// and [`MotorErrorKind::DataRangeError`] for values that are out of range.
+pub trait ControlTable {
+	/// The model number reported in response to a ping.
+	fn model_number(&self) -> u16;
+
+	/// The firmware version reported in response to a ping.
+	fn firmware_version(&self) -> u8;
+
+	/// Read `buffer.len()` bytes starting at `address`.
+	fn read(&mut self, address: u16, buffer: &mut [u8]) -> Result<(), MotorErrorKind>;
+
+	/// Write `data` starting at `address`.
+	fn write(&mut self, address: u16, data: &[u8]) -> Result<(), MotorErrorKind>;
+
+	/// Reset the control table to its factory defaults.
+	fn factory_reset(&mut self, kind: FactoryReset) -> Result<(), MotorErrorKind>;
+
+	/// Handle a Clear instruction.
+	///
+	/// The default implementation reports an instruction error.
+	fn clear(&mut self, kind: Clear) -> Result<(), MotorErrorKind> {
+		let _ = kind;
+		Err(MotorErrorKind::InstructionError)
+	}
+
+	/// Store the current control table in the backup area.
+	///
+	/// The default implementation reports an instruction error.
+	fn backup(&mut self) -> Result<(), MotorErrorKind> {
+		Err(MotorErrorKind::InstructionError)
+	}
+
+	/// Restore the control table from the backup area.
+	///
+	/// The default implementation reports an instruction error.
+	fn restore(&mut self) -> Result<(), MotorErrorKind> {
+		Err(MotorErrorKind::InstructionError)
+	}
+
+	/// Handle a reboot of the device.
+	///
+	/// This is called after the status packet for the reboot instruction has been sent.
+	/// The default implementation does nothing.
+	fn reboot(&mut self) {}
+
+	/// Check if the hardware error alert bit should be set in status packets.
+	///
+	/// The default implementation always returns `false`.
+	fn alert(&self) -> bool {
+		false
+	}
+}
+
+/// A hook that is called before data is written to the control table.
+type WriteHook = Box<dyn FnMut(u16, &[u8]) -> Result<(), MotorErrorKind>>;
+
+/// A [`Device`] that answers all instructions automatically from a [`ControlTable`].
+///
+/// The server answers the Ping, Read, Write, Reg Write, Action, Factory Reset, Reboot, Clear, Control Table Backup,
+/// Sync Read, Sync Write, Fast Sync Read, Bulk Read, Bulk Write and Fast Bulk Read instructions.
+/// Other instructions, and an Action instruction without a registered write, are answered with an instruction error.
+/// Errors from the control table are reported to the client in the status packet.
+///
+/// All devices reply to a broadcast ping, so the server delays its reply by the transfer time of one ping reply for each lower ID.
+/// This allows multiple servers on the same bus to be found by [`Client::scan()`][crate::Client::scan].
+///
+/// Use [`Self::on_write()`] to register hooks that are called before data is written to the control table,
+/// for example to act on a new goal position or to validate the written values.
+///
+/// This struct is only available if the `alloc` feature is enabled.
+pub struct DeviceServer<SerialPort, Buffer = crate::bus::DefaultBuffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// The device used to communicate on the bus.
+	device: Device<SerialPort, Buffer>,
+
+	/// The ID of the device.
+	id: u8,
+
+	/// The control table of the device.
+	control_table: Box<dyn ControlTable>,
+
+	/// Hooks called before writing to the control table.
+	write_hooks: Vec<WriteHook>,
+
+	/// The write registered with the Reg Write instruction, executed by the next Action instruction.
+	registered_write: Option<(u16, Vec<u8>)>,
+}
+
+impl<SerialPort, Buffer> core::fmt::Debug for DeviceServer<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("DeviceServer")
+			.field("device", &self.device)
+			.field("id", &self.id)
+			.field("write_hooks", &self.write_hooks.len())
+			.finish_non_exhaustive()
+	}
+}
+
+impl<SerialPort, Buffer> DeviceServer<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Create a new server for a device with the given ID.
+	///
+	/// This sets the ID of the device with [`Device::set_id()`].
+	pub fn new(mut device: Device<SerialPort, Buffer>, id: u8, control_table: impl ControlTable + 'static) -> Self {
+		device.set_id(id);
+		Self {
+			device,
+			id,
+			control_table: Box::new(control_table),
+			write_hooks: Vec::new(),
+			registered_write: None,
+		}
+	}
+
+	/// Register a hook that is called before data is written to the control table.
+	///
+	/// The hook receives the address and the data of the write.
+	/// If the hook returns an error, the write is rejected and the error is reported to the client.
+	/// Hooks are called in the order they were registered, for all instructions that write to the control table.
+	pub fn on_write(&mut self, hook: impl FnMut(u16, &[u8]) -> Result<(), MotorErrorKind> + 'static) {
+		self.write_hooks.push(Box::new(hook));
+	}
+
+	/// Get the ID of the device.
+	pub fn id(&self) -> u8 {
+		self.id
+	}
+
+	/// Get a reference to the underlying device.
+	pub fn device(&self) -> &Device<SerialPort, Buffer> {
+		&self.device
+	}
+
+	/// Get a mutable reference to the underlying device.
+	pub fn device_mut(&mut self) -> &mut Device<SerialPort, Buffer> {
+		&mut self.device
+	}
+
+	/// Get a reference to the control table.
+	pub fn control_table(&self) -> &dyn ControlTable {
+		self.control_table.as_ref()
+	}
+
+	/// Get a mutable reference to the control table.
+	///
+	/// Write hooks are not called for writes through this reference.
+	pub fn control_table_mut(&mut self) -> &mut dyn ControlTable {
+		self.control_table.as_mut()
+	}
+
+	/// Consume the server to get back the device.
+	pub fn into_device(self) -> Device<SerialPort, Buffer> {
+		self.device
+	}
+
+	/// Wait for the next instruction and answer it.
+	///
+	/// Instructions for other devices are ignored.
+	/// If no instruction arrives before the timeout, a [`ReadError::Timeout`][crate::ReadError::Timeout] is returned.
+	pub fn serve(&mut self, timeout: Duration) -> Result<(), TransferError<SerialPort::Error>> {
+		let instruction = self.device.read_owned(timeout)?;
+		self.handle(instruction)
+	}
+
+	/// Answer an instruction that was already read from the device.
+	fn handle(&mut self, instruction: Instruction<Vec<u8>>) -> Result<(), TransferError<SerialPort::Error>> {
+		self.answer(instruction, Ok(()))
+	}
+
+	/// Answer an instruction, or reject it with an error.
+	///
+	/// If `accepted` is an error, the instruction is not executed and the error is reported in the status packet instead.
+	/// Instructions that are not addressed to the device are ignored.
+	pub(crate) fn answer(
+		&mut self,
+		instruction: Instruction<Vec<u8>>,
+		accepted: Result<(), MotorErrorKind>,
+	) -> Result<(), TransferError<SerialPort::Error>> {
+		if !self.is_addressed(&instruction) {
+			return Ok(());
+		}
+
+		let id = self.id;
+		let unicast = instruction.id == id;
+		match instruction.instruction {
+			Instructions::Ping => {
+				if !unicast {
+					let slot = crate::bus::message_transfer_time(PING_REPLY_SIZE, self.device.baud_rate());
+					self.device.delay_reply(slot * u32::from(id));
+				}
+				let model_number = self.control_table.model_number().to_le_bytes();
+				let firmware_version = self.control_table.firmware_version();
+				let error = self.status_error(accepted);
+				self.device.write_status(id, error, 3, |buffer| {
+					buffer[..2].copy_from_slice(&model_number);
+					buffer[2] = firmware_version;
+					Ok(())
+				})?;
+			},
+			Instructions::Read { address, length } => {
+				if unicast {
+					self.respond_read(accepted, address, length)?;
+				}
+			},
+			Instructions::Write { address, parameters } => {
+				let result = accepted.and_then(|()| self.write(address, &parameters));
+				if unicast {
+					self.respond(result)?;
+				}
+			},
+			Instructions::RegWrite { address, parameters } => {
+				if accepted.is_ok() {
+					self.registered_write = Some((address, parameters));
+				}
+				if unicast {
+					self.respond(accepted)?;
+				}
+			},
+			Instructions::Action => {
+				let result = accepted.and_then(|()| match self.registered_write.take() {
+					Some((address, data)) => self.write(address, &data),
+					None => Err(MotorErrorKind::InstructionError),
+				});
+				if unicast {
+					self.respond(result)?;
+				}
+			},
+			Instructions::FactoryReset(kind) => {
+				let result = accepted.and_then(|()| {
+					self.registered_write = None;
+					self.control_table.factory_reset(kind)
+				});
+				if unicast {
+					self.respond(result)?;
+				}
+			},
+			Instructions::Reboot => {
+				if accepted.is_ok() {
+					self.registered_write = None;
+				}
+				if unicast {
+					self.respond(accepted)?;
+				}
+				if accepted.is_ok() {
+					self.control_table.reboot();
+				}
+			},
+			Instructions::Clear(kind) => {
+				let result = accepted.and_then(|()| self.control_table.clear(kind));
+				if unicast {
+					self.respond(result)?;
+				}
+			},
+			Instructions::ControlTableBackup(kind) => {
+				let result = accepted.and_then(|()| match kind {
+					ControlTableBackup::Backup => self.control_table.backup(),
+					ControlTableBackup::Restore => self.control_table.restore(),
+					ControlTableBackup::Reserved(_) => Err(MotorErrorKind::InstructionError),
+				});
+				if unicast {
+					self.respond(result)?;
+				}
+			},
+			Instructions::SyncRead { address, length, .. } => {
+				let (error, data) = self.read_for_reply(accepted, address, length);
+				self.device.reply_sync_read(id, &instruction, error, &data, PRECEDING_REPLY_TIMEOUT)?;
+			},
+			Instructions::SyncWrite { address, length, parameters } => {
+				let chunk_len = usize::from(length) + 1;
+				if let Some(chunk) = parameters.chunks_exact(chunk_len).find(|chunk| chunk[0] == id) {
+					// There is no status packet for sync write, so errors are not reported.
+					let _ = accepted.and_then(|()| self.write(address, &chunk[1..]));
+				}
+			},
+			Instructions::FastSyncRead { address, length, ids } => {
+				let (error, data) = self.read_for_fast_read(accepted, address, length);
+				let timeout = crate::bus::status_response_timeout(ids.len() * (usize::from(length) + 4), self.device.baud_rate());
+				self.device.write_fast_sync_read_segment(id, &ids, length, error, timeout, |buffer| {
+					buffer.copy_from_slice(&data);
+					Ok(())
+				})?;
+			},
+			Instructions::BulkRead { ref parameters } => {
+				if let Some((address, length)) = find_bulk_read(parameters, id) {
+					let (error, data) = self.read_for_reply(accepted, address, length);
+					self.device.reply_bulk_read(id, &instruction, error, &data, PRECEDING_REPLY_TIMEOUT)?;
+				}
+			},
+			Instructions::BulkWrite { parameters } => {
+				if let Some((address, data)) = find_bulk_write(&parameters, id) {
+					// There is no status packet for bulk write, so errors are not reported.
+					let _ = accepted.and_then(|()| self.write(address, data));
+				}
+			},
+			Instructions::FastBulkRead { parameters } => {
+				if let Some((address, length)) = find_bulk_read(&parameters, id) {
+					let (error, data) = self.read_for_fast_read(accepted, address, length);
+					let timeout = crate::bus::status_response_timeout(parameters.len(), self.device.baud_rate());
+					self.device.write_fast_bulk_read_segment(id, &parameters, error, timeout, |buffer| {
+						buffer.copy_from_slice(&data);
+						Ok(())
+					})?;
+				}
+			},
+			Instructions::Unknown { .. } => {
+				if unicast {
+					self.respond(accepted.and(Err(MotorErrorKind::InstructionError)))?;
+				}
+			},
+		}
+		Ok(())
+	}
+
+	/// Check if an instruction is addressed to this device.
+	///
+	/// Broadcast instructions that list devices by ID are only addressed to this device if its ID is listed.
+	pub(crate) fn is_addressed(&self, instruction: &Instruction<Vec<u8>>) -> bool {
+		let id = self.id;
+		if instruction.id == id {
+			return true;
+		}
+		if instruction.id != BROADCAST {
+			return false;
+		}
+		match &instruction.instruction {
+			Instructions::SyncRead { ids, .. } | Instructions::FastSyncRead { ids, .. } => ids.contains(&id),
+			Instructions::SyncWrite { length, parameters, .. } => {
+				parameters.chunks_exact(usize::from(*length) + 1).any(|chunk| chunk[0] == id)
+			},
+			Instructions::BulkRead { parameters } | Instructions::FastBulkRead { parameters } => find_bulk_read(parameters, id).is_some(),
+			Instructions::BulkWrite { parameters } => find_bulk_write(parameters, id).is_some(),
+			_ => true,
+		}
+	}
+
+	/// Write to the control table, after running the write hooks.
+	fn write(&mut self, address: u16, data: &[u8]) -> Result<(), MotorErrorKind> {
+		for hook in &mut self.write_hooks {
+			hook(address, data)?;
+		}
+		self.control_table.write(address, data)
+	}
+
+	/// Read from the control table.
+	fn read(&mut self, address: u16, length: u16) -> Result<Vec<u8>, MotorErrorKind> {
+		let mut data = alloc::vec![0; length.into()];
+		self.control_table.read(address, &mut data)?;
+		Ok(data)
+	}
+
+	/// Read data for a fast read segment, which must always have the requested length.
+	fn read_for_fast_read(&mut self, accepted: Result<(), MotorErrorKind>, address: u16, length: u16) -> (u8, Vec<u8>) {
+		match accepted.and_then(|()| self.read(address, length)) {
+			Ok(data) => (self.status_error(Ok(())), data),
+			Err(e) => (self.status_error(Err(e)), alloc::vec![0; length.into()]),
+		}
+	}
+
+	/// Get the error field of a status packet for the result of an instruction.
+	fn status_error(&self, result: Result<(), MotorErrorKind>) -> u8 {
+		let error_number = result.err().map_or(0, |e| e.error_number());
+		let alert = if self.control_table.alert() { 0x80 } else { 0 };
+		error_number | alert
+	}
+
+	/// Send an empty status packet with the result of an instruction.
+	fn respond(&mut self, result: Result<(), MotorErrorKind>) -> Result<(), TransferError<SerialPort::Error>> {
+		let error = self.status_error(result);
+		self.device.write_status(self.id, error, 0, |_| Ok(()))?;
+		Ok(())
+	}
+
+	/// Read data for a status packet, which has no data if the read failed.
+	fn read_for_reply(&mut self, accepted: Result<(), MotorErrorKind>, address: u16, length: u16) -> (u8, Vec<u8>) {
+		match accepted.and_then(|()| self.read(address, length)) {
+			Ok(data) => (self.status_error(Ok(())), data),
+			Err(e) => (self.status_error(Err(e)), Vec::new()),
+		}
+	}
+
+	/// Send a status packet with the result of a read.
+	fn respond_read(&mut self, accepted: Result<(), MotorErrorKind>, address: u16, length: u16) -> Result<(), TransferError<SerialPort::Error>> {
+		let (error, data) = self.read_for_reply(accepted, address, length);
+		self.device.write_status(self.id, error, data.len(), |buffer| {
+			buffer.copy_from_slice(&data);
+			Ok(())
+		})?;
+		Ok(())
+	}
+}
+
+/// A simple [`ControlTable`] backed by memory.
+///
+/// Every address in the table can be read, and every address outside of the read-only ranges can be written.
+/// Accessing an address outside of the table, or writing to a read-only range, results in an access error.
+/// Writes have no other side effects: use [`DeviceServer::on_write()`] to act on written values.
+/// The Clear instruction is accepted without changing the table,
+/// and the Control Table Backup instruction stores and restores a copy of the table in memory.
+///
+/// The model number and firmware version are stored at addresses 0 and 6, like the X-series motors,
+/// if the table is large enough.
+///
+/// This struct is only available if the `alloc` feature is enabled.
+#[derive(Debug, Clone)]
+pub struct MemoryControlTable {
+	/// The current contents of the control table.
+	data: Vec<u8>,
+
+	/// The contents of the control table after a factory reset.
+	defaults: Vec<u8>,
+
+	/// The contents of the control table stored with the Control Table Backup instruction.
+	backup: Option<Vec<u8>>,
+
+	/// The address ranges that can not be written.
+	read_only: Vec<Range<u16>>,
+
+	/// The model number reported in response to a ping.
+	model_number: u16,
+
+	/// The firmware version reported in response to a ping.
+	firmware_version: u8,
+}
+
+impl MemoryControlTable {
+	/// Create a new control table with the given size.
+	///
+	/// All other registers are initialized to zero.
+	pub fn new(model_number: u16, firmware_version: u8, size: usize) -> Self {
+		let mut data = alloc::vec![0; size];
+		for (address, value) in [(0, model_number as u8), (1, (model_number >> 8) as u8), (6, firmware_version)] {
+			if let Some(byte) = data.get_mut(address) {
+				*byte = value;
+			}
+		}
+		Self {
+			defaults: data.clone(),
+			data,
+			backup: None,
+			read_only: Vec::new(),
+			model_number,
+			firmware_version,
+		}
+	}
+
+	/// Mark a range of addresses as read-only for clients.
+	pub fn with_read_only(mut self, range: Range<u16>) -> Self {
+		self.read_only.push(range);
+		self
+	}
+
+	/// Set the initial value of a range of registers, which is also restored by a factory reset.
+	///
+	/// # Panics
+	/// Panics if the range is outside of the control table.
+	pub fn with_default(mut self, address: u16, data: &[u8]) -> Self {
+		let range = usize::from(address)..usize::from(address) + data.len();
+		self.data[range.clone()].copy_from_slice(data);
+		self.defaults[range].copy_from_slice(data);
+		self
+	}
+
+	/// Get the contents of the control table.
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// Get the contents of the control table mutably.
+	///
+	/// This bypasses the read-only ranges, and can be used to update registers such as the present position.
+	pub fn data_mut(&mut self) -> &mut [u8] {
+		&mut self.data
+	}
+
+	/// Get the range of a read or write, or an access error if it is outside of the table.
+	fn range(&self, address: u16, length: usize) -> Result<Range<usize>, MotorErrorKind> {
+		let start = usize::from(address);
+		let end = start + length;
+		if end > self.data.len() {
+			return Err(MotorErrorKind::AccessError);
+		}
+		Ok(start..end)
+	}
+}
+
+impl ControlTable for MemoryControlTable {
+	fn model_number(&self) -> u16 {
+		self.model_number
+	}
+
+	fn firmware_version(&self) -> u8 {
+		self.firmware_version
+	}
+
+	fn read(&mut self, address: u16, buffer: &mut [u8]) -> Result<(), MotorErrorKind> {
+		let range = self.range(address, buffer.len())?;
+		buffer.copy_from_slice(&self.data[range]);
+		Ok(())
+	}
+
+	fn write(&mut self, address: u16, data: &[u8]) -> Result<(), MotorErrorKind> {
+		let range = self.range(address, data.len())?;
+		let overlaps_read_only = self.read_only.iter()
+			.any(|read_only| usize::from(read_only.start) < range.end && range.start < usize::from(read_only.end));
+		if overlaps_read_only {
+			return Err(MotorErrorKind::AccessError);
+		}
+		self.data[range].copy_from_slice(data);
+		Ok(())
+	}
+
+	fn factory_reset(&mut self, kind: FactoryReset) -> Result<(), MotorErrorKind> {
+		// Keep the ID (address 7) and the baud rate (address 8) if requested, like the X-series motors.
+		let keep = match kind {
+			FactoryReset::All => 0..0,
+			FactoryReset::ExceptId => 7..8,
+			FactoryReset::ExceptIdBaudRate => 7..9,
+			FactoryReset::Unknown(_) => return Err(MotorErrorKind::DataRangeError),
+		};
+		for (address, (value, default)) in self.data.iter_mut().zip(&self.defaults).enumerate() {
+			if !keep.contains(&address) {
+				*value = *default;
+			}
+		}
+		Ok(())
+	}
+
+	fn clear(&mut self, kind: Clear) -> Result<(), MotorErrorKind> {
+		match kind {
+			Clear::MultiTurns | Clear::Errors => Ok(()),
+			Clear::Reserved(_) => Err(MotorErrorKind::InstructionError),
+		}
+	}
+
+	fn backup(&mut self) -> Result<(), MotorErrorKind> {
+		self.backup = Some(self.data.clone());
+		Ok(())
+	}
+
+	fn restore(&mut self) -> Result<(), MotorErrorKind> {
+		let backup = self.backup.as_ref().ok_or(MotorErrorKind::ResultFail)?;
+		self.data.copy_from_slice(backup);
+		Ok(())
+	}
+}
+
+/// Find the address and length of a device in the parameters of a bulk read instruction.
+fn find_bulk_read(parameters: &[u8], id: u8) -> Option<(u16, u16)> {
+	let read = parameters.chunks_exact(5).find(|read| read[0] == id)?;
+	Some((u16::from_le_bytes([read[1], read[2]]), u16::from_le_bytes([read[3], read[4]])))
+}
+
+/// Find the address and data of a device in the parameters of a bulk write instruction.
+fn find_bulk_write(parameters: &[u8], id: u8) -> Option<(u16, &[u8])> {
+	let mut remaining = parameters;
+	while remaining.len() >= 5 {
+		let address = u16::from_le_bytes([remaining[1], remaining[2]]);
+		let length = usize::from(u16::from_le_bytes([remaining[3], remaining[4]]));
+		let data = remaining.get(5..5 + length)?;
+		if remaining[0] == id {
+			return Some((address, data));
+		}
+		remaining = &remaining[5 + length..];
+	}
+	None
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::instructions::instruction_id;
+	use crate::serial_port::reply::ReplySerial;
+	use assert2::{assert, let_assert};
+	use std::cell::RefCell;
+	use std::rc::Rc;
+
+	/// Encode a packet without byte stuffing.
+	fn packet(id: u8, instruction: u8, parameters: &[u8]) -> Vec<u8> {
+		let length = (parameters.len() + 3) as u16;
+		let mut packet = vec![0xFF, 0xFF, 0xFD, 0x00, id, length as u8, (length >> 8) as u8, instruction];
+		packet.extend_from_slice(parameters);
+		let checksum = crate::checksum::calculate_checksum(0, &packet);
+		packet.extend_from_slice(&checksum.to_le_bytes());
+		packet
+	}
+
+	/// Encode a status packet.
+	fn status(id: u8, error: u8, parameters: &[u8]) -> Vec<u8> {
+		let mut body = vec![error];
+		body.extend_from_slice(parameters);
+		packet(id, instruction_id::STATUS, &body)
+	}
+
+	/// Create a server for device 1.
+	fn server() -> DeviceServer<ReplySerial, Vec<u8>> {
+		let serial_port = ReplySerial { written: Vec::new(), response: Vec::new() };
+		let_assert!(Ok(device) = Device::new(serial_port));
+		let control_table = MemoryControlTable::new(1060, 46, 150).with_read_only(0..10);
+		DeviceServer::new(device, 1, control_table)
+	}
+
+	/// Let the server answer a single instruction, and return the written response.
+	fn exchange(server: &mut DeviceServer<ReplySerial, Vec<u8>>, input: Vec<u8>) -> Vec<u8> {
+		server.device_mut().serial_port_mut().response = input;
+		let_assert!(Ok(()) = server.serve(Duration::from_millis(10)));
+		core::mem::take(&mut server.device_mut().serial_port_mut().written)
+	}
+
+	#[test]
+	fn answer_basic_instructions() {
+		let mut server = server();
+		assert!(exchange(&mut server, packet(1, instruction_id::PING, &[])) == status(1, 0, &[0x24, 0x04, 46]));
+		assert!(exchange(&mut server, packet(1, instruction_id::WRITE, &[116, 0, 1, 2, 3, 4])) == status(1, 0, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::READ, &[116, 0, 4, 0])) == status(1, 0, &[1, 2, 3, 4]));
+
+		// Out of range read and a write to a read-only register.
+		assert!(exchange(&mut server, packet(1, instruction_id::READ, &[148, 0, 4, 0])) == status(1, 0x07, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::WRITE, &[0, 0, 1, 2])) == status(1, 0x07, &[]));
+
+		// Instructions for other devices are skipped.
+		server.device_mut().serial_port_mut().response = packet(2, instruction_id::PING, &[]);
+		let_assert!(Err(e) = server.serve(Duration::from_millis(10)));
+		assert!(e.is_timeout());
+		assert!(server.device().serial_port().written.is_empty());
+	}
+
+	#[test]
+	fn write_hooks() {
+		let mut server = server();
+		let writes = Rc::new(RefCell::new(Vec::new()));
+		server.on_write({
+			let writes = writes.clone();
+			move |address, data| {
+				if address == 64 && data[0] > 1 {
+					return Err(MotorErrorKind::DataRangeError);
+				}
+				writes.borrow_mut().push((address, data.to_vec()));
+				Ok(())
+			}
+		});
+
+		assert!(exchange(&mut server, packet(1, instruction_id::WRITE, &[64, 0, 1])) == status(1, 0, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::WRITE, &[64, 0, 2])) == status(1, 0x04, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::REG_WRITE, &[65, 0, 1])) == status(1, 0, &[]));
+		assert!(writes.borrow().len() == 1);
+		assert!(exchange(&mut server, packet(1, instruction_id::ACTION, &[])) == status(1, 0, &[]));
+
+		// An action without a registered write is an instruction error.
+		assert!(exchange(&mut server, packet(1, instruction_id::ACTION, &[])) == status(1, 0x02, &[]));
+
+		// Sync write of address 64 with 1 byte to motor 2 and 1, which has no response.
+		assert!(exchange(&mut server, packet(BROADCAST, instruction_id::SYNC_WRITE, &[64, 0, 1, 0, 2, 0, 1, 0])).is_empty());
+		assert!(*writes.borrow() == [(64, vec![1]), (65, vec![1]), (64, vec![0])]);
+	}
+
+	#[test]
+	fn control_table_backup() {
+		let mut server = server();
+		assert!(exchange(&mut server, packet(1, instruction_id::CONTROL_TABLE_BACKUP, &[0x02, b'C', b'T', b'R', b'L'])) == status(1, 0x01, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::WRITE, &[64, 0, 1])) == status(1, 0, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::CONTROL_TABLE_BACKUP, &[0x01, b'C', b'T', b'R', b'L'])) == status(1, 0, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::WRITE, &[64, 0, 0])) == status(1, 0, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::CONTROL_TABLE_BACKUP, &[0x02, b'C', b'T', b'R', b'L'])) == status(1, 0, &[]));
+		assert!(exchange(&mut server, packet(1, instruction_id::READ, &[64, 0, 1, 0])) == status(1, 0, &[1]));
+	}
+
+	#[test]
+	fn sync_read_waits_for_preceding_devices() {
+		let mut server = server();
+		// Sync read of 2 bytes at address 0 from motor 3 and 1.
+		let sync_read = packet(BROADCAST, instruction_id::SYNC_READ, &[0, 0, 2, 0, 3, 1]);
+		let input = [sync_read.clone(), status(3, 0, &[0x00, 0x00])].concat();
+		assert!(exchange(&mut server, input) == status(1, 0, &[0x24, 0x04]));
+
+		// Without a response from the preceding motor, the server responds after a timeout.
+		assert!(server.device().stats().timeouts == 0);
+		assert!(exchange(&mut server, sync_read) == status(1, 0, &[0x24, 0x04]));
+		assert!(server.device().stats().timeouts == 1);
+	}
+}
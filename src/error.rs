@@ -1,5 +1,6 @@
 use crate::instructions::packet_id::BROADCAST;
 use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use core::time::Duration;
 
 /// An error that can occur during a read/write transfer.
 #[derive(Debug)]
@@ -9,6 +10,56 @@ pub enum TransferError<E> {
 
 	/// The read failed.
 	ReadError(ReadError<E>),
+
+	/// The instruction was attempted multiple times according to the [`RetryPolicy`][crate::RetryPolicy], but all attempts failed.
+	RetriesFailed {
+		/// The total number of attempts that were made.
+		attempts: u32,
+
+		/// The error of the last attempt.
+		last_error: ReadError<E>,
+	},
+}
+
+/// An error returned by an instruction of the [`Client`][crate::Client], with optional context about the instruction.
+///
+/// This is a breaking change from earlier versions, where the instructions returned a [`TransferError`] directly.
+/// Patterns like `Err(TransferError::ReadError(e))` no longer match the result of an instruction.
+/// Match on the `error` field instead, like `Err(ClientError { error: TransferError::ReadError(e), .. })`,
+/// or convert the error with [`TransferError::from()`] or `?` to drop the context.
+///
+/// The methods of [`TransferError`] can also be called directly on a `ClientError`, because it dereferences to the `error` field.
+#[derive(Debug)]
+pub struct ClientError<E> {
+	/// The error that occurred.
+	pub error: TransferError<E>,
+
+	/// Context about the instruction that failed, if available.
+	pub context: Option<ErrorContext>,
+}
+
+/// Context about the instruction that caused an error.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ErrorContext {
+	/// The ID of the instruction that was sent.
+	pub instruction_id: u8,
+
+	/// The ID of the motor the instruction was sent to.
+	///
+	/// This is [`BROADCAST`] for broadcast instructions, including sync and bulk instructions.
+	/// For errors in the reply of a single motor to a sync or bulk read, this is the ID of that motor.
+	pub motor_id: u8,
+
+	/// The register address accessed by the instruction, if it accesses a single register range.
+	pub address: Option<u16>,
+
+	/// The number of bytes accessed by the instruction, if it accesses a single register range.
+	pub count: Option<u16>,
+
+	/// The time between sending the instruction and the error, if it was sent and the serial port can measure it.
+	///
+	/// See [`SerialPort::elapsed_since()`][crate::SerialPort::elapsed_since] for more information.
+	pub elapsed: Option<Duration>,
 }
 
 /// An error that can occur during a write transfer.
@@ -46,6 +97,23 @@ pub enum ReadError<E> {
 	/// Failed to read from the serial port.
 	Io(E),
 
+	/// The timeout expired before a complete message was received.
+	///
+	/// This is the normal result of sending an instruction to a motor that is not present on the bus.
+	Timeout {
+		/// The ID of the motor that was expected to respond, if known.
+		motor_id: Option<u8>,
+
+		/// The expected size of the message in bytes.
+		///
+		/// If the header of the message was received, this is the size from the header.
+		/// Otherwise, it is the size that was expected for the response, or the minimum message size if that is not known.
+		expected_bytes: usize,
+
+		/// The time that was waited for the message.
+		waited: Duration,
+	},
+
 	/// The received message is invalid.
 	InvalidMessage(InvalidMessage),
 
@@ -75,6 +143,9 @@ pub enum InvalidMessage {
 
 	/// The message has an invalid parameter count.
 	InvalidParameterCount(InvalidParameterCount),
+
+	/// The message contains a value that is not valid for the requested type.
+	InvalidValue(InvalidValue),
 }
 
 /// An error reported by the motor.
@@ -84,6 +155,67 @@ pub struct MotorError {
 	pub raw: u8,
 }
 
+/// The kind of error reported by a motor, as defined by the DYNAMIXEL Protocol 2.0.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MotorErrorKind {
+	/// The motor failed to process the instruction.
+	ResultFail,
+
+	/// The instruction is not defined, or an action instruction was sent without a registered instruction.
+	InstructionError,
+
+	/// The CRC of the instruction packet does not match.
+	CrcError,
+
+	/// The data to be written is outside of the range of the register.
+	DataRangeError,
+
+	/// The data to be written is shorter than the register.
+	DataLengthError,
+
+	/// The data to be written exceeds a limit from the control table.
+	DataLimitError,
+
+	/// The register can not be accessed.
+	///
+	/// This happens when writing to a read-only register, reading a write-only register,
+	/// or writing to the EEPROM area while torque is enabled.
+	AccessError,
+
+	/// An error number that is not defined by the protocol.
+	Unknown(u8),
+}
+
+impl MotorErrorKind {
+	/// Get the error kind for an error number.
+	pub fn from_error_number(error_number: u8) -> Self {
+		match error_number {
+			0x01 => Self::ResultFail,
+			0x02 => Self::InstructionError,
+			0x03 => Self::CrcError,
+			0x04 => Self::DataRangeError,
+			0x05 => Self::DataLengthError,
+			0x06 => Self::DataLimitError,
+			0x07 => Self::AccessError,
+			other => Self::Unknown(other),
+		}
+	}
+
+	/// Get the error number of the error kind.
+	pub fn error_number(&self) -> u8 {
+		match self {
+			Self::ResultFail => 0x01,
+			Self::InstructionError => 0x02,
+			Self::CrcError => 0x03,
+			Self::DataRangeError => 0x04,
+			Self::DataLengthError => 0x05,
+			Self::DataLimitError => 0x06,
+			Self::AccessError => 0x07,
+			Self::Unknown(other) => *other,
+		}
+	}
+}
+
 impl MotorError {
 	/// The error number reported by the motor.
 	///
@@ -92,6 +224,11 @@ impl MotorError {
 		self.raw & !0x80
 	}
 
+	/// The kind of error reported by the motor.
+	pub fn kind(&self) -> MotorErrorKind {
+		MotorErrorKind::from_error_number(self.error_number())
+	}
+
 	/// The alert bit from the error field of the response.
 	///
 	/// This is the 8th bit of the raw error field.
@@ -106,7 +243,7 @@ impl MotorError {
 impl Debug for MotorError {
 	fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
 		f.debug_struct("MotorError")
-			.field("error_number", &self.error_number())
+			.field("kind", &self.kind())
 			.field("alert", &self.alert())
 			.finish()
 	}
@@ -175,6 +312,67 @@ pub struct InvalidParameterCount {
 	pub expected: ExpectedCount,
 }
 
+/// The received message contains a value that is not valid for the requested type.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct InvalidValue {
+	/// The actual value.
+	pub actual: i64,
+
+	/// The name of the type the value was decoded as.
+	pub type_name: &'static str,
+}
+
+impl<E> TransferError<E> {
+	/// Check if the error is a timeout.
+	///
+	/// This is also true if the instruction was retried and the last attempt timed out.
+	pub fn is_timeout(&self) -> bool {
+		match self {
+			Self::WriteError(_) => false,
+			Self::ReadError(e) => e.is_timeout(),
+			Self::RetriesFailed { last_error, .. } => last_error.is_timeout(),
+		}
+	}
+}
+
+impl<E> ClientError<E> {
+	/// Check if the error is a timeout.
+	///
+	/// This is also true if the instruction was retried and the last attempt timed out.
+	pub fn is_timeout(&self) -> bool {
+		self.error.is_timeout()
+	}
+}
+
+impl ErrorContext {
+	/// Create a new context for an instruction sent to a motor.
+	pub(crate) fn new(instruction_id: u8, motor_id: u8) -> Self {
+		Self {
+			instruction_id,
+			motor_id,
+			address: None,
+			count: None,
+			elapsed: None,
+		}
+	}
+
+	/// Add the register range accessed by the instruction.
+	pub(crate) fn with_registers(self, address: u16, count: u16) -> Self {
+		Self {
+			address: Some(address),
+			count: Some(count),
+			..self
+		}
+	}
+}
+
+impl<E> ReadError<E> {
+	/// Check if the error is a timeout.
+	pub fn is_timeout(&self) -> bool {
+		matches!(self, Self::Timeout { .. })
+	}
+}
+
 impl BufferTooSmallError {
 	/// Check if a buffer is large enough for the required total size.
 	pub fn check(required_size: usize, total_size: usize) -> Result<(), Self> {
@@ -291,6 +489,12 @@ impl InvalidParameterCount {
 #[cfg(feature = "std")]
 impl<E: Debug + Display> std::error::Error for TransferError<E> {}
 #[cfg(feature = "std")]
+impl<E: Debug + Display + 'static> std::error::Error for ClientError<E> {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		Some(&self.error)
+	}
+}
+#[cfg(feature = "std")]
 impl<E: Debug + Display> std::error::Error for WriteError<E> {}
 #[cfg(feature = "std")]
 impl<E: Debug + Display> std::error::Error for ReadError<E> {}
@@ -308,6 +512,43 @@ impl std::error::Error for InvalidPacketId {}
 impl std::error::Error for InvalidInstruction {}
 #[cfg(feature = "std")]
 impl std::error::Error for InvalidParameterCount {}
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidValue {}
+
+impl<E> From<TransferError<E>> for ClientError<E> {
+	fn from(other: TransferError<E>) -> Self {
+		Self {
+			error: other,
+			context: None,
+		}
+	}
+}
+
+impl<E> From<WriteError<E>> for ClientError<E> {
+	fn from(other: WriteError<E>) -> Self {
+		TransferError::from(other).into()
+	}
+}
+
+impl<E> From<ReadError<E>> for ClientError<E> {
+	fn from(other: ReadError<E>) -> Self {
+		TransferError::from(other).into()
+	}
+}
+
+impl<E> core::ops::Deref for ClientError<E> {
+	type Target = TransferError<E>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.error
+	}
+}
+
+impl<E> From<ClientError<E>> for TransferError<E> {
+	fn from(other: ClientError<E>) -> Self {
+		other.error
+	}
+}
 
 impl<E> From<WriteError<E>> for TransferError<E>
 {
@@ -365,6 +606,12 @@ impl<E> From<InvalidParameterCount> for TransferError<E> {
 	}
 }
 
+impl<E> From<InvalidValue> for TransferError<E> {
+	fn from(other: InvalidValue) -> Self {
+		Self::ReadError(other.into())
+	}
+}
+
 impl<E> From<BufferTooSmallError> for WriteError<E> {
 	fn from(other: BufferTooSmallError) -> Self {
 		Self::BufferTooSmall(other)
@@ -419,6 +666,12 @@ impl<E> From<InvalidParameterCount> for ReadError<E> {
 	}
 }
 
+impl<E> From<InvalidValue> for ReadError<E> {
+	fn from(other: InvalidValue) -> Self {
+		Self::InvalidMessage(other.into())
+	}
+}
+
 impl From<InvalidHeaderPrefix> for InvalidMessage {
 	fn from(other: InvalidHeaderPrefix) -> Self {
 		Self::InvalidHeaderPrefix(other)
@@ -449,6 +702,12 @@ impl From<InvalidParameterCount> for InvalidMessage {
 	}
 }
 
+impl From<InvalidValue> for InvalidMessage {
+	fn from(other: InvalidValue) -> Self {
+		Self::InvalidValue(other)
+	}
+}
+
 impl<E> Display for TransferError<E>
 where
 	E: Display,
@@ -457,10 +716,70 @@ where
 		match self {
 			Self::WriteError(e) => write!(f, "{}", e),
 			Self::ReadError(e) => write!(f, "{}", e),
+			Self::RetriesFailed { attempts, last_error } => write!(f, "{} (after {} attempts)", last_error, attempts),
 		}
 	}
 }
 
+impl<E> Display for ClientError<E>
+where
+	E: Display,
+{
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match &self.context {
+			Some(context) => write!(f, "{} ({})", self.error, context),
+			None => write!(f, "{}", self.error),
+		}
+	}
+}
+
+impl Display for ErrorContext {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match instruction_name(self.instruction_id) {
+			Some(name) => write!(f, "{} instruction", name)?,
+			None => write!(f, "instruction {:#02X}", self.instruction_id)?,
+		}
+		if self.motor_id == BROADCAST {
+			write!(f, " to all motors")?;
+		} else {
+			write!(f, " to motor {}", self.motor_id)?;
+		}
+		if let Some(address) = self.address {
+			write!(f, ", address {}", address)?;
+		}
+		if let Some(count) = self.count {
+			write!(f, ", count {}", count)?;
+		}
+		if let Some(elapsed) = self.elapsed {
+			write!(f, ", {:?} after sending", elapsed)?;
+		}
+		Ok(())
+	}
+}
+
+/// Get a human readable name for an instruction ID.
+fn instruction_name(instruction_id: u8) -> Option<&'static str> {
+	use crate::instructions::instruction_id;
+	match instruction_id {
+		instruction_id::PING => Some("ping"),
+		instruction_id::READ => Some("read"),
+		instruction_id::WRITE => Some("write"),
+		instruction_id::REG_WRITE => Some("reg write"),
+		instruction_id::ACTION => Some("action"),
+		instruction_id::FACTORY_RESET => Some("factory reset"),
+		instruction_id::REBOOT => Some("reboot"),
+		instruction_id::CLEAR => Some("clear"),
+		instruction_id::CONTROL_TABLE_BACKUP => Some("control table backup"),
+		instruction_id::SYNC_READ => Some("sync read"),
+		instruction_id::SYNC_WRITE => Some("sync write"),
+		instruction_id::FAST_SYNC_READ => Some("fast sync read"),
+		instruction_id::BULK_READ => Some("bulk read"),
+		instruction_id::BULK_WRITE => Some("bulk write"),
+		instruction_id::FAST_BULK_READ => Some("fast bulk read"),
+		_ => None,
+	}
+}
+
 impl<E> Display for WriteError<E>
 where
 	E: Display,
@@ -500,6 +819,16 @@ where
 				e.required_size, e.total_size
 			),
 			Self::Io(e) => write!(f, "failed to read from serial port: {}", e),
+			Self::Timeout { motor_id: Some(motor_id), expected_bytes, waited } => write!(
+				f,
+				"timeout after {:?} while waiting for {} bytes from motor {}",
+				waited, expected_bytes, motor_id
+			),
+			Self::Timeout { motor_id: None, expected_bytes, waited } => write!(
+				f,
+				"timeout after {:?} while waiting for {} bytes",
+				waited, expected_bytes
+			),
 			Self::InvalidMessage(e) => write!(f, "{}", e),
 			Self::MotorError(e) => write!(f, "{}", e),
 		}
@@ -514,13 +843,33 @@ impl Display for InvalidMessage {
 			Self::InvalidPacketId(e) => write!(f, "{}", e),
 			Self::InvalidInstruction(e) => write!(f, "{}", e),
 			Self::InvalidParameterCount(e) => write!(f, "{}", e),
+			Self::InvalidValue(e) => write!(f, "{}", e),
 		}
 	}
 }
 
 impl Display for MotorError {
 	fn fmt(&self, f: &mut Formatter) -> FmtResult {
-		write!(f, "motor reported error status: {:#02X}", self.raw,)
+		write!(f, "motor reported error: {}", self.kind())?;
+		if self.alert() {
+			write!(f, " (hardware error alert is set)")?;
+		}
+		Ok(())
+	}
+}
+
+impl Display for MotorErrorKind {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::ResultFail => write!(f, "result fail"),
+			Self::InstructionError => write!(f, "instruction error"),
+			Self::CrcError => write!(f, "CRC error"),
+			Self::DataRangeError => write!(f, "data range error"),
+			Self::DataLengthError => write!(f, "data length error"),
+			Self::DataLimitError => write!(f, "data limit error"),
+			Self::AccessError => write!(f, "access error"),
+			Self::Unknown(error_number) => write!(f, "unknown error {:#02X}", error_number),
+		}
 	}
 }
 
@@ -579,3 +928,81 @@ impl Display for InvalidParameterCount {
 		write!(f, "invalid parameter count, expected {}, got {}", self.expected, self.actual)
 	}
 }
+
+impl Display for InvalidValue {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		write!(f, "invalid value for {}: {}", self.type_name, self.actual)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::instructions::instruction_id;
+	use crate::serial_port::reply::ReplySerial;
+	use crate::Client;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn read_error_has_context() {
+		let serial_port = ReplySerial { written: Vec::new(), response: Vec::new() };
+		let_assert!(Ok(mut client) = Client::new(serial_port));
+		let_assert!(Err(ClientError { error: TransferError::ReadError(ReadError::Timeout { .. }), context: Some(context) }) = client.read::<u32>(1, 132));
+		assert!(context.instruction_id == instruction_id::READ);
+		assert!(context.motor_id == 1);
+		assert!(context.address == Some(132));
+		assert!(context.count == Some(4));
+		assert!(let Some(_) = context.elapsed);
+	}
+
+	#[test]
+	fn unsent_instruction_has_no_elapsed_time() {
+		let serial_port = ReplySerial { written: Vec::new(), response: Vec::new() };
+		let_assert!(Ok(mut client) = Client::with_buffers(serial_port, vec![0; 128], vec![0; 12]));
+		let_assert!(Err(error) = client.sync_read::<u32>(&[1, 2, 3], 132));
+		let_assert!(ClientError { error: TransferError::WriteError(WriteError::BufferTooSmall(_)), context: Some(context) } = error);
+		assert!(context.instruction_id == instruction_id::SYNC_READ);
+		assert!(context.motor_id == BROADCAST);
+		assert!(context.elapsed == None);
+	}
+
+	#[test]
+	fn client_error_converts_to_transfer_error() {
+		let serial_port = ReplySerial { written: Vec::new(), response: Vec::new() };
+		let_assert!(Ok(mut client) = Client::new(serial_port));
+		let_assert!(Err(error) = client.ping(1));
+		let_assert!(TransferError::ReadError(ReadError::Timeout { .. }) = &*error);
+		let_assert!(TransferError::ReadError(ReadError::Timeout { .. }) = TransferError::from(error));
+	}
+
+	#[test]
+	fn display_includes_context() {
+		let error = ClientError::<core::convert::Infallible> {
+			error: TransferError::ReadError(ReadError::MotorError(MotorError { raw: 0x01 })),
+			context: Some(ErrorContext {
+				elapsed: Some(Duration::from_millis(3)),
+				..ErrorContext::new(instruction_id::WRITE, 7).with_registers(116, 4)
+			}),
+		};
+		assert!(error.to_string() == "motor reported error: result fail (write instruction to motor 7, address 116, count 4, 3ms after sending)");
+
+		let error = ClientError::<core::convert::Infallible> {
+			error: TransferError::ReadError(ReadError::MotorError(MotorError { raw: 0x01 })),
+			context: Some(ErrorContext::new(0x42, BROADCAST)),
+		};
+		assert!(error.to_string() == "motor reported error: result fail (instruction 0x42 to all motors)");
+	}
+
+	#[test]
+	fn motor_error_kind() {
+		let error = MotorError { raw: 0x87 };
+		assert!(error.kind() == MotorErrorKind::AccessError);
+		assert!(error.alert());
+		assert!(error.to_string() == "motor reported error: access error (hardware error alert is set)");
+		assert!(MotorError { raw: 0x06 }.kind() == MotorErrorKind::DataLimitError);
+		assert!(MotorError { raw: 0x2A }.kind() == MotorErrorKind::Unknown(0x2A));
+		for error_number in 0..0x80 {
+			assert!(MotorErrorKind::from_error_number(error_number).error_number() == error_number);
+		}
+	}
+}
@@ -0,0 +1,53 @@
+/// A set of packet IDs.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct IdSet {
+	/// One bit for each possible ID.
+	bits: [u32; 8],
+}
+
+impl IdSet {
+	/// Add an ID to the set.
+	pub(crate) fn insert(&mut self, id: u8) {
+		self.bits[usize::from(id / 32)] |= 1 << (id % 32);
+	}
+
+	/// Remove an ID from the set.
+	pub(crate) fn remove(&mut self, id: u8) {
+		self.bits[usize::from(id / 32)] &= !(1 << (id % 32));
+	}
+
+	/// Check if an ID is in the set.
+	pub(crate) fn contains(&self, id: u8) -> bool {
+		self.bits[usize::from(id / 32)] & (1 << (id % 32)) != 0
+	}
+
+	/// Check if the set is empty.
+	pub(crate) fn is_empty(&self) -> bool {
+		self.bits.iter().all(|&word| word == 0)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn insert_and_remove() {
+		let mut set = IdSet::default();
+		assert!(set.is_empty());
+		set.insert(0);
+		set.insert(37);
+		set.insert(253);
+		assert!(!set.is_empty());
+		assert!(set.contains(0));
+		assert!(set.contains(37));
+		assert!(set.contains(253));
+		assert!(!set.contains(1));
+		assert!(!set.contains(36));
+
+		set.remove(37);
+		assert!(!set.contains(37));
+		assert!(set.contains(253));
+	}
+}
@@ -1,5 +1,5 @@
 use super::{instruction_id, packet_id};
-use crate::{Client, Response, TransferError, WriteError};
+use crate::{Client, ClientError, ErrorContext, Response};
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
 where
@@ -12,13 +12,17 @@ where
 	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
 	///
 	/// If you want to broadcast this instruction, it may be more convenient to use [`Self::broadcast_action()`] instead.
-	pub fn action(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::ACTION, 0, |_| Ok(()))?;
-		Ok(super::read_response_if_not_broadcast(self, motor_id)?)
+	pub fn action(&mut self, motor_id: u8) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::ACTION, motor_id), |client| {
+			client.write_instruction(motor_id, instruction_id::ACTION, 0, |_| Ok(()))?;
+			Ok(super::read_response_if_expected(client, motor_id, instruction_id::ACTION)?)
+		})
 	}
 
 	/// Broadcast an action command to all connected motors to trigger a previously registered instruction.
-	pub fn broadcast_action(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
-		self.write_instruction(packet_id::BROADCAST, instruction_id::ACTION, 0, |_| Ok(()))
+	pub fn broadcast_action(&mut self) -> Result<(), ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::ACTION, packet_id::BROADCAST), |client| {
+			Ok(client.write_instruction(packet_id::BROADCAST, instruction_id::ACTION, 0, |_| Ok(()))?)
+		})
 	}
 }
@@ -1,8 +1,8 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 use crate::bus::data::{decode_status_packet_bytes, decode_status_packet_bytes_borrow};
 use crate::bus::endian::{write_u16_le, write_u8_le};
-use crate::{Client, ReadError, Response, WriteError};
+use crate::{Client, ClientError, ErrorContext, ReadError, Response, TransferError, WriteError};
 use super::{instruction_id, packet_id, BulkReadData};
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
@@ -20,11 +20,14 @@ where
 	pub fn bulk_read_bytes<'a, T>(
 		&'a mut self,
 		reads: &'a [BulkReadData],
-	) -> Result<BulkReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	) -> Result<BulkReadBytes<'a, T, SerialPort, Buffer>, ClientError<SerialPort::Error>>
 	where
 		T: for<'b> From<&'b [u8]>,
 	{
-		write_bulk_read_instruction(self, reads)?;
+		self.with_error_context(ErrorContext::new(instruction_id::BULK_READ, packet_id::BROADCAST), |client| {
+			write_bulk_read_instruction(client, instruction_id::BULK_READ, reads)?;
+			Ok(())
+		})?;
 
 		Ok(BulkReadBytes {
 			client: self,
@@ -44,11 +47,14 @@ where
 	pub fn bulk_read_bytes_borrow<'a, T>(
 		&'a mut self,
 		reads: &'a [BulkReadData],
-	) -> Result<BulkReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	) -> Result<BulkReadBytes<'a, T, SerialPort, Buffer>, ClientError<SerialPort::Error>>
 	where
 		[u8]: core::borrow::Borrow<T>,
 	{
-		write_bulk_read_instruction(self, reads)?;
+		self.with_error_context(ErrorContext::new(instruction_id::BULK_READ, packet_id::BROADCAST), |client| {
+			write_bulk_read_instruction(client, instruction_id::BULK_READ, reads)?;
+			Ok(())
+		})?;
 
 		Ok(BulkReadBytes {
 			client: self,
@@ -59,18 +65,30 @@ where
 	}
 }
 
-/// Write a bulk write instruction to a bus.
+/// Write a bulk read or fast bulk read instruction to a bus.
 ///
 /// # Panic
 /// Panics if multiple read operation use the same motor ID.
-fn write_bulk_read_instruction<'a, SerialPort, Buffer>(
-	client: &'a mut Client<SerialPort, Buffer>,
+pub(super) fn write_bulk_read_instruction<SerialPort, Buffer>(
+	client: &mut Client<SerialPort, Buffer>,
+	instruction_id: u8,
 	reads: &[BulkReadData],
 ) -> Result<(), WriteError<SerialPort::Error>>
 where
 	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
+	check_unique_bulk_read_motor_ids(reads);
+	client.write_instruction(packet_id::BROADCAST, instruction_id, 5 * reads.len(), |buffer| {
+		encode_bulk_read_parameters(buffer, reads)
+	})
+}
+
+/// Check that each motor ID is used at most once in a bulk read.
+///
+/// # Panic
+/// Panics if multiple read operation use the same motor ID.
+pub(crate) fn check_unique_bulk_read_motor_ids(reads: &[BulkReadData]) {
 	for i in 0..reads.len() {
 		for j in i + 1..reads.len() {
 			if reads[i].motor_id == reads[j].motor_id {
@@ -81,15 +99,17 @@ where
 			}
 		}
 	}
-    client.write_instruction(packet_id::BROADCAST, instruction_id::BULK_READ, 5 * reads.len(), |buffer| {
-		for (i, read) in reads.iter().enumerate() {
-			let buffer = &mut buffer[i*5..][..5];
-			write_u8_le(&mut buffer[0..], read.motor_id);
-			write_u16_le(&mut buffer[1..], read.address);
-			write_u16_le(&mut buffer[3..], read.count);
-		}
-		Ok(())
-	})
+}
+
+/// Encode the parameters of a bulk read or fast bulk read instruction.
+pub(crate) fn encode_bulk_read_parameters(buffer: &mut [u8], reads: &[BulkReadData]) -> Result<(), crate::error::BufferTooSmallError> {
+	for (i, read) in reads.iter().enumerate() {
+		let buffer = &mut buffer[i*5..][..5];
+		write_u8_le(&mut buffer[0..], read.motor_id);
+		write_u16_le(&mut buffer[1..], read.address);
+		write_u16_le(&mut buffer[3..], read.count);
+	}
+	Ok(())
 }
 
 /// A bulk read operation that returns unparsed bytes.
@@ -106,7 +126,7 @@ where
 
 impl<T, SerialPort, Buffer> core::fmt::Debug for BulkReadBytes<'_, T, SerialPort, Buffer>
 where
-	SerialPort: crate::SerialPort + std::fmt::Debug,
+	SerialPort: crate::SerialPort + core::fmt::Debug,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -137,7 +157,7 @@ where
 	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	type Item = Result<Response<T>, crate::error::ReadError<SerialPort::Error>>;
+	type Item = Result<Response<T>, ClientError<SerialPort::Error>>;
 
 	fn next(&mut self) -> Option<Self::Item> {
 		self.read_next()
@@ -159,21 +179,32 @@ where
 	}
 
 	/// Read the next motor reply.
-	pub fn read_next(&mut self) -> Option<Result<Response<T>, ReadError<SerialPort::Error>>>
+	pub fn read_next(&mut self) -> Option<Result<Response<T>, ClientError<SerialPort::Error>>>
 	where
 		T: for<'b> From<&'b [u8]>,
 	{
-		let BulkReadData {motor_id, count, .. } = self.pop_bulk_read_data()?;
-		Some(self.next_response(motor_id, count))
+		let read = self.pop_bulk_read_data()?;
+		let packet_len = match self.receive_response(read) {
+			Ok(packet_len) => packet_len,
+			Err(e) => return Some(Err(self.error_with_context(e, read))),
+		};
+		let response = self.client.received_status_packet(packet_len);
+		Some(decode_status_packet_bytes(response).map_err(|e| self.error_with_context(ReadError::from(e), read)))
 	}
 
 	/// Read the next motor reply, borrowing the data from the internal read buffer.
-	pub fn read_next_borrow(&mut self) -> Option<Result<Response<&T>, ReadError<SerialPort::Error>>>
+	pub fn read_next_borrow(&mut self) -> Option<Result<Response<&T>, ClientError<SerialPort::Error>>>
 	where
 		[u8]: core::borrow::Borrow<T>,
 	{
-		let BulkReadData {motor_id, count, .. } = self.pop_bulk_read_data()?;
-		Some(self.next_response_borrow(motor_id, count))
+		let read = self.pop_bulk_read_data()?;
+		let packet_len = match self.receive_response(read) {
+			Ok(packet_len) => packet_len,
+			Err(e) => return Some(Err(self.error_with_context(e, read))),
+		};
+		let this: &Self = self;
+		let response = this.client.received_status_packet(packet_len);
+		Some(decode_status_packet_bytes_borrow(response).map_err(|e| this.error_with_context(ReadError::from(e), read)))
 	}
 
 	fn pop_bulk_read_data(&mut self) -> Option<BulkReadData> {
@@ -182,28 +213,24 @@ where
 		Some(*data)
 	}
 
-	fn next_response(&mut self, motor_id: u8, count: u16) -> Result<Response<T>, ReadError<SerialPort::Error>>
-	where
-		T: for<'b> From<&'b [u8]>,
-	{
-		let response = self.client.read_status_response(count)?;
+	/// Receive the status packet of a motor into the read buffer.
+	///
+	/// Returns the length of the status packet, which can be passed to [`Client::received_status_packet()`].
+	fn receive_response(&mut self, read: BulkReadData) -> Result<usize, ReadError<SerialPort::Error>> {
+		// Report timeouts for the motor that should respond next.
+		self.client.bus.response_motor_id = Some(read.motor_id);
+		let packet_len = self.client.receive_status_response(read.count)?;
+		let response = self.client.received_status_packet(packet_len);
 		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
 		// We need to report a timeout or something for the missed motor though.
-		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
-		crate::InvalidParameterCount::check(response.parameters().len(), count.into())?;
-
-		Ok(decode_status_packet_bytes(response)?)
+		crate::InvalidPacketId::check(response.packet_id(), read.motor_id)?;
+		crate::InvalidParameterCount::check(response.parameters().len(), read.count.into())?;
+		Ok(packet_len)
 	}
 
-	fn next_response_borrow(&mut self, motor_id: u8, count: u16) -> Result<Response<&T>, ReadError<SerialPort::Error>>
-	where
-		[u8]: core::borrow::Borrow<T>,
-	{
-		let response = self.client.read_status_response(count)?;
-		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
-		// We need to report a timeout or something for the missed motor though.
-		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
-		crate::InvalidParameterCount::check(response.parameters().len(), count.into())?;
-		Ok(decode_status_packet_bytes_borrow(response)?)
+	/// Attach context about the read of a motor to an error.
+	fn error_with_context(&self, error: impl Into<TransferError<SerialPort::Error>>, read: BulkReadData) -> ClientError<SerialPort::Error> {
+		let context = ErrorContext::new(instruction_id::BULK_READ, read.motor_id).with_registers(read.address, read.count);
+		self.client.error_with_context(error, context)
 	}
 }
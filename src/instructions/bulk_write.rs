@@ -1,6 +1,6 @@
 use super::{instruction_id, packet_id, BulkWriteData};
 use crate::bus::endian::{write_u16_le, write_u8_le};
-use crate::{Client, WriteError};
+use crate::{Client, ClientError, ErrorContext};
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
 where
@@ -45,46 +45,70 @@ where
 	/// # Ok(())
 	/// # }
 	/// ```
-	pub fn bulk_write<'a, I, D>(&mut self, writes: &'a I) -> Result<(), WriteError<SerialPort::Error>>
+	pub fn bulk_write<'a, I, D>(&mut self, writes: &'a I) -> Result<(), ClientError<SerialPort::Error>>
 	where
 		&'a I: IntoIterator,
 		<&'a I as IntoIterator>::IntoIter: Clone,
 		<&'a I as IntoIterator>::Item: core::borrow::Borrow<BulkWriteData<D>>,
 		D: AsRef<[u8]>,
 	{
-		use core::borrow::Borrow;
-
 		let writes = writes.into_iter();
-		let mut parameter_count = 0;
-		for write in writes.clone() {
-			let write = write.borrow();
-			let data = write.data.as_ref();
-			if data.len() > u16::MAX.into() {
-				panic!(
-					"bulk_write: data length ({}) for motor {} exceeds maximum size of {}",
-					data.len(),
-					write.motor_id,
-					u16::MAX
-				);
-			}
-			parameter_count += 5 + data.len();
+		let parameter_count = bulk_write_parameter_count(writes.clone());
+		self.with_error_context(ErrorContext::new(instruction_id::BULK_WRITE, packet_id::BROADCAST), |client| {
+			Ok(client.write_instruction(packet_id::BROADCAST, instruction_id::BULK_WRITE, parameter_count, |buffer| {
+				encode_bulk_write_parameters(buffer, writes)
+			})?)
+		})
+	}
+}
+
+/// Get the number of parameters for a bulk write instruction.
+///
+/// # Panics
+/// Panics if the data length for a motor exceeds the capacity of a `u16`.
+pub(crate) fn bulk_write_parameter_count<W, D>(writes: impl Iterator<Item = W>) -> usize
+where
+	W: core::borrow::Borrow<BulkWriteData<D>>,
+	D: AsRef<[u8]>,
+{
+	let mut parameter_count = 0;
+	for write in writes {
+		let write = write.borrow();
+		let data = write.data.as_ref();
+		if data.len() > u16::MAX.into() {
+			panic!(
+				"bulk_write: data length ({}) for motor {} exceeds maximum size of {}",
+				data.len(),
+				write.motor_id,
+				u16::MAX
+			);
 		}
+		parameter_count += 5 + data.len();
+	}
+	parameter_count
+}
 
-		self.write_instruction(packet_id::BROADCAST, instruction_id::BULK_WRITE, parameter_count, |buffer| {
-			let mut offset = 0;
-			for write in writes {
-				let write = write.borrow();
-				let data = write.data.as_ref();
-				let buffer = &mut buffer[offset..];
-				offset += 5 + data.len();
-				write_u8_le(&mut buffer[0..], write.motor_id);
-				write_u16_le(&mut buffer[1..], write.address);
-				write_u16_le(&mut buffer[3..], data.len() as u16);
-				buffer[5..][..data.len()].copy_from_slice(data);
-			}
-			Ok(())
-		})
+/// Encode the parameters of a bulk write instruction.
+pub(crate) fn encode_bulk_write_parameters<W, D>(
+	buffer: &mut [u8],
+	writes: impl Iterator<Item = W>,
+) -> Result<(), crate::error::BufferTooSmallError>
+where
+	W: core::borrow::Borrow<BulkWriteData<D>>,
+	D: AsRef<[u8]>,
+{
+	let mut offset = 0;
+	for write in writes {
+		let write = write.borrow();
+		let data = write.data.as_ref();
+		let buffer = &mut buffer[offset..];
+		offset += 5 + data.len();
+		write_u8_le(&mut buffer[0..], write.motor_id);
+		write_u16_le(&mut buffer[1..], write.address);
+		write_u16_le(&mut buffer[3..], data.len() as u16);
+		buffer[5..][..data.len()].copy_from_slice(data);
 	}
+	Ok(())
 }
 
 #[cfg(test)]
@@ -1,13 +1,13 @@
 use super::{instruction_id, packet_id};
-use crate::{Client, Response, TransferError, WriteError};
+use crate::{Client, ClientError, ErrorContext, Response};
 
 /// The parameters for the CLEAR command to clear the revolution counter.
-const CLEAR_REVOLUTION_COUNT: [u8; 5] = [0x01, 0x44, 0x58, 0x4C, 0x22];
+pub(crate) const CLEAR_REVOLUTION_COUNT: [u8; 5] = [0x01, 0x44, 0x58, 0x4C, 0x22];
 
 /// The parameters for the CLEAR command to clear the error state.
 ///
 /// This is only supported on some motors.
-const CLEAR_ERROR: [u8; 5] = [0x01, 0x45, 0x52, 0x43, 0x4C];
+pub(crate) const CLEAR_ERROR: [u8; 5] = [0x01, 0x45, 0x52, 0x43, 0x4C];
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
 where
@@ -24,9 +24,11 @@ where
 	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
 	///
 	/// If you want to broadcast this instruction, it may be more convenient to use [`Self::broadcast_clear_revolution_counter()`] instead.
-	pub fn clear_revolution_counter(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::CLEAR, CLEAR_REVOLUTION_COUNT.len(), clear_revolution_count_parameters)?;
-		Ok(super::read_response_if_not_broadcast(self, motor_id)?)
+	pub fn clear_revolution_counter(&mut self, motor_id: u8) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::CLEAR, motor_id), |client| {
+			client.write_instruction(motor_id, instruction_id::CLEAR, CLEAR_REVOLUTION_COUNT.len(), clear_revolution_count_parameters)?;
+			Ok(super::read_response_if_expected(client, motor_id, instruction_id::CLEAR)?)
+		})
 	}
 
 	/// Clear the revolution counter of all connected motors.
@@ -34,14 +36,16 @@ where
 	/// This will reset the "present position" register to a value between 0 and a whole revolution.
 	/// It is not possible to clear the mutli-revolution counter of a motor while it is moving.
 	/// Doing so will cause the motor to return an error, and the revolution counter will not be reset.
-	pub fn broadcast_clear_revolution_counter(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
-		self.write_instruction(
-			packet_id::BROADCAST,
-			instruction_id::CLEAR,
-			CLEAR_REVOLUTION_COUNT.len(),
-			clear_revolution_count_parameters,
-		)?;
-		Ok(())
+	pub fn broadcast_clear_revolution_counter(&mut self) -> Result<(), ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::CLEAR, packet_id::BROADCAST), |client| {
+			client.write_instruction(
+				packet_id::BROADCAST,
+				instruction_id::CLEAR,
+				CLEAR_REVOLUTION_COUNT.len(),
+				clear_revolution_count_parameters,
+			)?;
+			Ok(())
+		})
 	}
 
 	/// Clear the error of a motor.
@@ -50,10 +54,11 @@ where
 	/// If the error cannot be cleared, the function returns a [`MotorError`](crate::MotorError) with error code `0x01`.
 	///
 	/// This instruction is currently only implemented on the Dynamixel Y series.
-	pub fn clear_error(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::CLEAR, CLEAR_ERROR.len(), clear_error_parameters)?;
-		Ok(super::read_response_if_not_broadcast(self, motor_id)?)
-
+	pub fn clear_error(&mut self, motor_id: u8) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::CLEAR, motor_id), |client| {
+			client.write_instruction(motor_id, instruction_id::CLEAR, CLEAR_ERROR.len(), clear_error_parameters)?;
+			Ok(super::read_response_if_expected(client, motor_id, instruction_id::CLEAR)?)
+		})
 	}
 
 	/// Try to clear the error of all motors on the bus.
@@ -62,23 +67,25 @@ where
 	/// and if the instruction is supported by the motor.
     ///
 	/// This instruction is currently only implemented on the Dynamixel Y series.
-	pub fn broadcast_clear_error(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
-		self.write_instruction(
-			packet_id::BROADCAST,
-			instruction_id::CLEAR,
-			CLEAR_ERROR.len(),
-			clear_error_parameters,
-		)?;
-		Ok(())
+	pub fn broadcast_clear_error(&mut self) -> Result<(), ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::CLEAR, packet_id::BROADCAST), |client| {
+			client.write_instruction(
+				packet_id::BROADCAST,
+				instruction_id::CLEAR,
+				CLEAR_ERROR.len(),
+				clear_error_parameters,
+			)?;
+			Ok(())
+		})
 	}
 }
 
-fn clear_revolution_count_parameters(buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
+pub(crate) fn clear_revolution_count_parameters(buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
 	buffer.copy_from_slice(&CLEAR_REVOLUTION_COUNT);
 	Ok(())
 }
 
-fn clear_error_parameters(buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
+pub(crate) fn clear_error_parameters(buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
 	buffer.copy_from_slice(&CLEAR_ERROR);
 	Ok(())
 }
@@ -0,0 +1,70 @@
+use super::{instruction_id, packet_id};
+use crate::{Client, ClientError, ErrorContext, Response};
+
+/// The parameters for the CONTROL_TABLE_BACKUP command to store the control table in the backup area.
+pub(crate) const BACKUP: [u8; 5] = [0x01, 0x43, 0x54, 0x52, 0x4C];
+
+/// The parameters for the CONTROL_TABLE_BACKUP command to restore the control table from the backup area.
+pub(crate) const RESTORE: [u8; 5] = [0x02, 0x43, 0x54, 0x52, 0x4C];
+
+impl<SerialPort, Buffer> Client<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Store the current control table of a motor in its backup area.
+	///
+	/// The backup can later be restored with [`Self::control_table_restore()`].
+	/// Most motors only accept this instruction while torque is disabled.
+	///
+	/// You may specify [`crate::instructions::packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	///
+	/// If you want to broadcast this instruction, it may be more convenient to use [`Self::broadcast_control_table_backup()`] instead.
+	pub fn control_table_backup(&mut self, motor_id: u8) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::CONTROL_TABLE_BACKUP, motor_id), |client| {
+			client.write_instruction(motor_id, instruction_id::CONTROL_TABLE_BACKUP, BACKUP.len(), backup_parameters)?;
+			Ok(super::read_response_if_expected(client, motor_id, instruction_id::CONTROL_TABLE_BACKUP)?)
+		})
+	}
+
+	/// Store the current control table of all connected motors in their backup area.
+	pub fn broadcast_control_table_backup(&mut self) -> Result<(), ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::CONTROL_TABLE_BACKUP, packet_id::BROADCAST), |client| {
+			Ok(client.write_instruction(packet_id::BROADCAST, instruction_id::CONTROL_TABLE_BACKUP, BACKUP.len(), backup_parameters)?)
+		})
+	}
+
+	/// Restore the control table of a motor from its backup area.
+	///
+	/// The backup must have been made earlier with [`Self::control_table_backup()`].
+	/// Most motors only accept this instruction while torque is disabled.
+	///
+	/// You may specify [`crate::instructions::packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	///
+	/// If you want to broadcast this instruction, it may be more convenient to use [`Self::broadcast_control_table_restore()`] instead.
+	pub fn control_table_restore(&mut self, motor_id: u8) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::CONTROL_TABLE_BACKUP, motor_id), |client| {
+			client.write_instruction(motor_id, instruction_id::CONTROL_TABLE_BACKUP, RESTORE.len(), restore_parameters)?;
+			Ok(super::read_response_if_expected(client, motor_id, instruction_id::CONTROL_TABLE_BACKUP)?)
+		})
+	}
+
+	/// Restore the control table of all connected motors from their backup area.
+	pub fn broadcast_control_table_restore(&mut self) -> Result<(), ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::CONTROL_TABLE_BACKUP, packet_id::BROADCAST), |client| {
+			Ok(client.write_instruction(packet_id::BROADCAST, instruction_id::CONTROL_TABLE_BACKUP, RESTORE.len(), restore_parameters)?)
+		})
+	}
+}
+
+pub(crate) fn backup_parameters(buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
+	buffer.copy_from_slice(&BACKUP);
+	Ok(())
+}
+
+pub(crate) fn restore_parameters(buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
+	buffer.copy_from_slice(&RESTORE);
+	Ok(())
+}
@@ -1,5 +1,5 @@
 use super::{instruction_id, packet_id};
-use crate::{Client, Response, TransferError, WriteError};
+use crate::{Client, ClientError, ErrorContext, Response};
 
 /// The kind of factory reset to perform.
 #[repr(u8)]
@@ -37,12 +37,14 @@ where
 	/// At that point, communication with those motors is not possible anymore.
 	/// The only way to restore communication is to physically disconnect all but one motor at a time and re-assign unique IDs.
 	/// Or use the ID Inspection Tool in the Dynamixel Wizard 2.0
-	pub fn factory_reset(&mut self, motor_id: u8, kind: FactoryResetKind) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::FACTORY_RESET, 1, |buffer| {
-			buffer[0] = kind as u8;
-			Ok(())
-		})?;
-		Ok(super::read_response_if_not_broadcast(self, motor_id)?)
+	pub fn factory_reset(&mut self, motor_id: u8, kind: FactoryResetKind) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::FACTORY_RESET, motor_id), |client| {
+			client.write_instruction(motor_id, instruction_id::FACTORY_RESET, 1, |buffer| {
+				buffer[0] = kind as u8;
+				Ok(())
+			})?;
+			Ok(super::read_response_if_expected(client, motor_id, instruction_id::FACTORY_RESET)?)
+		})
 	}
 
 	/// Reset the settings of all connected motors to the factory defaults.
@@ -56,11 +58,13 @@ where
 	/// which would cause multiple motors on the bus to have the same ID.
 	/// At that point, communication with those motors is not possible anymore.
 	/// The only way to restore communication is to physically disconnect all but one motor at a time and re-assign unique IDs.
-	pub fn broadcast_factory_reset(&mut self, kind: FactoryResetKind) -> Result<(), WriteError<SerialPort::Error>> {
-		self.write_instruction(packet_id::BROADCAST, instruction_id::FACTORY_RESET, 1, |buffer| {
-			buffer[0] = kind as u8;
+	pub fn broadcast_factory_reset(&mut self, kind: FactoryResetKind) -> Result<(), ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::FACTORY_RESET, packet_id::BROADCAST), |client| {
+			client.write_instruction(packet_id::BROADCAST, instruction_id::FACTORY_RESET, 1, |buffer| {
+				buffer[0] = kind as u8;
+				Ok(())
+			})?;
 			Ok(())
-		})?;
-		Ok(())
+		})
 	}
 }
@@ -0,0 +1,197 @@
+use core::marker::PhantomData;
+
+use super::bulk_read::write_bulk_read_instruction;
+use super::{instruction_id, packet_id, BulkReadData};
+use crate::{Client, ClientError, ErrorContext, Response};
+
+impl<SerialPort, Buffer> Client<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Synchronously read arbitrary data ranges from multiple motors, with a single combined response.
+	///
+	/// Like a regular bulk read, this can be used to read a different amount of data from a different address for each motor.
+	/// Unlike [`Self::bulk_read_bytes`], all motors reply together in one status packet.
+	/// This avoids the overhead of a separate header and turnaround time for each motor.
+	///
+	/// The combined response is received before this function returns.
+	/// The returned iterator yields the reply of each motor in the order of `reads`.
+	/// If a motor reports an error, only the item for that motor is an error,
+	/// with the ID of that motor and the registers it was asked for in the [`ErrorContext`].
+	///
+	/// # Panics
+	/// The protocol forbids specifying the same motor ID multiple times.
+	/// This function panics if the same motor ID is used for more than one read.
+	pub fn fast_bulk_read_bytes<'a, T>(
+		&'a mut self,
+		reads: &'a [BulkReadData],
+	) -> Result<FastBulkRead<'a, T, SerialPort::Error>, ClientError<SerialPort::Error>>
+	where
+		T: From<&'a [u8]>,
+	{
+		let packet_len = self.with_error_context(ErrorContext::new(instruction_id::FAST_BULK_READ, packet_id::BROADCAST), |client| {
+			write_bulk_read_instruction(client, instruction_id::FAST_BULK_READ, reads)?;
+
+			if reads.is_empty() {
+				return Ok(None);
+			}
+
+			let parameters = fast_bulk_read_response_parameters(reads);
+			Ok(Some(super::receive_fast_read_response(client, parameters)?))
+		})?;
+		Ok(FastBulkRead::new(super::received_fast_read_response(self, packet_len), reads))
+	}
+}
+
+/// Get the number of parameters in the combined response of a fast bulk read.
+pub(crate) fn fast_bulk_read_response_parameters(reads: &[BulkReadData]) -> usize {
+	super::fast_read_response_parameters(reads.iter().map(|read| read.count))
+}
+
+macro_rules! make_fast_bulk_read_struct {
+	($($DefaultError:ty)?) => {
+		/// The combined response of a fast bulk read, that yields the reply of each motor when iterated.
+		///
+		/// The `E` generic type argument is the error type of the serial port.
+		/// It defaults to [`std::io::Error`] if the `"std"` feature is enabled.
+		pub struct FastBulkRead<'a, T, E $(= $DefaultError)?> {
+			packet: &'a [u8],
+			bulk_read_data: &'a [BulkReadData],
+			index: usize,
+			offset: usize,
+			data: PhantomData<fn() -> (T, E)>,
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+make_fast_bulk_read_struct!(std::io::Error);
+
+#[cfg(not(feature = "std"))]
+make_fast_bulk_read_struct!();
+
+impl<'a, T, E> FastBulkRead<'a, T, E> {
+	pub(crate) fn new(packet: &'a [u8], bulk_read_data: &'a [BulkReadData]) -> Self {
+		Self {
+			packet,
+			bulk_read_data,
+			index: 0,
+			// The first segment starts at the error field of the status packet.
+			offset: crate::bus::HEADER_SIZE + 1,
+			data: PhantomData,
+		}
+	}
+
+	/// Get the number of motor replies that have not been yielded yet.
+	pub fn remaining(&self) -> usize {
+		self.bulk_read_data.len() - self.index
+	}
+
+	/// Split off the reply of the next motor.
+	///
+	/// Errors have the ID of the motor and the registers it was asked for in the [`ErrorContext`].
+	pub fn read_next(&mut self) -> Option<Result<Response<T>, ClientError<E>>>
+	where
+		T: From<&'a [u8]>,
+	{
+		let BulkReadData { motor_id, address, count } = *self.bulk_read_data.get(self.index)?;
+		self.index += 1;
+		let response = super::split_fast_read_segment::<E>(self.packet, &mut self.offset, motor_id, count);
+		Some(match response {
+			Ok(response) => Ok(Response {
+				motor_id: response.motor_id,
+				alert: response.alert,
+				data: T::from(response.data),
+			}),
+			Err(error) => Err(ClientError {
+				error: error.into(),
+				context: Some(ErrorContext::new(instruction_id::FAST_BULK_READ, motor_id).with_registers(address, count)),
+			}),
+		})
+	}
+}
+
+impl<T, E> core::fmt::Debug for FastBulkRead<'_, T, E> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("FastBulkRead")
+			.field("bulk_read_data", &self.bulk_read_data)
+			.field("index", &self.index)
+			.field("data", &format_args!("{}", core::any::type_name::<T>()))
+			.finish()
+	}
+}
+
+impl<'a, T, E> Iterator for FastBulkRead<'a, T, E>
+where
+	T: From<&'a [u8]>,
+{
+	type Item = Result<Response<T>, ClientError<E>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.read_next()
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining(), Some(self.remaining()))
+	}
+}
+
+impl<'a, T, E> ExactSizeIterator for FastBulkRead<'a, T, E>
+where
+	T: From<&'a [u8]>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::serial_port::reply::{fast_read_response, fast_read_response_with_bad_crc, make_client};
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn fast_bulk_read_splits_segments_of_different_size() {
+		let mut client = make_client(fast_read_response(&[
+			(0x00, 1, &[1, 2, 3, 4]),
+			(0x00, 5, &[5]),
+			(0x80, 9, &[6, 7]),
+		]));
+		let reads = [
+			BulkReadData { motor_id: 1, address: 132, count: 4 },
+			BulkReadData { motor_id: 5, address: 146, count: 1 },
+			BulkReadData { motor_id: 9, address: 126, count: 2 },
+		];
+		let_assert!(Ok(responses) = client.fast_bulk_read_bytes::<&[u8]>(&reads));
+		assert!(responses.len() == 3);
+		let responses: Vec<_> = responses.map(|response| response.map(|response| (response.motor_id, response.alert, response.data.to_vec()))).collect();
+		let_assert!([Ok(first), Ok(second), Ok(third)] = responses.as_slice());
+		assert!(first == &(1, false, vec![1, 2, 3, 4]));
+		assert!(second == &(5, false, vec![5]));
+		assert!(third == &(9, true, vec![6, 7]));
+
+		let written = &client.serial_port().written;
+		assert!(written[7] == instruction_id::FAST_BULK_READ);
+		assert!(written[8..23] == [1, 132, 0, 4, 0, 5, 146, 0, 1, 0, 9, 126, 0, 2, 0]);
+	}
+
+	#[test]
+	fn fast_bulk_read_checks_segment_crc() {
+		let mut client = make_client(fast_read_response_with_bad_crc(
+			&[
+				(0x00, 1, &[1, 2, 3, 4]),
+				(0x00, 5, &[5]),
+			],
+			Some(0),
+		));
+		let reads = [
+			BulkReadData { motor_id: 1, address: 132, count: 4 },
+			BulkReadData { motor_id: 5, address: 146, count: 1 },
+		];
+		let_assert!(Ok(responses) = client.fast_bulk_read_bytes::<Vec<u8>>(&reads));
+		let responses: Vec<_> = responses.collect();
+		let_assert!([Err(first), Ok(second)] = responses.as_slice());
+		assert!(let crate::TransferError::ReadError(crate::ReadError::InvalidMessage(crate::InvalidMessage::InvalidChecksum(_))) = first.error);
+		assert!(first.context == Some(ErrorContext::new(instruction_id::FAST_BULK_READ, 1).with_registers(132, 4)));
+		assert!(second == &Response { motor_id: 5, alert: false, data: vec![5] });
+	}
+}
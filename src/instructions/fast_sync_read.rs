@@ -0,0 +1,270 @@
+use core::marker::PhantomData;
+
+use super::{instruction_id, packet_id};
+use crate::bus::data::Data;
+use crate::{Client, ClientError, ErrorContext, Response, TransferError};
+
+impl<SerialPort, Buffer> Client<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Synchronously read a number of bytes from multiple motors, with a single combined response.
+	///
+	/// Unlike [`Self::sync_read_bytes`], all motors reply together in one status packet.
+	/// This avoids the overhead of a separate header and turnaround time for each motor.
+	///
+	/// The combined response is received before this function returns.
+	/// The returned iterator yields the reply of each motor in the order of `motor_ids`.
+	/// If a motor reports an error, only the item for that motor is an error,
+	/// with the ID of that motor in the [`ErrorContext`].
+	pub fn fast_sync_read_bytes<'a, T>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+		count: u16,
+	) -> Result<FastSyncRead<'a, T, SerialPort::Error>, ClientError<SerialPort::Error>>
+	where
+		T: From<&'a [u8]>,
+	{
+		let context = ErrorContext::new(instruction_id::FAST_SYNC_READ, packet_id::BROADCAST).with_registers(address, count);
+		let packet_len = self.with_error_context(context, |client| transfer_fast_sync_read(client, motor_ids, address, count))?;
+		let packet = super::received_fast_read_response(self, packet_len);
+		Ok(FastSyncRead::new(packet, motor_ids, address, count, |data| Ok(T::from(data))))
+	}
+
+	/// Synchronously read values from multiple motors, with a single combined response.
+	///
+	/// Unlike [`Self::sync_read`], all motors reply together in one status packet.
+	/// This avoids the overhead of a separate header and turnaround time for each motor.
+	///
+	/// The combined response is received before this function returns.
+	/// The returned iterator yields the reply of each motor in the order of `motor_ids`.
+	/// If a motor reports an error, only the item for that motor is an error,
+	/// with the ID of that motor in the [`ErrorContext`].
+	pub fn fast_sync_read<'a, T: Data>(
+		&'a mut self,
+		motor_ids: &'a [u8],
+		address: u16,
+	) -> Result<FastSyncRead<'a, T, SerialPort::Error>, ClientError<SerialPort::Error>> {
+		let context = ErrorContext::new(instruction_id::FAST_SYNC_READ, packet_id::BROADCAST).with_registers(address, T::ENCODED_SIZE);
+		let packet_len = self.with_error_context(context, |client| transfer_fast_sync_read(client, motor_ids, address, T::ENCODED_SIZE))?;
+		let packet = super::received_fast_read_response(self, packet_len);
+		Ok(FastSyncRead::new(packet, motor_ids, address, T::ENCODED_SIZE, T::decode))
+	}
+}
+
+/// Write a fast sync read instruction and read the combined response.
+///
+/// Returns the length of the status packet, or `None` if there are no motors to read from.
+fn transfer_fast_sync_read<SerialPort, Buffer>(
+	client: &mut Client<SerialPort, Buffer>,
+	motor_ids: &[u8],
+	address: u16,
+	count: u16,
+) -> Result<Option<usize>, TransferError<SerialPort::Error>>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	client.write_instruction(packet_id::BROADCAST, instruction_id::FAST_SYNC_READ, 4 + motor_ids.len(), |buffer| {
+		super::sync_read::encode_sync_read_parameters(buffer, motor_ids, address, count)
+	})?;
+
+	if motor_ids.is_empty() {
+		return Ok(None);
+	}
+
+	let parameters = fast_sync_read_response_parameters(motor_ids, count);
+	Ok(Some(super::receive_fast_read_response(client, parameters)?))
+}
+
+/// Get the number of parameters in the combined response of a fast sync read.
+pub(crate) fn fast_sync_read_response_parameters(motor_ids: &[u8], count: u16) -> usize {
+	super::fast_read_response_parameters(motor_ids.iter().map(|_| count))
+}
+
+macro_rules! make_fast_sync_read_struct {
+	($($DefaultError:ty)?) => {
+		/// The combined response of a fast sync read, that yields the reply of each motor when iterated.
+		///
+		/// The `E` generic type argument is the error type of the serial port.
+		/// It defaults to [`std::io::Error`] if the `"std"` feature is enabled.
+		pub struct FastSyncRead<'a, T, E $(= $DefaultError)?> {
+			packet: &'a [u8],
+			motor_ids: &'a [u8],
+			address: u16,
+			count: u16,
+			index: usize,
+			offset: usize,
+			decode: fn(&'a [u8]) -> Result<T, crate::InvalidMessage>,
+			error: PhantomData<fn() -> E>,
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+make_fast_sync_read_struct!(std::io::Error);
+
+#[cfg(not(feature = "std"))]
+make_fast_sync_read_struct!();
+
+impl<'a, T, E> FastSyncRead<'a, T, E> {
+	pub(crate) fn new(
+		packet: &'a [u8],
+		motor_ids: &'a [u8],
+		address: u16,
+		count: u16,
+		decode: fn(&'a [u8]) -> Result<T, crate::InvalidMessage>,
+	) -> Self {
+		Self {
+			packet,
+			motor_ids,
+			address,
+			count,
+			index: 0,
+			// The first segment starts at the error field of the status packet.
+			offset: crate::bus::HEADER_SIZE + 1,
+			decode,
+			error: PhantomData,
+		}
+	}
+
+	/// Get the number of motor replies that have not been yielded yet.
+	pub fn remaining(&self) -> usize {
+		self.motor_ids.len() - self.index
+	}
+
+	/// Decode the reply of the next motor.
+	///
+	/// Errors have the ID of the motor in the [`ErrorContext`].
+	pub fn read_next(&mut self) -> Option<Result<Response<T>, ClientError<E>>> {
+		let motor_id = *self.motor_ids.get(self.index)?;
+		self.index += 1;
+		Some(self.next_response(motor_id).map_err(|error| ClientError {
+			error: error.into(),
+			context: Some(ErrorContext::new(instruction_id::FAST_SYNC_READ, motor_id).with_registers(self.address, self.count)),
+		}))
+	}
+
+	fn next_response(&mut self, motor_id: u8) -> Result<Response<T>, crate::ReadError<E>> {
+		let response = super::split_fast_read_segment(self.packet, &mut self.offset, motor_id, self.count)?;
+		Ok(Response {
+			motor_id: response.motor_id,
+			alert: response.alert,
+			data: (self.decode)(response.data)?,
+		})
+	}
+}
+
+impl<T, E> core::fmt::Debug for FastSyncRead<'_, T, E> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("FastSyncRead")
+			.field("motor_ids", &self.motor_ids)
+			.field("address", &self.address)
+			.field("count", &self.count)
+			.field("index", &self.index)
+			.field("data", &format_args!("{}", core::any::type_name::<T>()))
+			.finish()
+	}
+}
+
+impl<T, E> Iterator for FastSyncRead<'_, T, E> {
+	type Item = Result<Response<T>, ClientError<E>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.read_next()
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining(), Some(self.remaining()))
+	}
+}
+
+impl<T, E> ExactSizeIterator for FastSyncRead<'_, T, E> {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use assert2::{assert, let_assert};
+	use crate::ReadError;
+	use crate::serial_port::reply::{fast_read_response, fast_read_response_with_bad_crc, make_client};
+
+	#[test]
+	fn fast_sync_read_decodes_all_motors() {
+		let mut client = make_client(fast_read_response(&[
+			(0x00, 3, &1000u32.to_le_bytes()),
+			(0x80, 7, &2000u32.to_le_bytes()),
+		]));
+		let_assert!(Ok(responses) = client.fast_sync_read::<u32>(&[3, 7], 132));
+		assert!(responses.len() == 2);
+		let responses: Vec<_> = responses.collect();
+		let_assert!([Ok(first), Ok(second)] = responses.as_slice());
+		assert!(first == &Response { motor_id: 3, alert: false, data: 1000 });
+		assert!(second == &Response { motor_id: 7, alert: true, data: 2000 });
+
+		let written = &client.serial_port().written;
+		assert!(written[4] == packet_id::BROADCAST);
+		assert!(written[7] == instruction_id::FAST_SYNC_READ);
+		assert!(written[8..14] == [132, 0, 4, 0, 3, 7]);
+	}
+
+	#[test]
+	fn fast_sync_read_bytes_borrows_data() {
+		let mut client = make_client(fast_read_response(&[
+			(0x00, 1, &[1, 2]),
+			(0x00, 2, &[3, 4]),
+			(0x00, 3, &[5, 6]),
+		]));
+		let_assert!(Ok(responses) = client.fast_sync_read_bytes::<&[u8]>(&[1, 2, 3], 10, 2));
+		let data: Vec<_> = responses.map(|response| response.map(|response| response.data.to_vec())).collect();
+		let_assert!([Ok(first), Ok(second), Ok(third)] = data.as_slice());
+		assert!(first == &[1, 2]);
+		assert!(second == &[3, 4]);
+		assert!(third == &[5, 6]);
+	}
+
+	#[test]
+	fn fast_sync_read_removes_byte_stuffing() {
+		let mut client = make_client(fast_read_response(&[
+			(0x00, 1, &[0xFF, 0xFF, 0xFD, 0xFD]),
+			(0x00, 2, &[0x00, 0xFF, 0xFF, 0xFD]),
+			(0x00, 3, &[0xFF, 0xFF, 0xFD, 0x00]),
+		]));
+		let_assert!(Ok(responses) = client.fast_sync_read::<u32>(&[1, 2, 3], 132));
+		let responses: Vec<_> = responses.collect();
+		let_assert!([Ok(first), Ok(second), Ok(third)] = responses.as_slice());
+		assert!(first.data == 0xFDFD_FFFF);
+		assert!(second.data == 0xFDFF_FF00);
+		assert!(third.data == 0x00FD_FFFF);
+	}
+
+	#[test]
+	fn fast_sync_read_reports_errors_per_motor() {
+		let response = fast_read_response_with_bad_crc(
+			&[
+				(0x00, 1, &[1, 2]),
+				(0x04, 2, &[3, 4]),
+				(0x00, 3, &[5, 6]),
+			],
+			Some(0),
+		);
+		let mut client = make_client(response);
+		let_assert!(Ok(responses) = client.fast_sync_read::<u16>(&[1, 2, 3], 10));
+		let responses: Vec<_> = responses.collect();
+		let_assert!([Err(first), Err(second), Ok(third)] = responses.as_slice());
+		assert!(let TransferError::ReadError(ReadError::InvalidMessage(crate::InvalidMessage::InvalidChecksum(_))) = first.error);
+		assert!(first.context == Some(ErrorContext::new(instruction_id::FAST_SYNC_READ, 1).with_registers(10, 2)));
+		let_assert!(TransferError::ReadError(ReadError::MotorError(error)) = &second.error);
+		assert!(error.error_number() == 0x04);
+		assert!(second.context == Some(ErrorContext::new(instruction_id::FAST_SYNC_READ, 2).with_registers(10, 2)));
+		assert!(third == &Response { motor_id: 3, alert: false, data: 0x0605 });
+	}
+
+	#[test]
+	fn fast_sync_read_checks_response_length() {
+		let mut client = make_client(fast_read_response(&[(0x00, 1, &[1, 2])]));
+		let_assert!(Err(ClientError { error: TransferError::ReadError(ReadError::InvalidMessage(error)), .. }) = client.fast_sync_read::<u16>(&[1, 2], 10));
+		assert!(let crate::InvalidMessage::InvalidParameterCount(_) = error);
+	}
+}
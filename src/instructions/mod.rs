@@ -1,23 +1,27 @@
 //! Types and functions for specific instructions.
 use crate::error::ReadError;
+use crate::bus::endian::read_u16_le;
 
 /// Raw instructions IDs.
 #[rustfmt::skip]
 #[allow(missing_docs)]
 pub mod instruction_id {
-	pub const PING          : u8 = 0x01;
-	pub const READ          : u8 = 0x02;
-	pub const WRITE         : u8 = 0x03;
-	pub const REG_WRITE     : u8 = 0x04;
-	pub const ACTION        : u8 = 0x05;
-	pub const FACTORY_RESET : u8 = 0x06;
-	pub const REBOOT        : u8 = 0x08;
-	pub const CLEAR         : u8 = 0x10;
-	pub const SYNC_READ     : u8 = 0x82;
-	pub const SYNC_WRITE    : u8 = 0x83;
-	pub const BULK_READ     : u8 = 0x92;
-	pub const BULK_WRITE    : u8 = 0x93;
-	pub const STATUS        : u8 = 0x55;
+	pub const PING                 : u8 = 0x01;
+	pub const READ                 : u8 = 0x02;
+	pub const WRITE                : u8 = 0x03;
+	pub const REG_WRITE            : u8 = 0x04;
+	pub const ACTION               : u8 = 0x05;
+	pub const FACTORY_RESET        : u8 = 0x06;
+	pub const REBOOT               : u8 = 0x08;
+	pub const CLEAR                : u8 = 0x10;
+	pub const CONTROL_TABLE_BACKUP : u8 = 0x20;
+	pub const SYNC_READ            : u8 = 0x82;
+	pub const SYNC_WRITE           : u8 = 0x83;
+	pub const FAST_SYNC_READ       : u8 = 0x8A;
+	pub const BULK_READ            : u8 = 0x92;
+	pub const BULK_WRITE           : u8 = 0x93;
+	pub const FAST_BULK_READ       : u8 = 0x9A;
+	pub const STATUS               : u8 = 0x55;
 }
 
 /// Special packet IDs.
@@ -27,19 +31,24 @@ pub mod packet_id {
 }
 
 mod action;
-mod bulk_read;
-mod bulk_write;
-mod clear;
+pub(crate) mod bulk_read;
+pub(crate) mod bulk_write;
+pub(crate) mod clear;
+pub(crate) mod control_table_backup;
 mod factory_reset;
+pub(crate) mod fast_bulk_read;
+pub(crate) mod fast_sync_read;
 mod ping;
 mod read;
 mod reboot;
 mod reg_write;
-mod sync_read;
-mod sync_write;
+pub(crate) mod sync_read;
+pub(crate) mod sync_write;
 mod write;
 
 pub use factory_reset::FactoryResetKind;
+pub use fast_bulk_read::FastBulkRead;
+pub use fast_sync_read::FastSyncRead;
 pub use ping::{Ping, Scan};
 pub use sync_read::SyncRead;
 
@@ -87,7 +96,7 @@ impl<T> AsRef<BulkWriteData<T>> for BulkWriteData<T> {
 
 /// Parameters for a bulk read instruction.
 ///
-/// Use with [`crate::Client::bulk_read_bytes`].
+/// Use with [`crate::Client::bulk_read_bytes`] and [`crate::Client::fast_bulk_read_bytes`].
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct BulkReadData {
 	/// The ID of the motor.
@@ -100,24 +109,126 @@ pub struct BulkReadData {
 	pub count: u16,
 }
 
-/// Read an empty response from the bus if the motor ID is not the broadcast ID.
+/// Read an empty response from the bus if the motor is expected to reply to the instruction.
 ///
-/// If the motor ID is the broadcast ID, return a fake response from the broadcast ID.
-fn read_response_if_not_broadcast<SerialPort, Buffer>(
+/// If the motor ID is the broadcast ID, or if the motor does not reply at its recorded status return level,
+/// return a fake response from the motor ID without waiting.
+fn read_response_if_expected<SerialPort, Buffer>(
 	client: &mut crate::Client<SerialPort, Buffer>,
 	motor_id: u8,
+	instruction_id: u8,
 ) -> Result<crate::Response<()>, ReadError<SerialPort::Error>>
 where
 	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	if motor_id == packet_id::BROADCAST {
+	if client.expects_reply(motor_id, instruction_id) {
+		Ok(client.read_status_response(0)?.try_into()?)
+	} else {
 		Ok(crate::Response {
-			motor_id: packet_id::BROADCAST,
+			motor_id,
 			alert: false,
 			data: (),
 		})
-	} else {
-		Ok(client.read_status_response(0)?.try_into()?)
 	}
 }
+
+/// Get the number of parameters in a combined fast read response.
+///
+/// Each motor adds an error field, ID, data and CRC.
+/// The error field of the first motor is not counted as parameter, and the CRC of the last motor is the packet CRC.
+///
+/// The data count of at least one motor must be given.
+fn fast_read_response_parameters(counts: impl IntoIterator<Item = u16>) -> usize {
+	counts.into_iter().map(|count| 4 + usize::from(count)).sum::<usize>() - 3
+}
+
+/// Check the packet ID and the length of a combined fast read response.
+///
+/// Returns the raw bytes of the status packet, without the final CRC.
+pub(crate) fn check_fast_read_response(response: crate::bus::StatusPacket<'_>, parameters: usize) -> Result<&[u8], crate::InvalidMessage> {
+	crate::InvalidPacketId::check(response.packet_id(), packet_id::BROADCAST)?;
+	crate::InvalidParameterCount::check(response.parameters().len(), parameters)?;
+	Ok(response.as_bytes())
+}
+
+/// Read the combined response of a fast sync read or fast bulk read into the read buffer.
+///
+/// Returns the length of the status packet, which can be passed to [`received_fast_read_response()`].
+fn receive_fast_read_response<SerialPort, Buffer>(
+	client: &mut crate::Client<SerialPort, Buffer>,
+	parameters: usize,
+) -> Result<usize, ReadError<SerialPort::Error>>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	let timeout = crate::bus::status_response_timeout(parameters, client.baud_rate());
+	let packet_len = client.receive_status_response_unchecked(crate::bus::ExpectedPacket::fast_read_response(parameters, timeout))?;
+	check_fast_read_response(client.received_status_packet(packet_len), parameters)?;
+	Ok(packet_len)
+}
+
+/// Get the raw bytes of a combined fast read response, without the final CRC.
+///
+/// The `packet_len` must be the value returned by [`receive_fast_read_response()`],
+/// or `None` if no response was read because the instruction did not include any motors.
+fn received_fast_read_response<SerialPort, Buffer>(
+	client: &crate::Client<SerialPort, Buffer>,
+	packet_len: Option<usize>,
+) -> &[u8]
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	match packet_len {
+		Some(packet_len) => client.received_status_packet(packet_len).as_bytes(),
+		None => &[],
+	}
+}
+
+/// Split off the segment of the next motor from a combined fast read response.
+///
+/// The segment starts at `offset` in the raw (unstuffed) status packet,
+/// and `offset` is advanced past the segment, even if the segment is invalid.
+///
+/// Each segment consists of an error field, the motor ID, `count` bytes of data and a CRC.
+/// The CRC of a segment is the running checksum of the packet as it was sent on the bus (with byte-stuffing),
+/// up to and including the data of the segment.
+/// The CRC of the last segment is the CRC of the whole packet,
+/// which has already been checked when the packet was read.
+fn split_fast_read_segment<'a, E>(
+	packet: &'a [u8],
+	offset: &mut usize,
+	motor_id: u8,
+	count: u16,
+) -> Result<crate::Response<&'a [u8]>, ReadError<E>> {
+	let start = *offset;
+	let data_end = start + 2 + usize::from(count);
+	*offset = data_end + 2;
+
+	crate::InvalidParameterCount::check_min(packet.len(), data_end)?;
+	if data_end < packet.len() {
+		crate::InvalidParameterCount::check_min(packet.len(), data_end + 2)?;
+		let checksum_message = read_u16_le(&packet[data_end..]);
+		let checksum_computed = crate::checksum::calculate_checksum(0, &packet[..crate::bus::HEADER_SIZE]);
+		let checksum_computed =
+			crate::bus::bytestuff::stuffed_checksum(checksum_computed, &mut 0, &packet[crate::bus::HEADER_SIZE..data_end]);
+		if checksum_message != checksum_computed {
+			return Err(crate::InvalidChecksum {
+				message: checksum_message,
+				computed: checksum_computed,
+			}
+			.into());
+		}
+	}
+
+	let error = packet[start];
+	crate::InvalidPacketId::check(packet[start + 1], motor_id)?;
+	crate::MotorError::check(error)?;
+	Ok(crate::Response {
+		motor_id,
+		alert: error & 0x80 != 0,
+		data: &packet[start + 2..data_end],
+	})
+}
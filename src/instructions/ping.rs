@@ -2,7 +2,7 @@ use core::time::Duration;
 
 use super::{instruction_id, packet_id};
 use crate::bus::StatusPacket;
-use crate::{Client, ReadError, Response, TransferError};
+use crate::{Client, ClientError, ErrorContext, ReadError, Response};
 
 /// A response from a motor to a ping instruction.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -42,14 +42,21 @@ where
 	///
 	/// This will not work correctly if the motor ID is [`packet_id::BROADCAST`].
 	/// Use [`Self::scan`] instead.
-	pub fn ping(&mut self, motor_id: u8) -> Result<Response<Ping>, TransferError<SerialPort::Error>> {
-		let response = self.transfer_single(motor_id, instruction_id::PING, 0, 3, |_| Ok(()))?;
-		Ok(response.try_into()?)
+	///
+	/// The ping is retried according to the [retry policy][Self::set_retry_policy] of the client.
+	pub fn ping(&mut self, motor_id: u8) -> Result<Response<Ping>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::PING, motor_id), |client| {
+			let packet_len = client.transfer_single_with_retry(motor_id, instruction_id::PING, 0, 3, |_| Ok(()))?;
+			Ok(client.received_status_packet(packet_len).try_into()?)
+		})
 	}
 
 	/// Scan the bus for motors with a broadcast ping
-	pub fn scan(&mut self) -> Result<Scan<SerialPort, Buffer>, crate::WriteError<SerialPort::Error>> {
-		self.write_instruction(packet_id::BROADCAST, instruction_id::PING, 0, |_| Ok(()))?;
+	pub fn scan(&mut self) -> Result<Scan<'_, SerialPort, Buffer>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::PING, packet_id::BROADCAST), |client| {
+			client.write_instruction(packet_id::BROADCAST, instruction_id::PING, 0, |_| Ok(()))?;
+			Ok(())
+		})?;
 		Ok(Scan { client: self })
 	}
 }
@@ -74,7 +81,7 @@ make_scan_struct!(serial2::SerialPort);
 make_scan_struct!();
 impl<SerialPort, Buffer> core::fmt::Debug for Scan<'_, SerialPort, Buffer>
 where
-	SerialPort: crate::SerialPort + std::fmt::Debug,
+	SerialPort: crate::SerialPort + core::fmt::Debug,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -88,15 +95,15 @@ where
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	/// Scan for the next motor reply
-	pub fn scan_next(&mut self) -> Option<Result<Response<Ping>, crate::ReadError<SerialPort::Error>>> {
+	pub fn scan_next(&mut self) -> Option<Result<Response<Ping>, ClientError<SerialPort::Error>>> {
 		let response = self.next_response();
 		match response {
 			Ok(response) => Some(Ok(response)),
-			Err(ReadError::Io(e)) if SerialPort::is_timeout_error(&e) => {
+			Err(ReadError::Timeout { .. }) => {
 				trace!("Ping response timed out.");
 				None
 			},
-			Err(e) => Some(Err(e)),
+			Err(e) => Some(Err(self.client.error_with_context(e, ErrorContext::new(instruction_id::PING, packet_id::BROADCAST)))),
 		}
 	}
 
@@ -124,7 +131,7 @@ where
 	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	type Item = Result<Response<Ping>, crate::ReadError<SerialPort::Error>>;
+	type Item = Result<Response<Ping>, ClientError<SerialPort::Error>>;
 
 	fn next(&mut self) -> Option<Self::Item> {
 		self.scan_next()
@@ -1,7 +1,7 @@
 use crate::bus::data::{decode_status_packet, decode_status_packet_bytes};
-use crate::bus::{Data, StatusPacket};
+use crate::bus::Data;
 use crate::bus::endian::write_u16_le;
-use crate::{Client, Response, TransferError};
+use crate::{Client, ClientError, ErrorContext, ReadError, Response, TransferError};
 use super::instruction_id;
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
@@ -9,26 +9,29 @@ where
 	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	fn read_raw(&mut self, motor_id: u8, address: u16, count: u16) -> Result<StatusPacket<'_>, TransferError<SerialPort::Error>> {
-		let response = self.transfer_single(motor_id, instruction_id::READ, 4, count, |buffer| {
+	fn read_raw(&mut self, motor_id: u8, address: u16, count: u16) -> Result<usize, TransferError<SerialPort::Error>> {
+		self.transfer_single_with_retry(motor_id, instruction_id::READ, 4, count, |buffer| {
 			write_u16_le(&mut buffer[0..], address);
 			write_u16_le(&mut buffer[2..], count);
 			Ok(())
-		})?;
-		crate::error::InvalidParameterCount::check(response.parameters().len(), count.into()).map_err(crate::ReadError::from)?;
-		Ok(response)
+		})
 	}
 
 	/// Read an arbitrary number of bytes from a specific motor.
 	///
 	/// This function will not work correctly if the motor ID is set to [`packet_id::BROADCAST`][crate::instructions::packet_id::BROADCAST].
 	/// Use [`Self::sync_read`] to read from multiple motors with one command.
-	pub fn read_bytes<'a, T>(&'a mut self, motor_id: u8, address: u16, count: u16) -> Result<Response<T>, TransferError<SerialPort::Error>>
+	///
+	/// The read is retried according to the [retry policy][Self::set_retry_policy] of the client.
+	pub fn read_bytes<'a, T>(&'a mut self, motor_id: u8, address: u16, count: u16) -> Result<Response<T>, ClientError<SerialPort::Error>>
 	where
 		T: From<&'a [u8]>
 	{
-		let status = self.read_raw(motor_id, address, count)?;
-		Ok(decode_status_packet_bytes(status)?)
+		let context = ErrorContext::new(instruction_id::READ, motor_id).with_registers(address, count);
+		let packet_len = self.with_error_context(context.clone(), |client| client.read_raw(motor_id, address, count))?;
+		let this: &'a Self = self;
+		decode_status_packet_bytes(this.received_status_packet(packet_len))
+			.map_err(|e| this.error_with_context(ReadError::from(e), context))
 	}
 
 	/// Read a value from a specific motor.
@@ -37,11 +40,16 @@ where
 	///
 	/// This function will not work correctly if the motor ID is set to [`packet_id::BROADCAST`][crate::instructions::packet_id::BROADCAST].
 	/// Use [`Self::sync_read`] to read from multiple motors with one command.
-	pub fn read<T>(&mut self, motor_id: u8, address: u16) -> Result<Response<T>, TransferError<SerialPort::Error>>
+	///
+	/// The read is retried according to the [retry policy][Self::set_retry_policy] of the client.
+	pub fn read<T>(&mut self, motor_id: u8, address: u16) -> Result<Response<T>, ClientError<SerialPort::Error>>
 	where
 	T: Data
 	{
-		let status = self.read_raw(motor_id, address, T::ENCODED_SIZE)?;
-		Ok(decode_status_packet(status)?)
+		let context = ErrorContext::new(instruction_id::READ, motor_id).with_registers(address, T::ENCODED_SIZE);
+		self.with_error_context(context, |client| {
+			let packet_len = client.read_raw(motor_id, address, T::ENCODED_SIZE)?;
+			Ok(decode_status_packet(client.received_status_packet(packet_len))?)
+		})
 	}
 }
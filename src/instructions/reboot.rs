@@ -1,4 +1,4 @@
-use crate::{Client, Response, TransferError, WriteError};
+use crate::{Client, ClientError, ErrorContext, Response};
 use super::{instruction_id, packet_id};
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
@@ -16,13 +16,17 @@ where
 	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
 	///
 	/// If you want to broadcast this instruction, it may be more convenient to use [`Self::broadcast_reboot()`] instead.
-	pub fn reboot(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::REBOOT, 0, |_| Ok(()))?;
-		Ok(super::read_response_if_not_broadcast(self, motor_id)?)
+	pub fn reboot(&mut self, motor_id: u8) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::REBOOT, motor_id), |client| {
+			client.write_instruction(motor_id, instruction_id::REBOOT, 0, |_| Ok(()))?;
+			Ok(super::read_response_if_expected(client, motor_id, instruction_id::REBOOT)?)
+		})
 	}
 
 	/// Broadcast an reboot command to all connected motors to trigger a previously registered instruction.
-	pub fn broadcast_reboot(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
-		self.write_instruction(packet_id::BROADCAST, instruction_id::REBOOT, 0, |_| Ok(()))
+	pub fn broadcast_reboot(&mut self) -> Result<(), ClientError<SerialPort::Error>> {
+		self.with_error_context(ErrorContext::new(instruction_id::REBOOT, packet_id::BROADCAST), |client| {
+			Ok(client.write_instruction(packet_id::BROADCAST, instruction_id::REBOOT, 0, |_| Ok(()))?)
+		})
 	}
 }
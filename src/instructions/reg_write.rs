@@ -1,7 +1,7 @@
 use crate::bus::endian::write_u16_le;
-use crate::{Client, Response, TransferError};
+use crate::{Client, ClientError, ErrorContext, Response};
 use crate::bus::Data;
-use super::{instruction_id, read_response_if_not_broadcast};
+use super::{instruction_id, read_response_if_expected};
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
 where
@@ -17,13 +17,16 @@ where
 	///
 	/// You may specify [`crate::instructions::packet_id::BROADCAST`] as motor ID.
 	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
-	pub fn reg_write_bytes(&mut self, motor_id: u8, address: u16, data: &[u8]) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::REG_WRITE, 2 + data.len(), |buffer| {
-			write_u16_le(&mut buffer[0..], address);
-			buffer[2..].copy_from_slice(data);
-			Ok(())
-		})?;
-		Ok(read_response_if_not_broadcast(self, motor_id)?)
+	pub fn reg_write_bytes(&mut self, motor_id: u8, address: u16, data: &[u8]) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		let context = ErrorContext::new(instruction_id::REG_WRITE, motor_id).with_registers(address, data.len() as u16);
+		self.with_error_context(context, |client| {
+			client.write_instruction(motor_id, instruction_id::REG_WRITE, 2 + data.len(), |buffer| {
+				write_u16_le(&mut buffer[0..], address);
+				buffer[2..].copy_from_slice(data);
+				Ok(())
+			})?;
+			Ok(read_response_if_expected(client, motor_id, instruction_id::REG_WRITE)?)
+		})
 	}
 
 	/// Register a write command for value to a specific motor.
@@ -35,11 +38,14 @@ where
 	///
 	/// You may specify [`crate::instructions::packet_id::BROADCAST`] as motor ID.
 	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
-	pub fn reg_write<T: Data>(&mut self, motor_id: u8, address: u16, value: &T) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::REG_WRITE, 2 + 1, |buffer| {
-			write_u16_le(&mut buffer[0..], address);
-			value.encode(&mut buffer[2..])
-		})?;
-		Ok(read_response_if_not_broadcast(self, motor_id)?)
+	pub fn reg_write<T: Data>(&mut self, motor_id: u8, address: u16, value: &T) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		let context = ErrorContext::new(instruction_id::REG_WRITE, motor_id).with_registers(address, T::ENCODED_SIZE);
+		self.with_error_context(context, |client| {
+			client.write_instruction(motor_id, instruction_id::REG_WRITE, 2 + 1, |buffer| {
+				write_u16_le(&mut buffer[0..], address);
+				value.encode(&mut buffer[2..])
+			})?;
+			Ok(read_response_if_expected(client, motor_id, instruction_id::REG_WRITE)?)
+		})
 	}
 }
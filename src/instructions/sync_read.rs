@@ -3,7 +3,8 @@ use core::marker::PhantomData;
 use crate::bus::endian::write_u16_le;
 use crate::bus::data::{decode_status_packet, decode_status_packet_bytes, decode_status_packet_bytes_borrow};
 use crate::bus::data::Data;
-use crate::{Client, ReadError, Response, WriteError};
+use crate::id_set::IdSet;
+use crate::{Client, ClientError, ErrorContext, ReadError, Response, RetryPolicy, TransferError};
 use super::{instruction_id, packet_id};
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
@@ -12,80 +13,292 @@ where
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	/// Synchronously read a number of bytes from multiple motors in one command.
+	///
+	/// If the client has a [retry policy][Self::set_retry_policy],
+	/// the sync read instruction is re-issued for the motors that did not respond properly.
 	pub fn sync_read_bytes<'a, T>(
 		&'a mut self,
 		motor_ids: &'a [u8],
 		address: u16,
 		count: u16,
-	) -> Result<SyncReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	) -> Result<SyncReadBytes<'a, T, SerialPort, Buffer>, ClientError<SerialPort::Error>>
 	where
 		T: for<'b> From<&'b [u8]>,
 	{
-		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids.len(), |buffer| {
-			write_u16_le(&mut buffer[0..], address);
-			write_u16_le(&mut buffer[2..], count);
-			buffer[4..].copy_from_slice(motor_ids);
+		let context = ErrorContext::new(instruction_id::SYNC_READ, packet_id::BROADCAST).with_registers(address, count);
+		self.with_error_context(context, |client| {
+			client.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids.len(), |buffer| {
+				encode_sync_read_parameters(buffer, motor_ids, address, count)
+			})?;
 			Ok(())
 		})?;
 
+		let state = SyncReadState::new(motor_ids, address, count, self.retry_policy);
 		Ok(SyncReadBytes {
 			client: self,
-			count,
-			motor_ids,
-			index: 0,
+			state,
 			data: PhantomData,
 		})
 	}
 
 	/// Synchronously read a number of bytes from multiple motors in one command.
+	///
+	/// If the client has a [retry policy][Self::set_retry_policy],
+	/// the sync read instruction is re-issued for the motors that did not respond properly.
 	pub fn sync_read_bytes_borrow<'a, T>(
 		&'a mut self,
 		motor_ids: &'a [u8],
 		address: u16,
 		count: u16,
-	) -> Result<SyncReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	) -> Result<SyncReadBytes<'a, T, SerialPort, Buffer>, ClientError<SerialPort::Error>>
 	where
 		[u8]: core::borrow::Borrow<T>,
 	{
-		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids.len(), |buffer| {
-			write_u16_le(&mut buffer[0..], address);
-			write_u16_le(&mut buffer[2..], count);
-			buffer[4..].copy_from_slice(motor_ids);
+		let context = ErrorContext::new(instruction_id::SYNC_READ, packet_id::BROADCAST).with_registers(address, count);
+		self.with_error_context(context, |client| {
+			client.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids.len(), |buffer| {
+				encode_sync_read_parameters(buffer, motor_ids, address, count)
+			})?;
 			Ok(())
 		})?;
 
+		let state = SyncReadState::new(motor_ids, address, count, self.retry_policy);
 		Ok(SyncReadBytes {
 			client: self,
-			count,
-			motor_ids,
-			index: 0,
+			state,
 			data: PhantomData,
 		})
 	}
 
 	/// Synchronously read values from multiple motors in one command.
+	///
+	/// If the client has a [retry policy][Self::set_retry_policy],
+	/// the sync read instruction is re-issued for the motors that did not respond properly.
+	/// The responses of those motors are returned after the responses of the other motors.
 	pub fn sync_read<'a, T: Data>(
 		&'a mut self,
 		motor_ids: &'a [u8],
 		address: u16,
-	) -> Result<SyncRead<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>> {
+	) -> Result<SyncRead<'a, T, SerialPort, Buffer>, ClientError<SerialPort::Error>> {
 		let count = T::ENCODED_SIZE;
-		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids.len(), |buffer| {
-			write_u16_le(&mut buffer[0..], address);
-			write_u16_le(&mut buffer[2..], count);
-			buffer[4..].copy_from_slice(motor_ids);
+		let context = ErrorContext::new(instruction_id::SYNC_READ, packet_id::BROADCAST).with_registers(address, count);
+		self.with_error_context(context, |client| {
+			client.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids.len(), |buffer| {
+				encode_sync_read_parameters(buffer, motor_ids, address, count)
+			})?;
 			Ok(())
 		})?;
 
+		let state = SyncReadState::new(motor_ids, address, count, self.retry_policy);
 		Ok(SyncRead {
 			client: self,
-			motor_ids,
-			index: 0,
+			state,
 			data: PhantomData,
 		})
 	}
 }
 
+/// Encode the parameters of a sync read or fast sync read instruction.
+pub(crate) fn encode_sync_read_parameters(
+	buffer: &mut [u8],
+	motor_ids: &[u8],
+	address: u16,
+	count: u16,
+) -> Result<(), crate::error::BufferTooSmallError> {
+	write_u16_le(&mut buffer[0..], address);
+	write_u16_le(&mut buffer[2..], count);
+	buffer[4..].copy_from_slice(motor_ids);
+	Ok(())
+}
+
+/// The state of a sync read operation.
+///
+/// Keeps track of the motors that still need to respond,
+/// and re-issues the sync read instruction for motors that did not respond properly.
+struct SyncReadState<'a> {
+	/// The motor IDs from the original instruction.
+	motor_ids: &'a [u8],
+
+	/// The address to read from.
+	address: u16,
+
+	/// The number of bytes to read from each motor.
+	count: u16,
+
+	/// The index of the next motor ID in `motor_ids` to read a response for.
+	index: usize,
+
+	/// The retry policy of the client.
+	retry_policy: RetryPolicy,
+
+	/// The number of times the sync read instruction has been sent.
+	attempts: u32,
+
+	/// The motors included in the last sent instruction (only used after the first attempt).
+	current: IdSet,
+
+	/// The motors that should be included in the next instruction.
+	deferred: IdSet,
+}
+
+impl<'a> SyncReadState<'a> {
+	fn new(motor_ids: &'a [u8], address: u16, count: u16, retry_policy: RetryPolicy) -> Self {
+		Self {
+			motor_ids,
+			address,
+			count,
+			index: 0,
+			retry_policy,
+			attempts: 1,
+			current: IdSet::default(),
+			deferred: IdSet::default(),
+		}
+	}
+
+	/// Check if a motor is included in the last sent instruction.
+	fn is_current(&self, motor_id: u8) -> bool {
+		self.attempts == 1 || self.current.contains(motor_id)
+	}
+
+	/// Get the number of responses that should still be received, including those of retries.
+	fn remaining(&self) -> usize {
+		self.motor_ids.iter()
+			.enumerate()
+			.filter(|&(i, &motor_id)| (i >= self.index && self.is_current(motor_id)) || self.deferred.contains(motor_id))
+			.count()
+	}
+
+	/// Get the ID of the next motor that should respond.
+	///
+	/// If all motors of the last instruction have been handled,
+	/// a new sync read instruction is sent for the deferred motors.
+	fn pop_motor_id<SerialPort, Buffer>(&mut self, client: &mut Client<SerialPort, Buffer>) -> Option<Result<u8, TransferError<SerialPort::Error>>>
+	where
+		SerialPort: crate::SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		loop {
+			if let Some(&motor_id) = self.motor_ids.get(self.index) {
+				self.index += 1;
+				if self.is_current(motor_id) {
+					return Some(Ok(motor_id));
+				}
+				continue;
+			}
+
+			if self.deferred.is_empty() {
+				return None;
+			}
+			self.current = core::mem::take(&mut self.deferred);
+			self.attempts += 1;
+			self.index = 0;
+			if let Err(e) = self.reissue(client) {
+				self.current = IdSet::default();
+				return Some(Err(e));
+			}
+		}
+	}
+
+	/// Send a new sync read instruction for the motors in `self.current`.
+	fn reissue<SerialPort, Buffer>(&self, client: &mut Client<SerialPort, Buffer>) -> Result<(), TransferError<SerialPort::Error>>
+	where
+		SerialPort: crate::SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		debug!("re-issuing sync read, attempt {}", self.attempts);
+		// Do not report the time since the previous instruction if the new one can not be sent.
+		client.sent_at = None;
+		client.retry_backoff(self.retry_policy.backoff)?;
+		let motor_ids = || self.motor_ids.iter().copied().filter(|&motor_id| self.current.contains(motor_id));
+		client.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_READ, 4 + motor_ids().count(), |buffer| {
+			write_u16_le(&mut buffer[0..], self.address);
+			write_u16_le(&mut buffer[2..], self.count);
+			for (dest, motor_id) in buffer[4..].iter_mut().zip(motor_ids()) {
+				*dest = motor_id;
+			}
+			Ok(())
+		})?;
+		Ok(())
+	}
+
+	/// Receive the status packet of the next motor into the read buffer.
+	///
+	/// Motors that fail to reply are deferred to a later attempt if the retry policy allows it.
+	/// Returns the length of the status packet, which can be passed to [`Client::received_status_packet()`].
+	fn next_status<SerialPort, Buffer>(&mut self, client: &mut Client<SerialPort, Buffer>) -> Option<Result<usize, ClientError<SerialPort::Error>>>
+	where
+		SerialPort: crate::SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		loop {
+			let motor_id = match self.pop_motor_id(client)? {
+				Ok(motor_id) => motor_id,
+				Err(e) => return Some(Err(self.error_with_context(client, e, packet_id::BROADCAST))),
+			};
+			match self.receive_response(client, motor_id) {
+				Ok(packet_len) => return Some(Ok(packet_len)),
+				Err(e) if self.defer(motor_id, &e) => continue,
+				Err(e) => return Some(Err(self.error_with_context(client, e, motor_id))),
+			}
+		}
+	}
+
+	/// Receive the status packet of a motor into the read buffer.
+	///
+	/// Returns the length of the status packet, which can be passed to [`Client::received_status_packet()`].
+	fn receive_response<SerialPort, Buffer>(&self, client: &mut Client<SerialPort, Buffer>, motor_id: u8) -> Result<usize, ReadError<SerialPort::Error>>
+	where
+		SerialPort: crate::SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		// Report timeouts for the motor that should respond next.
+		client.bus.response_motor_id = Some(motor_id);
+		let packet_len = client.receive_status_response(self.count)?;
+		let response = client.received_status_packet(packet_len);
+		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
+		// We need to report a timeout or something for the missed motor though.
+		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
+		crate::InvalidParameterCount::check(response.parameters().len(), self.count.into())?;
+		Ok(packet_len)
+	}
+
+	/// Attach context about the sync read to an error.
+	///
+	/// The motor ID of the context is the motor that failed to reply,
+	/// or the broadcast ID if the instruction could not be re-issued.
+	fn error_with_context<SerialPort, Buffer>(
+		&self,
+		client: &Client<SerialPort, Buffer>,
+		error: impl Into<TransferError<SerialPort::Error>>,
+		motor_id: u8,
+	) -> ClientError<SerialPort::Error>
+	where
+		SerialPort: crate::SerialPort,
+		Buffer: AsRef<[u8]> + AsMut<[u8]>,
+	{
+		let context = ErrorContext::new(instruction_id::SYNC_READ, motor_id).with_registers(self.address, self.count);
+		client.error_with_context(error, context)
+	}
+
+	/// Defer a motor to the next attempt if the error should be retried.
+	///
+	/// Returns `true` if the motor was deferred.
+	fn defer<E>(&mut self, motor_id: u8, error: &ReadError<E>) -> bool {
+		if self.retry_policy.should_retry(self.attempts, error) {
+			self.deferred.insert(motor_id);
+			true
+		} else {
+			false
+		}
+	}
+
+	/// Stop retrying, so that only the responses to the last sent instruction remain.
+	fn stop_retrying(&mut self) {
+		self.retry_policy = RetryPolicy::NEVER;
+		self.deferred = IdSet::default();
+	}
+}
+
 macro_rules! make_sync_read_bytes_struct {
 	($($DefaultSerialPort:ty)?) => {
 		/// A sync read operation that returns unparsed bytes.
@@ -96,9 +309,7 @@ macro_rules! make_sync_read_bytes_struct {
 			Buffer: AsRef<[u8]> + AsMut<[u8]>,
 		{
 			client: &'a mut Client<SerialPort, Buffer>,
-			count: u16,
-			motor_ids: &'a [u8],
-			index: usize,
+			state: SyncReadState<'a>,
 			data: PhantomData<fn() -> T>,
 		}
 	}
@@ -112,15 +323,16 @@ make_sync_read_bytes_struct!();
 
 impl<T, SerialPort, Buffer> core::fmt::Debug for SyncReadBytes<'_, T, SerialPort, Buffer>
 where
-	SerialPort: crate::SerialPort + std::fmt::Debug,
+	SerialPort: crate::SerialPort + core::fmt::Debug,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		f.debug_struct("SyncRead")
 			.field("serial_port", self.client.serial_port())
-			.field("motor_ids", &self.motor_ids)
-			.field("count", &self.count)
-			.field("index", &self.index)
+			.field("motor_ids", &self.state.motor_ids)
+			.field("count", &self.state.count)
+			.field("index", &self.state.index)
+			.field("attempts", &self.state.attempts)
 			.field("data", &format_args!("{}", core::any::type_name::<T>()))
 			.finish()
 	}
@@ -133,8 +345,9 @@ where
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	fn drop(&mut self) {
-		while self.pop_motor_id().is_some() {
-			self.client.read_status_response(self.count).ok();
+		self.state.stop_retrying();
+		while let Some(Ok(_motor_id)) = self.state.pop_motor_id(self.client) {
+			self.client.read_status_response(self.state.count).ok();
 		}
 	}
 }
@@ -145,7 +358,7 @@ where
 	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	type Item = Result<Response<T>, crate::error::ReadError<SerialPort::Error>>;
+	type Item = Result<Response<T>, ClientError<SerialPort::Error>>;
 
 	fn next(&mut self) -> Option<Self::Item> {
 		self.read_next()
@@ -163,56 +376,45 @@ where
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	/// Get the number of responses that should still be received.
+	///
+	/// This includes the responses of motors that will be retried.
 	pub fn remaining(&self) -> usize {
-		self.motor_ids.len() - self.index
+		self.state.remaining()
 	}
 
-	/// Read the next motor reply.
-	pub fn read_next<'a>(&'a mut self) -> Option<Result<Response<T>, ReadError<SerialPort::Error>>>
-	where
-		T: From<&'a [u8]>,
-	{
-		let motor_id = self.pop_motor_id()?;
-		Some(self.next_response(motor_id))
+	/// Get the number of times the sync read instruction has been sent so far.
+	///
+	/// This is more than one if the instruction was re-issued for some motors because of the retry policy.
+	pub fn attempts(&self) -> u32 {
+		self.state.attempts
 	}
 
-	/// Read the next motor reply, borrowing the data from the internal read buffer.
-	pub fn read_next_borrow<'a>(&'a mut self) -> Option<Result<Response<&'a T>, ReadError<SerialPort::Error>>>
-	where
-		[u8]: core::borrow::Borrow<T>,
-	{
-		let motor_id = self.pop_motor_id()?;
-		Some(self.next_response_borrow(motor_id))
-	}
-
-	fn pop_motor_id(&mut self) -> Option<u8> {
-		let motor_id = self.motor_ids.get(self.index)?;
-		self.index += 1;
-		Some(*motor_id)
-	}
-
-	fn next_response<'a>(&'a mut self, motor_id: u8) -> Result<Response<T>, ReadError<SerialPort::Error>>
+	/// Read the next motor reply.
+	pub fn read_next<'a>(&'a mut self) -> Option<Result<Response<T>, ClientError<SerialPort::Error>>>
 	where
 		T: From<&'a [u8]>,
 	{
-		let response = self.client.read_status_response(self.count)?;
-		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
-		// We need to report a timeout or something for the missed motor though.
-		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
-		crate::InvalidParameterCount::check(response.parameters().len(), self.count.into())?;
-		Ok(decode_status_packet_bytes(response)?)
+		let packet_len = match self.state.next_status(self.client)? {
+			Ok(packet_len) => packet_len,
+			Err(e) => return Some(Err(e)),
+		};
+		let this: &'a Self = self;
+		let response = this.client.received_status_packet(packet_len);
+		Some(decode_status_packet_bytes(response).map_err(|e| this.state.error_with_context(this.client, ReadError::from(e), response.packet_id())))
 	}
 
-	fn next_response_borrow<'a>(&'a mut self, motor_id: u8) -> Result<Response<&'a T>, ReadError<SerialPort::Error>>
+	/// Read the next motor reply, borrowing the data from the internal read buffer.
+	pub fn read_next_borrow(&mut self) -> Option<Result<Response<&T>, ClientError<SerialPort::Error>>>
 	where
 		[u8]: core::borrow::Borrow<T>,
 	{
-		let response = self.client.read_status_response(self.count)?;
-		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
-		// We need to report a timeout or something for the missed motor though.
-		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
-		crate::InvalidParameterCount::check(response.parameters().len(), self.count.into())?;
-		Ok(decode_status_packet_bytes_borrow(response)?)
+		let packet_len = match self.state.next_status(self.client)? {
+			Ok(packet_len) => packet_len,
+			Err(e) => return Some(Err(e)),
+		};
+		let this: &Self = self;
+		let response = this.client.received_status_packet(packet_len);
+		Some(decode_status_packet_bytes_borrow(response).map_err(|e| this.state.error_with_context(this.client, ReadError::from(e), response.packet_id())))
 	}
 }
 macro_rules! make_sync_read_struct {
@@ -225,8 +427,7 @@ macro_rules! make_sync_read_struct {
 			Buffer: AsRef<[u8]> + AsMut<[u8]>,
 		{
 			client: &'a mut Client<SerialPort, Buffer>,
-			motor_ids: &'a [u8],
-			index: usize,
+			state: SyncReadState<'a>,
 			data: PhantomData<fn() -> T>,
 		}
 	}
@@ -241,15 +442,16 @@ make_sync_read_struct!();
 impl<T, SerialPort, Buffer> core::fmt::Debug for SyncRead<'_, T, SerialPort, Buffer>
 where
 	T: Data,
-	SerialPort: crate::SerialPort + std::fmt::Debug,
+	SerialPort: crate::SerialPort + core::fmt::Debug,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 		f.debug_struct("SyncRead")
 			.field("serial_port", self.client.serial_port())
-			.field("motor_ids", &self.motor_ids)
+			.field("motor_ids", &self.state.motor_ids)
 			.field("count", &T::ENCODED_SIZE)
-			.field("index", &self.index)
+			.field("index", &self.state.index)
+			.field("attempts", &self.state.attempts)
 			.field("data", &format_args!("{}", core::any::type_name::<T>()))
 			.finish()
 	}
@@ -262,6 +464,7 @@ where
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	fn drop(&mut self) {
+		self.state.stop_retrying();
 		while self.read_next().is_some() {}
 	}
 }
@@ -272,7 +475,7 @@ where
 	SerialPort: crate::SerialPort,
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
-	type Item = Result<Response<T>, crate::error::ReadError<SerialPort::Error>>;
+	type Item = Result<Response<T>, ClientError<SerialPort::Error>>;
 
 	fn next(&mut self) -> Option<Self::Item> {
 		self.read_next()
@@ -290,32 +493,39 @@ where
 	Buffer: AsRef<[u8]> + AsMut<[u8]>,
 {
 	/// Get the number of responses that should still be received.
+	///
+	/// This includes the responses of motors that will be retried.
 	pub fn remaining(&self) -> usize {
-		self.motor_ids.len() - self.index
+		self.state.remaining()
+	}
+
+	/// Get the number of times the sync read instruction has been sent so far.
+	///
+	/// This is more than one if the instruction was re-issued for some motors because of the retry policy.
+	pub fn attempts(&self) -> u32 {
+		self.state.attempts
 	}
 
 	/// Read the next motor reply.
-	pub fn read_next(&mut self) -> Option<Result<Response<T>, ReadError<SerialPort::Error>>>
+	pub fn read_next(&mut self) -> Option<Result<Response<T>, ClientError<SerialPort::Error>>>
 	where
 		T: Data,
 	{
-		let motor_id = self.pop_motor_id()?;
-		Some(self.next_response(motor_id))
-	}
-
-	fn pop_motor_id(&mut self) -> Option<u8> {
-		let motor_id = self.motor_ids.get(self.index)?;
-		self.index += 1;
-		Some(*motor_id)
+		loop {
+			let motor_id = match self.state.pop_motor_id(self.client)? {
+				Ok(motor_id) => motor_id,
+				Err(e) => return Some(Err(self.state.error_with_context(self.client, e, packet_id::BROADCAST))),
+			};
+			match self.next_response(motor_id) {
+				Ok(response) => return Some(Ok(response)),
+				Err(e) if self.state.defer(motor_id, &e) => continue,
+				Err(e) => return Some(Err(self.state.error_with_context(self.client, e, motor_id))),
+			}
+		}
 	}
 
 	fn next_response(&mut self, motor_id: u8) -> Result<Response<T>, ReadError<SerialPort::Error>> {
-		let response = self.client.read_status_response(T::ENCODED_SIZE)?;
-		// TODO: Allow a response from a motor later in the list (meaning we missed an earlier motor response).
-		// We need to report a timeout or something for the missed motor though.
-		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
-		crate::InvalidParameterCount::check(response.parameters().len(), T::ENCODED_SIZE.into())?;
-
-		Ok(decode_status_packet(response)?)
+		let packet_len = self.state.receive_response(self.client, motor_id)?;
+		decode_status_packet(self.client.received_status_packet(packet_len))
 	}
 }
@@ -1,5 +1,5 @@
 use crate::bus::endian::write_u16_le;
-use crate::{Client, WriteError};
+use crate::{Client, ClientError, ErrorContext};
 use super::{instruction_id, packet_id, SyncWriteData};
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
@@ -38,7 +38,7 @@ where
 	/// # Ok(())
 	/// # }
 	/// ```
-	pub fn sync_write_bytes<'a, Iter, Data, Buf>(&mut self, address: u16, count: u16, data: Iter) -> Result<(), WriteError<SerialPort::Error>>
+	pub fn sync_write_bytes<'a, Iter, Data, Buf>(&mut self, address: u16, count: u16, data: Iter) -> Result<(), ClientError<SerialPort::Error>>
 	where
 		Iter: IntoIterator<Item = Data>,
 		Iter::IntoIter: ExactSizeIterator,
@@ -46,20 +46,12 @@ where
 		Buf: AsRef<[u8]> + 'a,
 	{
 		let data = data.into_iter();
-		let motors = data.len();
-		let stride = 1 + usize::from(count);
-		let parameter_count = 4 + motors * stride;
-		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_WRITE, parameter_count, |buffer| {
-			write_u16_le(&mut buffer[0..], address);
-			write_u16_le(&mut buffer[2..], count);
-			for (i, command) in data.enumerate() {
-				let command = command.as_ref();
-				assert_eq!(command.data.as_ref().len(), count as usize);
-				let buffer = &mut buffer[4 + i * stride..][..stride];
-				buffer[0] = command.motor_id;
-				buffer[1..].copy_from_slice(command.data.as_ref());
-			}
-			Ok(())
+		let parameter_count = 4 + data.len() * (1 + usize::from(count));
+		let context = ErrorContext::new(instruction_id::SYNC_WRITE, packet_id::BROADCAST).with_registers(address, count);
+		self.with_error_context(context, |client| {
+			Ok(client.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_WRITE, parameter_count, |buffer| {
+				encode_sync_write_bytes_parameters(buffer, address, count, data)
+			})?)
 		})
 	}
 
@@ -67,7 +59,7 @@ where
 	///
 	/// Each motor will perform the write as soon as it receives the command.
 	/// This gives much shorter delays than executing a regular [`Self::write`] for each motor individually.
-	pub fn sync_write<Iter, Data, T>(&mut self, address: u16, data: Iter) -> Result<(), WriteError<SerialPort::Error>>
+	pub fn sync_write<Iter, Data, T>(&mut self, address: u16, data: Iter) -> Result<(), ClientError<SerialPort::Error>>
 	where
 		Iter: IntoIterator<Item = Data>,
 		Iter::IntoIter: ExactSizeIterator,
@@ -75,20 +67,61 @@ where
 		T: crate::bus::Data,
 	{
 		let data = data.into_iter();
-		let count = core::mem::size_of::<u8>();
-		let motors = data.len();
-		let stride = 1 + count;
-		let parameter_count = 4 + motors * stride;
-		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_WRITE, parameter_count, |buffer| {
-			write_u16_le(&mut buffer[0..], address);
-			write_u16_le(&mut buffer[2..], count as u16);
-			for (i, command) in data.enumerate() {
-				let command = command.as_ref();
-				let buffer = &mut buffer[4 + i * stride..][..stride];
-				buffer[0] = command.motor_id;
-				command.data.encode(&mut buffer[1..])?;
-			}
-			Ok(())
+		let parameter_count = 4 + data.len() * (1 + usize::from(T::ENCODED_SIZE));
+		let context = ErrorContext::new(instruction_id::SYNC_WRITE, packet_id::BROADCAST).with_registers(address, T::ENCODED_SIZE);
+		self.with_error_context(context, |client| {
+			Ok(client.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_WRITE, parameter_count, |buffer| {
+				encode_sync_write_parameters(buffer, address, data)
+			})?)
 		})
 	}
 }
+
+/// Encode the parameters of a sync write instruction with raw bytes for each motor.
+///
+/// # Panics
+/// Panics if the data for a motor is not exactly `count` bytes.
+pub(crate) fn encode_sync_write_bytes_parameters<Data, Buf>(
+	buffer: &mut [u8],
+	address: u16,
+	count: u16,
+	data: impl Iterator<Item = Data>,
+) -> Result<(), crate::error::BufferTooSmallError>
+where
+	Data: AsRef<SyncWriteData<Buf>>,
+	Buf: AsRef<[u8]>,
+{
+	let stride = 1 + usize::from(count);
+	write_u16_le(&mut buffer[0..], address);
+	write_u16_le(&mut buffer[2..], count);
+	for (i, command) in data.enumerate() {
+		let command = command.as_ref();
+		assert_eq!(command.data.as_ref().len(), count as usize);
+		let buffer = &mut buffer[4 + i * stride..][..stride];
+		buffer[0] = command.motor_id;
+		buffer[1..].copy_from_slice(command.data.as_ref());
+	}
+	Ok(())
+}
+
+/// Encode the parameters of a sync write instruction with a value for each motor.
+pub(crate) fn encode_sync_write_parameters<Data, T>(
+	buffer: &mut [u8],
+	address: u16,
+	data: impl Iterator<Item = Data>,
+) -> Result<(), crate::error::BufferTooSmallError>
+where
+	Data: AsRef<SyncWriteData<T>>,
+	T: crate::bus::Data,
+{
+	let stride = 1 + usize::from(T::ENCODED_SIZE);
+	write_u16_le(&mut buffer[0..], address);
+	write_u16_le(&mut buffer[2..], T::ENCODED_SIZE);
+	for (i, command) in data.enumerate() {
+		let command = command.as_ref();
+		let buffer = &mut buffer[4 + i * stride..][..stride];
+		buffer[0] = command.motor_id;
+		command.data.encode(&mut buffer[1..])?;
+	}
+	Ok(())
+}
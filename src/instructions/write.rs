@@ -1,7 +1,7 @@
 use crate::bus::endian::write_u16_le;
-use crate::{Client, Response, TransferError};
+use crate::{Client, ClientError, ErrorContext, Response};
 use crate::bus::Data;
-use super::{instruction_id, read_response_if_not_broadcast};
+use super::{instruction_id, read_response_if_expected};
 
 impl<SerialPort, Buffer> Client<SerialPort, Buffer>
 where
@@ -12,25 +12,51 @@ where
 	///
 	/// You may specify [`crate::instructions::packet_id::BROADCAST`] as motor ID.
 	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
-	pub fn write<T: Data>(&mut self, motor_id: u8, address: u16, data: &T) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::WRITE, 2 + T::ENCODED_SIZE as usize, |buffer| {
+	///
+	/// If the motor does not reply to writes at its [status return level][Self::set_status_return_level], this function does not wait for a reply either.
+	///
+	/// If a reply is expected, the write is retried according to the [retry policy][Self::set_retry_policy] of the client.
+	pub fn write<T: Data>(&mut self, motor_id: u8, address: u16, data: &T) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		let encode_parameters = |buffer: &mut [u8]| {
 			write_u16_le(&mut buffer[0..], address);
 			data.encode(&mut buffer[2..])?;
 			Ok(())
-		})?;
-		Ok(read_response_if_not_broadcast(self, motor_id)?)
+		};
+		let context = ErrorContext::new(instruction_id::WRITE, motor_id).with_registers(address, T::ENCODED_SIZE);
+		self.with_error_context(context, |client| {
+			if !client.expects_reply(motor_id, instruction_id::WRITE) {
+				client.write_instruction(motor_id, instruction_id::WRITE, 2 + T::ENCODED_SIZE as usize, encode_parameters)?;
+				Ok(read_response_if_expected(client, motor_id, instruction_id::WRITE)?)
+			} else {
+				let packet_len = client.transfer_single_with_retry(motor_id, instruction_id::WRITE, 2 + T::ENCODED_SIZE as usize, 0, encode_parameters)?;
+				Ok(client.received_status_packet(packet_len).try_into()?)
+			}
+		})
 	}
 
 	/// Write an arbitrary amount of bytes to a specific motor.
 	///
 	/// You may specify [`crate::instructions::packet_id::BROADCAST`] as motor ID.
 	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
-	pub fn write_bytes(&mut self, motor_id: u8, address: u16, data: &[u8]) -> Result<Response<()>, TransferError<SerialPort::Error>> {
-		self.write_instruction(motor_id, instruction_id::WRITE, 2 + data.len(), |buffer| {
+	///
+	/// If the motor does not reply to writes at its [status return level][Self::set_status_return_level], this function does not wait for a reply either.
+	///
+	/// If a reply is expected, the write is retried according to the [retry policy][Self::set_retry_policy] of the client.
+	pub fn write_bytes(&mut self, motor_id: u8, address: u16, data: &[u8]) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		let encode_parameters = |buffer: &mut [u8]| {
 			write_u16_le(&mut buffer[0..], address);
 			buffer[2..].copy_from_slice(data);
 			Ok(())
-		})?;
-		Ok(read_response_if_not_broadcast(self, motor_id)?)
+		};
+		let context = ErrorContext::new(instruction_id::WRITE, motor_id).with_registers(address, data.len() as u16);
+		self.with_error_context(context, |client| {
+			if !client.expects_reply(motor_id, instruction_id::WRITE) {
+				client.write_instruction(motor_id, instruction_id::WRITE, 2 + data.len(), encode_parameters)?;
+				Ok(read_response_if_expected(client, motor_id, instruction_id::WRITE)?)
+			} else {
+				let packet_len = client.transfer_single_with_retry(motor_id, instruction_id::WRITE, 2 + data.len(), 0, encode_parameters)?;
+				Ok(client.received_status_packet(packet_len).try_into()?)
+			}
+		})
 	}
 }
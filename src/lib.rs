@@ -13,11 +13,34 @@
 //! The [`Client`] struct exposes functions for all supported instructions such as [`Client::ping`], [`Client::read`], [`Client::write`] and much more.
 //! Additionally, you can also transmit raw commands using [`Client::write_instruction`] and [`Client::read_status_response`], or [`Client::transfer_single`].
 //!
-//! The library currently implements all instructions except for the Control Table Backup, Fast Sync Read and Fast Sync Write instructions.
+//! To implement the device side of the protocol, you can use the [`Device`] struct directly,
+//! or a [`DeviceServer`] that answers all instructions automatically from a [`ControlTable`].
 //!
 //! # Optional features
 //!
 //! You can enable the `log` feature to have the library use `log::trace!()` to log all sent instructions and received replies.
+//!
+//! With the `std` feature (enabled by default), you can wrap any [`SerialPort`] in a [`CaptureSerialPort`] to record all communication to a pcapng file for analysis in Wireshark.
+//! The recording can be replayed with [`ReplaySerialPort`] to test your code without motors.
+//!
+//! You can enable the `protocol1` feature to get the [`protocol1`] module, with a client for motors that only support the Dynamixel Protocol 1.0.
+//!
+//! You can enable the `models` feature to get the [`models`] module, with typed control table definitions for the X-series motors and decoding of their hardware error status.
+//!
+//! You can enable the `motor-stats` feature to count the errors of each motor ID in the [`Stats`] of a bus.
+//! This is disabled by default, because it adds about 1 KiB to every bus.
+//!
+//! You can enable the `testing` feature to get the [`testing`] module, with a simulated bus and devices to test your code without hardware.
+//!
+//! You can enable the `derive` feature to get a derive macro for the [`bus::Data`] trait.
+//! This allows you to read and write a block of registers as a single struct.
+//!
+//! You can enable the `async` feature to get the [`AsyncClient`], which is built on the [`AsyncSerialPort`] trait instead of [`SerialPort`].
+//! The `serial2-tokio` feature additionally enables [`TokioSerialPort`], an implementation of [`AsyncSerialPort`] for the `serial2-tokio` crate.
+//!
+//! You can enable the `embedded-io` feature to get [`EmbeddedIoSerialPort`], which implements [`SerialPort`] for any serial port implementing the `embedded-io` traits.
+//! This can be used together with `default-features = false` for `no_std` targets.
+//! The `embassy` feature additionally enables [`AsyncDevice`], which uses the `embedded-io-async` traits and `embassy-time` deadlines.
 
 #![warn(missing_docs)]
 #![warn(missing_debug_implementations)]
@@ -30,6 +53,10 @@ extern crate alloc;
 /// Public re-export of the serial2 crate.
 pub use serial2;
 
+#[cfg(feature = "serial2-tokio")]
+/// Public re-export of the serial2-tokio crate.
+pub use serial2_tokio;
+
 #[macro_use]
 mod log;
 
@@ -39,12 +66,44 @@ pub mod instructions;
 mod client;
 pub use client::*;
 
+mod id_set;
+
+mod retry;
+pub use retry::RetryPolicy;
+
+mod stats;
+pub use stats::{LatencyHistogram, Stats};
+
 mod device;
 pub use device::*;
 
+#[cfg(feature = "alloc")]
+mod device_server;
+
+#[cfg(feature = "alloc")]
+pub use device_server::{ControlTable, DeviceServer, MemoryControlTable};
+
 mod serial_port;
 pub use serial_port::SerialPort;
 
+#[cfg(feature = "std")]
+pub use serial_port::capture::CaptureSerialPort;
+
+#[cfg(feature = "std")]
+pub use serial_port::replay::{ReplayError, ReplaySerialPort};
+
+#[cfg(feature = "async")]
+pub use serial_port::AsyncSerialPort;
+
+#[cfg(feature = "serial2-tokio")]
+pub use serial_port::serial2_tokio::TokioSerialPort;
+
+#[cfg(feature = "embedded-io")]
+pub use serial_port::embedded_io::{Clock, EmbeddedIoError, EmbeddedIoSerialPort, NoDirectionPin};
+
+#[cfg(feature = "embassy")]
+pub use serial_port::embassy::{EmbassySerialPort, EmbassySerialPortError};
+
 mod error;
 pub use error::*;
 
@@ -52,3 +111,24 @@ mod response;
 pub use response::*;
 
 pub mod bus;
+
+#[cfg(feature = "protocol1")]
+pub mod protocol1;
+
+#[cfg(feature = "models")]
+pub mod models;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "async")]
+pub mod async_client;
+
+#[cfg(feature = "async")]
+pub use async_client::AsyncClient;
+
+#[cfg(feature = "embassy")]
+mod async_device;
+
+#[cfg(feature = "embassy")]
+pub use async_device::AsyncDevice;
@@ -0,0 +1,425 @@
+//! Typed control table definitions for DYNAMIXEL motors.
+//!
+//! Each model has a module with a [`Register`] constant for every item in its control table.
+//! A register knows its address, its [`Data`] type, its [`AccessMode`] and its [`MemoryArea`].
+//! Use [`Client::read_reg()`] and [`Client::write_reg()`] to access the registers:
+//!
+//! ```no_run
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use dynamixel2::Client;
+//! use dynamixel2::models::xm430;
+//!
+//! let mut client = Client::open("/dev/ttyUSB0", 57600)?;
+//! client.write_reg(1, xm430::TORQUE_ENABLE, &true)?;
+//! client.write_reg(1, xm430::GOAL_POSITION, &2048)?;
+//! let position = client.read_reg(1, xm430::PRESENT_POSITION)?;
+//! println!("Present position: {}", position.data);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Because the type of the value is part of the register, writing a value with the wrong size is a compile error.
+//! So is writing to a read-only register:
+//!
+//! ```compile_fail
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let mut client = dynamixel2::Client::open("/dev/ttyUSB0", 57600)?;
+//! use dynamixel2::models::xm430;
+//! client.write_reg(1, xm430::PRESENT_POSITION, &0)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! When a response has the [`alert`][crate::Response::alert] bit set,
+//! use [`Client::read_hardware_error_status()`] to find out what went wrong.
+//!
+//! The control tables are taken from the ROBOTIS e-manual.
+//! This module is only available if the `models` feature is enabled.
+
+use core::marker::PhantomData;
+
+use crate::bus::Data;
+use crate::{Client, ClientError, Response};
+
+pub mod xh430;
+pub mod xl330;
+pub mod xl430;
+pub mod xm430;
+pub mod xm540;
+pub mod xw540;
+
+/// The Hardware Error Status register of X-series motors, which has the same address for all of them.
+const HARDWARE_ERROR_STATUS: Register<HardwareErrorStatus, ReadOnly> = Register::new(70, MemoryArea::Ram);
+
+bitflags::bitflags! {
+	/// The hardware errors reported in the Hardware Error Status register of a motor.
+	///
+	/// The motor sets the [`alert`][crate::Response::alert] bit in every response while any of these errors is present.
+	/// Depending on the Shutdown register, the motor may also disable torque.
+	#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+	pub struct HardwareErrorStatus: u8 {
+		/// The input voltage is outside of the operating range from the control table.
+		const INPUT_VOLTAGE = 0x01;
+
+		/// The internal temperature exceeds the temperature limit from the control table.
+		const OVERHEATING = 0x04;
+
+		/// The motor encoder is malfunctioning.
+		const MOTOR_ENCODER = 0x08;
+
+		/// An electrical shock was detected, or the motor circuit is damaged.
+		const ELECTRICAL_SHOCK = 0x10;
+
+		/// The motor is overloaded.
+		const OVERLOAD = 0x20;
+	}
+}
+
+impl Data for HardwareErrorStatus {
+	const ENCODED_SIZE: u16 = 1;
+
+	fn encode(&self, buffer: &mut [u8]) -> Result<(), crate::error::BufferTooSmallError> {
+		self.bits().encode(buffer)
+	}
+
+	fn decode(buffer: &[u8]) -> Result<Self, crate::error::InvalidMessage> {
+		// Keep unknown bits, so that errors of newer motors are not silently dropped.
+		Ok(Self::from_bits_retain(u8::decode(buffer)?))
+	}
+}
+
+impl core::fmt::Display for HardwareErrorStatus {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		if self.is_empty() {
+			return write!(f, "no hardware error");
+		}
+		let names = [
+			(Self::INPUT_VOLTAGE, "input voltage error"),
+			(Self::OVERHEATING, "overheating"),
+			(Self::MOTOR_ENCODER, "motor encoder error"),
+			(Self::ELECTRICAL_SHOCK, "electrical shock"),
+			(Self::OVERLOAD, "overload"),
+		];
+		let mut separator = "";
+		for (flag, name) in names {
+			if self.contains(flag) {
+				write!(f, "{}{}", separator, name)?;
+				separator = ", ";
+			}
+		}
+		let unknown = self.bits() & !Self::all().bits();
+		if unknown != 0 {
+			write!(f, "{}unknown error bits {:#04X}", separator, unknown)?;
+		}
+		Ok(())
+	}
+}
+
+/// A register in the control table of a motor.
+///
+/// The `T` type argument is the type of the value stored in the register.
+/// The `Access` type argument is [`ReadOnly`] or [`ReadWrite`].
+pub struct Register<T, Access> {
+	address: u16,
+	area: MemoryArea,
+	_marker: PhantomData<(fn() -> T, Access)>,
+}
+
+/// The memory area of a register.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MemoryArea {
+	/// The register is stored in non-volatile memory.
+	///
+	/// Most motors only allow writing to the EEPROM area when torque is disabled.
+	Eeprom,
+
+	/// The register is stored in volatile memory and is reset when the motor is powered off.
+	Ram,
+}
+
+/// The access mode of a register.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AccessMode {
+	/// The register can only be read.
+	Read,
+
+	/// The register can be read and written.
+	ReadWrite,
+}
+
+/// Marker type for registers that can only be read.
+#[derive(Debug, Copy, Clone)]
+pub struct ReadOnly;
+
+/// Marker type for registers that can be read and written.
+#[derive(Debug, Copy, Clone)]
+pub struct ReadWrite;
+
+/// Trait for the access marker types of a [`Register`].
+pub trait Access {
+	/// The access mode represented by the marker type.
+	const MODE: AccessMode;
+}
+
+/// Trait for the access marker types of registers that can be written.
+pub trait Writable: Access {}
+
+impl Access for ReadOnly {
+	const MODE: AccessMode = AccessMode::Read;
+}
+
+impl Access for ReadWrite {
+	const MODE: AccessMode = AccessMode::ReadWrite;
+}
+
+impl Writable for ReadWrite {}
+
+impl<T: Data, A: Access> Register<T, A> {
+	/// Create a new register descriptor.
+	pub const fn new(address: u16, area: MemoryArea) -> Self {
+		Self {
+			address,
+			area,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Get the address of the register.
+	pub const fn address(&self) -> u16 {
+		self.address
+	}
+
+	/// Get the size of the register in bytes.
+	pub const fn size(&self) -> u16 {
+		T::ENCODED_SIZE
+	}
+
+	/// Get the memory area of the register.
+	pub const fn area(&self) -> MemoryArea {
+		self.area
+	}
+
+	/// Get the access mode of the register.
+	pub const fn access(&self) -> AccessMode {
+		A::MODE
+	}
+}
+
+impl<T, A> Clone for Register<T, A> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T, A> Copy for Register<T, A> {}
+
+impl<T: Data, A: Access> core::fmt::Debug for Register<T, A> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("Register")
+			.field("address", &self.address)
+			.field("size", &self.size())
+			.field("area", &self.area)
+			.field("access", &A::MODE)
+			.field("type", &format_args!("{}", core::any::type_name::<T>()))
+			.finish()
+	}
+}
+
+impl<SerialPort, Buffer> Client<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Read a register from a specific motor.
+	///
+	/// This function will not work correctly if the motor ID is set to [`packet_id::BROADCAST`][crate::instructions::packet_id::BROADCAST].
+	pub fn read_reg<T: Data, A: Access>(&mut self, motor_id: u8, register: Register<T, A>) -> Result<Response<T>, ClientError<SerialPort::Error>> {
+		self.read(motor_id, register.address())
+	}
+
+	/// Write a value to a register of a specific motor.
+	///
+	/// You may specify [`crate::instructions::packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub fn write_reg<T: Data, A: Writable>(
+		&mut self,
+		motor_id: u8,
+		register: Register<T, A>,
+		value: &T,
+	) -> Result<Response<()>, ClientError<SerialPort::Error>> {
+		self.write(motor_id, register.address(), value)
+	}
+
+	/// Read the Hardware Error Status register of an X-series motor.
+	///
+	/// The motor sets the [`alert`][crate::Response::alert] bit in every response while a hardware error is present.
+	/// The hardware error status tells which error it is.
+	pub fn read_hardware_error_status(&mut self, motor_id: u8) -> Result<Response<HardwareErrorStatus>, ClientError<SerialPort::Error>> {
+		self.read_reg(motor_id, HARDWARE_ERROR_STATUS)
+	}
+}
+
+#[cfg(feature = "async")]
+impl<SerialPort, Buffer> crate::AsyncClient<SerialPort, Buffer>
+where
+	SerialPort: crate::AsyncSerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Read a register from a specific motor.
+	///
+	/// This function will not work correctly if the motor ID is set to [`packet_id::BROADCAST`][crate::instructions::packet_id::BROADCAST].
+	pub async fn read_reg<T: Data, A: Access>(&mut self, motor_id: u8, register: Register<T, A>) -> Result<Response<T>, crate::TransferError<SerialPort::Error>> {
+		self.read(motor_id, register.address()).await
+	}
+
+	/// Write a value to a register of a specific motor.
+	///
+	/// You may specify [`crate::instructions::packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub async fn write_reg<T: Data, A: Writable>(
+		&mut self,
+		motor_id: u8,
+		register: Register<T, A>,
+		value: &T,
+	) -> Result<Response<()>, crate::TransferError<SerialPort::Error>> {
+		self.write(motor_id, register.address(), value).await
+	}
+
+	/// Read the Hardware Error Status register of an X-series motor.
+	///
+	/// The motor sets the [`alert`][crate::Response::alert] bit in every response while a hardware error is present.
+	/// The hardware error status tells which error it is.
+	pub async fn read_hardware_error_status(&mut self, motor_id: u8) -> Result<Response<HardwareErrorStatus>, crate::TransferError<SerialPort::Error>> {
+		self.read_reg(motor_id, HARDWARE_ERROR_STATUS).await
+	}
+}
+
+/// Define [`Register`] constants.
+macro_rules! registers {
+	($($name:ident = $address:literal: $type:ty, $access:ident, $area:ident, $label:literal;)*) => {
+		$(
+			#[doc = concat!("The ", $label, " register (address ", stringify!($address), ").")]
+			pub const $name: $crate::models::Register<$type, $crate::models::$access> = $crate::models::Register::new($address, $crate::models::MemoryArea::$area);
+		)*
+	};
+}
+
+/// Define the registers that are shared by all X-series motors.
+macro_rules! x_series_registers {
+	() => {
+		$crate::models::registers! {
+			MODEL_NUMBER = 0: u16, ReadOnly, Eeprom, "Model Number";
+			MODEL_INFORMATION = 2: u32, ReadOnly, Eeprom, "Model Information";
+			FIRMWARE_VERSION = 6: u8, ReadOnly, Eeprom, "Firmware Version";
+			ID = 7: u8, ReadWrite, Eeprom, "ID";
+			BAUD_RATE = 8: u8, ReadWrite, Eeprom, "Baud Rate";
+			RETURN_DELAY_TIME = 9: u8, ReadWrite, Eeprom, "Return Delay Time";
+			DRIVE_MODE = 10: u8, ReadWrite, Eeprom, "Drive Mode";
+			OPERATING_MODE = 11: u8, ReadWrite, Eeprom, "Operating Mode";
+			SECONDARY_ID = 12: u8, ReadWrite, Eeprom, "Secondary (Shadow) ID";
+			PROTOCOL_TYPE = 13: u8, ReadWrite, Eeprom, "Protocol Type";
+			HOMING_OFFSET = 20: i32, ReadWrite, Eeprom, "Homing Offset";
+			MOVING_THRESHOLD = 24: u32, ReadWrite, Eeprom, "Moving Threshold";
+			TEMPERATURE_LIMIT = 31: u8, ReadWrite, Eeprom, "Temperature Limit";
+			MAX_VOLTAGE_LIMIT = 32: u16, ReadWrite, Eeprom, "Max Voltage Limit";
+			MIN_VOLTAGE_LIMIT = 34: u16, ReadWrite, Eeprom, "Min Voltage Limit";
+			PWM_LIMIT = 36: u16, ReadWrite, Eeprom, "PWM Limit";
+			VELOCITY_LIMIT = 44: u32, ReadWrite, Eeprom, "Velocity Limit";
+			MAX_POSITION_LIMIT = 48: u32, ReadWrite, Eeprom, "Max Position Limit";
+			MIN_POSITION_LIMIT = 52: u32, ReadWrite, Eeprom, "Min Position Limit";
+			SHUTDOWN = 63: u8, ReadWrite, Eeprom, "Shutdown";
+
+			TORQUE_ENABLE = 64: bool, ReadWrite, Ram, "Torque Enable";
+			LED = 65: bool, ReadWrite, Ram, "LED";
+			STATUS_RETURN_LEVEL = 68: u8, ReadWrite, Ram, "Status Return Level";
+			REGISTERED_INSTRUCTION = 69: u8, ReadOnly, Ram, "Registered Instruction";
+			HARDWARE_ERROR_STATUS = 70: $crate::models::HardwareErrorStatus, ReadOnly, Ram, "Hardware Error Status";
+			VELOCITY_I_GAIN = 76: u16, ReadWrite, Ram, "Velocity I Gain";
+			VELOCITY_P_GAIN = 78: u16, ReadWrite, Ram, "Velocity P Gain";
+			POSITION_D_GAIN = 80: u16, ReadWrite, Ram, "Position D Gain";
+			POSITION_I_GAIN = 82: u16, ReadWrite, Ram, "Position I Gain";
+			POSITION_P_GAIN = 84: u16, ReadWrite, Ram, "Position P Gain";
+			FEEDFORWARD_2ND_GAIN = 88: u16, ReadWrite, Ram, "Feedforward 2nd Gain";
+			FEEDFORWARD_1ST_GAIN = 90: u16, ReadWrite, Ram, "Feedforward 1st Gain";
+			BUS_WATCHDOG = 98: u8, ReadWrite, Ram, "Bus Watchdog";
+			GOAL_PWM = 100: i16, ReadWrite, Ram, "Goal PWM";
+			GOAL_VELOCITY = 104: i32, ReadWrite, Ram, "Goal Velocity";
+			PROFILE_ACCELERATION = 108: u32, ReadWrite, Ram, "Profile Acceleration";
+			PROFILE_VELOCITY = 112: u32, ReadWrite, Ram, "Profile Velocity";
+			GOAL_POSITION = 116: i32, ReadWrite, Ram, "Goal Position";
+			REALTIME_TICK = 120: u16, ReadOnly, Ram, "Realtime Tick";
+			MOVING = 122: u8, ReadOnly, Ram, "Moving";
+			MOVING_STATUS = 123: u8, ReadOnly, Ram, "Moving Status";
+			PRESENT_PWM = 124: i16, ReadOnly, Ram, "Present PWM";
+			PRESENT_VELOCITY = 128: i32, ReadOnly, Ram, "Present Velocity";
+			PRESENT_POSITION = 132: i32, ReadOnly, Ram, "Present Position";
+			VELOCITY_TRAJECTORY = 136: i32, ReadOnly, Ram, "Velocity Trajectory";
+			POSITION_TRAJECTORY = 140: i32, ReadOnly, Ram, "Position Trajectory";
+			PRESENT_INPUT_VOLTAGE = 144: u16, ReadOnly, Ram, "Present Input Voltage";
+			PRESENT_TEMPERATURE = 146: u8, ReadOnly, Ram, "Present Temperature";
+			BACKUP_READY = 147: u8, ReadOnly, Ram, "Backup Ready";
+		}
+	};
+}
+
+/// Define the current control registers of X-series motors with a current sensor.
+macro_rules! x_series_current_registers {
+	() => {
+		$crate::models::registers! {
+			CURRENT_LIMIT = 38: u16, ReadWrite, Eeprom, "Current Limit";
+			GOAL_CURRENT = 102: i16, ReadWrite, Ram, "Goal Current";
+			PRESENT_CURRENT = 126: i16, ReadOnly, Ram, "Present Current";
+		}
+	};
+}
+
+/// Define the external port registers of X-series motors with external ports.
+macro_rules! x_series_external_port_registers {
+	() => {
+		$crate::models::registers! {
+			EXTERNAL_PORT_MODE_1 = 56: u8, ReadWrite, Eeprom, "External Port Mode 1";
+			EXTERNAL_PORT_MODE_2 = 57: u8, ReadWrite, Eeprom, "External Port Mode 2";
+			EXTERNAL_PORT_MODE_3 = 58: u8, ReadWrite, Eeprom, "External Port Mode 3";
+			EXTERNAL_PORT_DATA_1 = 152: u16, ReadWrite, Ram, "External Port Data 1";
+			EXTERNAL_PORT_DATA_2 = 154: u16, ReadWrite, Ram, "External Port Data 2";
+			EXTERNAL_PORT_DATA_3 = 156: u16, ReadWrite, Ram, "External Port Data 3";
+		}
+	};
+}
+
+use registers;
+use x_series_current_registers;
+use x_series_external_port_registers;
+use x_series_registers;
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn register_descriptors() {
+		assert!(xm430::GOAL_POSITION.address() == 116);
+		assert!(xm430::GOAL_POSITION.size() == 4);
+		assert!(xm430::GOAL_POSITION.access() == AccessMode::ReadWrite);
+		assert!(xm430::GOAL_POSITION.area() == MemoryArea::Ram);
+		assert!(xm430::PRESENT_CURRENT.address() == 126);
+		assert!(xm430::PRESENT_CURRENT.access() == AccessMode::Read);
+		assert!(xl430::PRESENT_LOAD.address() == 126);
+		assert!(xm540::EXTERNAL_PORT_DATA_1.address() == 152);
+		assert!(xl330::ID.area() == MemoryArea::Eeprom);
+		assert!(xm430::HARDWARE_ERROR_STATUS.address() == HARDWARE_ERROR_STATUS.address());
+	}
+
+	#[test]
+	fn hardware_error_status() {
+		let_assert!(Ok(status) = HardwareErrorStatus::decode(&[0x24]));
+		assert!(status == HardwareErrorStatus::OVERHEATING | HardwareErrorStatus::OVERLOAD);
+		assert!(status.to_string() == "overheating, overload");
+		assert!(HardwareErrorStatus::empty().to_string() == "no hardware error");
+
+		let_assert!(Ok(status) = HardwareErrorStatus::decode(&[0x82]));
+		assert!(status.bits() == 0x82);
+		assert!(status.to_string() == "unknown error bits 0x82");
+	}
+}
@@ -0,0 +1,6 @@
+//! Control table of the XH430 series (XH430-W210, XH430-W350, XH430-V210 and XH430-V350).
+//!
+//! See the [e-manual](https://emanual.robotis.com/docs/en/dxl/x/xh430-w350/#control-table) for the meaning of the registers.
+
+super::x_series_registers!();
+super::x_series_current_registers!();
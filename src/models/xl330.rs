@@ -0,0 +1,6 @@
+//! Control table of the XL330 series (XL330-M077 and XL330-M288).
+//!
+//! See the [e-manual](https://emanual.robotis.com/docs/en/dxl/x/xl330-m288/#control-table) for the meaning of the registers.
+
+super::x_series_registers!();
+super::x_series_current_registers!();
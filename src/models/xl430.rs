@@ -0,0 +1,10 @@
+//! Control table of the XL430-W250.
+//!
+//! See the [e-manual](https://emanual.robotis.com/docs/en/dxl/x/xl430-w250/#control-table) for the meaning of the registers.
+
+super::x_series_registers!();
+
+// The XL430 has no current sensor, so it reports the load instead.
+super::registers! {
+	PRESENT_LOAD = 126: i16, ReadOnly, Ram, "Present Load";
+}
@@ -0,0 +1,6 @@
+//! Control table of the XM430 series (XM430-W210 and XM430-W350).
+//!
+//! See the [e-manual](https://emanual.robotis.com/docs/en/dxl/x/xm430-w350/#control-table) for the meaning of the registers.
+
+super::x_series_registers!();
+super::x_series_current_registers!();
@@ -0,0 +1,7 @@
+//! Control table of the XM540 series (XM540-W150 and XM540-W270).
+//!
+//! See the [e-manual](https://emanual.robotis.com/docs/en/dxl/x/xm540-w270/#control-table) for the meaning of the registers.
+
+super::x_series_registers!();
+super::x_series_current_registers!();
+super::x_series_external_port_registers!();
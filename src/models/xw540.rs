@@ -0,0 +1,7 @@
+//! Control table of the XW540 series (XW540-T140 and XW540-T260).
+//!
+//! See the [e-manual](https://emanual.robotis.com/docs/en/dxl/x/xw540-t260/#control-table) for the meaning of the registers.
+
+super::x_series_registers!();
+super::x_series_current_registers!();
+super::x_series_external_port_registers!();
@@ -0,0 +1,314 @@
+//! Low level interface to a DYNAMIXEL Protocol 1.0 bus.
+
+use core::time::Duration;
+
+use crate::bus::{Bus, ExpectedPacket};
+use crate::{ReadError, TransferError, WriteError};
+
+/// Prefix of a Protocol 1.0 packet.
+///
+/// Unlike Protocol 2.0, the prefix may also occur in the body of a packet.
+const HEADER_PREFIX: [u8; 2] = [0xFF, 0xFF];
+
+/// The size of a message header, including the pre-amble, packet ID and length.
+///
+/// Excludes the instruction ID or error field, the parameters and the checksum.
+const HEADER_SIZE: usize = 4;
+
+/// The maximum number of parameters in a packet, limited by the 8-bit length field.
+const MAX_PARAMETERS: usize = 253;
+
+/// Protocol 1.0 support for the low level bus.
+///
+/// The Protocol 1.0 packets use the same serial port and buffers as the Protocol 2.0 packets,
+/// so that both protocols can be used on the same bus.
+impl<SerialPort, Buffer> Bus<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Write a Protocol 1.0 instruction message to the bus.
+	///
+	/// The length field of a Protocol 1.0 packet is only 8 bits.
+	/// If `parameter_count` exceeds 253, a [`WriteError::BufferTooSmall`] is returned,
+	/// as if the write buffer could not hold more than the largest possible packet.
+	pub(crate) fn write_protocol1_instruction<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		encode_parameters: F,
+	) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		let buffer = self.write_buffer.as_mut();
+		let checksum_index = HEADER_SIZE + 1 + parameter_count;
+		let max_message_len = buffer.len().min(HEADER_SIZE + 1 + MAX_PARAMETERS + 1);
+		crate::error::BufferTooSmallError::check(checksum_index + 1, max_message_len)?;
+
+		buffer[..2].copy_from_slice(&HEADER_PREFIX);
+		buffer[2] = packet_id;
+		buffer[3] = parameter_count as u8 + 2;
+		buffer[4] = instruction_id;
+		encode_parameters(&mut buffer[HEADER_SIZE + 1..][..parameter_count])?;
+		buffer[checksum_index] = super::calculate_checksum(&buffer[2..checksum_index]);
+		self.response_motor_id = (packet_id != crate::instructions::packet_id::BROADCAST).then_some(packet_id);
+
+		// Throw away old data in the read buffer and the kernel read buffer.
+		// We don't do this when reading a reply, because we might receive multiple replies for one instruction,
+		// and read() can potentially read more than one reply per syscall.
+		self.read_len = 0;
+		self.used_bytes = 0;
+		self.serial_port.discard_input_buffer().map_err(WriteError::DiscardBuffer)?;
+
+		// Send message.
+		let message = &buffer[..checksum_index + 1];
+		trace!("sending protocol 1 packet: {:02X?}", message);
+		self.serial_port.write_all(message).map_err(WriteError::Write)?;
+		self.record_sent(checksum_index + 1);
+		Ok(())
+	}
+
+	/// Read a Protocol 1.0 status packet from the bus with the given deadline.
+	pub(crate) fn read_protocol1_status_deadline(
+		&mut self,
+		deadline: SerialPort::Instant,
+		expected: ExpectedPacket,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>> {
+		// Check that the read buffer is large enough to hold atleast a status packet with 0 parameters.
+		crate::error::BufferTooSmallError::check(HEADER_SIZE + 2, self.read_buffer.as_mut().len())?;
+
+		let message_len = loop {
+			self.remove_protocol1_garbage();
+
+			// The call to remove_garbage() removes all leading bytes that don't match a packet header.
+			// So if there's enough bytes left, it's a packet header.
+			if self.read_len >= HEADER_SIZE {
+				let body_len = usize::from(self.read_buffer.as_ref()[3]);
+
+				// The body must hold atleast the error field and the checksum.
+				if body_len < 2 {
+					self.consume_read_bytes(HEADER_SIZE);
+					return Err(crate::InvalidParameterCount {
+						actual: 0,
+						expected: crate::ExpectedCount::Min(1),
+					}.into());
+				}
+
+				// Check if the read buffer is large enough for the entire message.
+				crate::error::BufferTooSmallError::check(HEADER_SIZE + body_len, self.read_buffer.as_mut().len()).inspect_err(|_| {
+					self.consume_read_bytes(HEADER_SIZE);
+				})?;
+
+				if self.read_len >= HEADER_SIZE + body_len {
+					break HEADER_SIZE + body_len;
+				}
+			}
+
+			// Try to read more data into the buffer.
+			match self.serial_port.read(&mut self.read_buffer.as_mut()[self.read_len..], &deadline) {
+				Ok(new_data) => self.record_read(new_data),
+				Err(e) if SerialPort::is_timeout_error(&e) => {
+					// The header of a Protocol 1.0 packet is only known to be complete once the length byte is received.
+					let size = if self.read_len >= HEADER_SIZE {
+						HEADER_SIZE + usize::from(self.read_buffer.as_ref()[3])
+					} else {
+						expected.size.unwrap_or(HEADER_SIZE + 2)
+					};
+					return Err(self.timeout_error(&ExpectedPacket { size: Some(size), ..expected }));
+				},
+				Err(e) => return Err(ReadError::Io(e)),
+			}
+		};
+
+		let buffer = self.read_buffer.as_ref();
+		let parameters_end = message_len - 1;
+		trace!("read protocol 1 packet: {:02X?}", &buffer[..parameters_end]);
+
+		let checksum_message = buffer[parameters_end];
+		let checksum_computed = super::calculate_checksum(&buffer[2..parameters_end]);
+		if checksum_message != checksum_computed {
+			self.stats.checksum_errors += 1;
+			self.consume_read_bytes(message_len);
+			return Err(crate::InvalidChecksum {
+				message: checksum_message.into(),
+				computed: checksum_computed.into(),
+			}
+			.into());
+		}
+
+		// Mark the whole message as "used_bytes", so that the next call to `remove_garbage()` removes it.
+		self.used_bytes += message_len;
+		self.stats.packets_received += 1;
+		if super::check_motor_error(buffer[4]).is_err() {
+			self.stats.record_motor_error(buffer[2]);
+		}
+
+		Ok(StatusPacket {
+			data: &self.read_buffer.as_ref()[..parameters_end],
+		})
+	}
+
+	/// Read a Protocol 1.0 status packet from the bus with the given timeout.
+	///
+	/// Errors other than hardware errors in the error field are reported as [`crate::MotorError`].
+	pub(crate) fn read_protocol1_status_timeout(
+		&mut self,
+		timeout: Duration,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>> {
+		self.read_protocol1_status_expected(ExpectedPacket::unknown_size(timeout))
+	}
+
+	/// Read a Protocol 1.0 status packet from the bus, waiting for the given expected packet.
+	fn read_protocol1_status_expected(
+		&mut self,
+		expected: ExpectedPacket,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>> {
+		let deadline = self.serial_port.make_deadline(expected.timeout);
+		let status = self.read_protocol1_status_deadline(deadline, expected)?;
+		super::check_motor_error(status.error())?;
+		Ok(status)
+	}
+
+	/// Read a Protocol 1.0 status packet, with a timeout based on the expected number of parameters.
+	pub(crate) fn read_protocol1_status_response(
+		&mut self,
+		expected_parameters: u8,
+	) -> Result<StatusPacket<'_>, ReadError<SerialPort::Error>> {
+		let message_size = StatusPacket::message_len(expected_parameters.into());
+		self.read_protocol1_status_expected(ExpectedPacket {
+			size: Some(message_size),
+			exact_size: false,
+			timeout: crate::bus::response_timeout(message_size, self.baud_rate),
+		})
+	}
+
+	/// Write a Protocol 1.0 instruction, and read a single status response from the same motor.
+	pub(crate) fn transfer_protocol1_single<F>(
+		&mut self,
+		packet_id: u8,
+		instruction_id: u8,
+		parameter_count: usize,
+		expected_response_parameters: u8,
+		encode_parameters: F,
+	) -> Result<StatusPacket<'_>, TransferError<SerialPort::Error>>
+	where
+		F: FnOnce(&mut [u8]) -> Result<(), crate::error::BufferTooSmallError>,
+	{
+		self.write_protocol1_instruction(packet_id, instruction_id, parameter_count, encode_parameters)?;
+		let response = self.read_protocol1_status_response(expected_response_parameters)?;
+		crate::error::InvalidPacketId::check(response.packet_id(), packet_id).map_err(ReadError::from)?;
+		Ok(response)
+	}
+
+	/// Remove leading data from the read buffer that is not a Protocol 1.0 packet.
+	fn remove_protocol1_garbage(&mut self) {
+		let read_buffer = self.read_buffer.as_mut();
+		let garbage_len = find_header(&read_buffer[..self.read_len][self.used_bytes..]);
+		if garbage_len > 0 {
+			self.stats.garbage_bytes += garbage_len as u64;
+			debug!("skipping {} bytes of leading garbage.", garbage_len);
+			trace!("skipped garbage: {:02X?}", &read_buffer[..garbage_len]);
+		}
+		self.consume_read_bytes(self.used_bytes + garbage_len);
+		debug_assert_eq!(self.used_bytes, 0);
+	}
+}
+
+/// Find the potential starting position of a header.
+///
+/// This will return the first possible position of the header prefix.
+/// Note that if the buffer ends with a partial header prefix,
+/// the start position of the partial header prefix is returned.
+///
+/// Since 0xFF is not a valid packet ID, a prefix followed by another 0xFF byte is skipped.
+fn find_header(buffer: &[u8]) -> usize {
+	for i in 0..buffer.len() {
+		let possible_prefix = HEADER_PREFIX.len().min(buffer.len() - i);
+		if buffer[i..].starts_with(&HEADER_PREFIX[..possible_prefix]) && buffer.get(i + 2) != Some(&0xFF) {
+			return i;
+		}
+	}
+
+	buffer.len()
+}
+
+/// A Protocol 1.0 status packet in the read buffer of the client.
+///
+/// Sent by a device to the client.
+#[derive(Debug, Copy, Clone)]
+pub struct StatusPacket<'a> {
+	/// Message data, without the checksum.
+	data: &'a [u8],
+}
+
+impl<'a> StatusPacket<'a> {
+	/// Get the total length of a status packet with the given number of parameters.
+	pub fn message_len(parameters: usize) -> usize {
+		HEADER_SIZE + 2 + parameters
+	}
+
+	/// The packet ID.
+	pub fn packet_id(self) -> u8 {
+		self.data[2]
+	}
+
+	/// The error field of the response.
+	///
+	/// See [`crate::protocol1::error_bit`] for the meaning of the individual bits.
+	pub fn error(self) -> u8 {
+		self.data[4]
+	}
+
+	/// Check if the error field reports a hardware error.
+	///
+	/// This is used as the `alert` field of a [`crate::Response`].
+	/// It is set if the input voltage, overheating or overload bits are set.
+	pub fn alert(self) -> bool {
+		self.error() & super::error_bit::HARDWARE != 0
+	}
+
+	/// The parameters of the response.
+	pub fn parameters(self) -> &'a [u8] {
+		&self.data[HEADER_SIZE + 1..]
+	}
+}
+
+impl<'a> TryFrom<StatusPacket<'a>> for crate::Response<()> {
+	type Error = crate::InvalidParameterCount;
+
+	fn try_from(status_packet: StatusPacket<'a>) -> Result<Self, Self::Error> {
+		crate::InvalidParameterCount::check(status_packet.parameters().len(), 0)?;
+		Ok(Self {
+			motor_id: status_packet.packet_id(),
+			alert: status_packet.alert(),
+			data: (),
+		})
+	}
+}
+
+impl<'a> From<StatusPacket<'a>> for crate::Response<&'a [u8]> {
+	fn from(status_packet: StatusPacket<'a>) -> Self {
+		Self {
+			motor_id: status_packet.packet_id(),
+			alert: status_packet.alert(),
+			data: status_packet.parameters(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_find_header() {
+		assert!(find_header(&[0xFF, 0xFF, 0x01, 0x02]) == 0);
+		assert!(find_header(&[0x00, 0xFF, 0xFF, 0x01]) == 1);
+		assert!(find_header(&[0xFF, 0xFF, 0xFF, 0x01]) == 1);
+		assert!(find_header(&[0x00, 0x01, 0xFF]) == 2);
+		assert!(find_header(&[0x00, 0x01, 0x02]) == 3);
+	}
+}
@@ -0,0 +1,522 @@
+use core::marker::PhantomData;
+
+use super::{instruction_id, Client, StatusPacket};
+use crate::bus::Data;
+use crate::instructions::{packet_id, SyncWriteData};
+use crate::{ReadError, Response, TransferError, WriteError};
+
+/// Bulk read data for a specific motor.
+///
+/// Used by [`Client::bulk_read_bytes`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BulkReadData {
+	/// The ID of the motor.
+	pub motor_id: u8,
+
+	/// The address for the read command.
+	pub address: u8,
+
+	/// The number of bytes to read.
+	pub count: u8,
+}
+
+impl<SerialPort, Buffer> Client<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Ping a specific motor by ID.
+	///
+	/// Protocol 1.0 motors do not report their model or firmware version in response to a ping.
+	/// Read them from the control table instead.
+	///
+	/// This will not work correctly if the motor ID is [`packet_id::BROADCAST`].
+	pub fn ping(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		let response = self.transfer_single(motor_id, instruction_id::PING, 0, 0, |_| Ok(()))?;
+		Ok(response.try_into().map_err(ReadError::from)?)
+	}
+
+	fn read_raw(&mut self, motor_id: u8, address: u8, count: u8) -> Result<StatusPacket<'_>, TransferError<SerialPort::Error>> {
+		let response = self.transfer_single(motor_id, instruction_id::READ, 2, count, |buffer| {
+			buffer[0] = address;
+			buffer[1] = count;
+			Ok(())
+		})?;
+		crate::error::InvalidParameterCount::check(response.parameters().len(), count.into()).map_err(ReadError::from)?;
+		Ok(response)
+	}
+
+	/// Read an arbitrary number of bytes from a specific motor.
+	///
+	/// This function will not work correctly if the motor ID is set to [`packet_id::BROADCAST`].
+	pub fn read_bytes<'a, T>(&'a mut self, motor_id: u8, address: u8, count: u8) -> Result<Response<T>, TransferError<SerialPort::Error>>
+	where
+		T: From<&'a [u8]>,
+	{
+		let status = self.read_raw(motor_id, address, count)?;
+		Ok(Response {
+			motor_id: status.packet_id(),
+			alert: status.alert(),
+			data: T::from(status.parameters()),
+		})
+	}
+
+	/// Read a value from a specific motor.
+	///
+	/// Specify the return type using turbofish: `client.read::<u8>`
+	///
+	/// This function will not work correctly if the motor ID is set to [`packet_id::BROADCAST`].
+	pub fn read<T: Data>(&mut self, motor_id: u8, address: u8) -> Result<Response<T>, TransferError<SerialPort::Error>> {
+		let status = self.read_raw(motor_id, address, encoded_size::<T>())?;
+		Ok(Response {
+			motor_id: status.packet_id(),
+			alert: status.alert(),
+			data: T::decode(status.parameters()).map_err(ReadError::from)?,
+		})
+	}
+
+	/// Write value to a specific motor.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub fn write<T: Data>(&mut self, motor_id: u8, address: u8, data: &T) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::WRITE, 1 + usize::from(encoded_size::<T>()), |buffer| {
+			buffer[0] = address;
+			data.encode(&mut buffer[1..])
+		})?;
+		Ok(read_response_if_not_broadcast(self, motor_id)?)
+	}
+
+	/// Write an arbitrary amount of bytes to a specific motor.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub fn write_bytes(&mut self, motor_id: u8, address: u8, data: &[u8]) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::WRITE, 1 + data.len(), |buffer| {
+			buffer[0] = address;
+			buffer[1..].copy_from_slice(data);
+			Ok(())
+		})?;
+		Ok(read_response_if_not_broadcast(self, motor_id)?)
+	}
+
+	/// Register a write of a value to a specific motor.
+	///
+	/// The value will be written to the motor when the [`Self::action`] or [`Self::broadcast_action`] instruction is sent.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub fn reg_write<T: Data>(&mut self, motor_id: u8, address: u8, data: &T) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::REG_WRITE, 1 + usize::from(encoded_size::<T>()), |buffer| {
+			buffer[0] = address;
+			data.encode(&mut buffer[1..])
+		})?;
+		Ok(read_response_if_not_broadcast(self, motor_id)?)
+	}
+
+	/// Register a write of an arbitrary number of bytes to a specific motor.
+	///
+	/// The data will be written to the motor when the [`Self::action`] or [`Self::broadcast_action`] instruction is sent.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	pub fn reg_write_bytes(&mut self, motor_id: u8, address: u8, data: &[u8]) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::REG_WRITE, 1 + data.len(), |buffer| {
+			buffer[0] = address;
+			buffer[1..].copy_from_slice(data);
+			Ok(())
+		})?;
+		Ok(read_response_if_not_broadcast(self, motor_id)?)
+	}
+
+	/// Send an action command to trigger a previously registered instruction.
+	///
+	/// You may specify [`packet_id::BROADCAST`] as motor ID.
+	/// If you do, none of the devices will reply with a response, and this function will not wait for any.
+	///
+	/// If you want to broadcast this instruction, it may be more convenient to use [`Self::broadcast_action()`] instead.
+	pub fn action(&mut self, motor_id: u8) -> Result<Response<()>, TransferError<SerialPort::Error>> {
+		self.write_instruction(motor_id, instruction_id::ACTION, 0, |_| Ok(()))?;
+		Ok(read_response_if_not_broadcast(self, motor_id)?)
+	}
+
+	/// Broadcast an action command to all connected motors to trigger a previously registered instruction.
+	pub fn broadcast_action(&mut self) -> Result<(), WriteError<SerialPort::Error>> {
+		self.write_instruction(packet_id::BROADCAST, instruction_id::ACTION, 0, |_| Ok(()))
+	}
+
+	/// Synchronously write an arbitrary number of bytes to multiple motors.
+	///
+	/// Each motor will perform the write as soon as it receives the command.
+	/// This gives much shorter delays than executing a regular [`Self::write`] for each motor individually.
+	///
+	/// # Panics
+	/// The amount of data to write for each motor must be exactly `count` bytes.
+	/// This function panics if that is not the case.
+	pub fn sync_write_bytes<'a, Iter, Data, Buf>(&mut self, address: u8, count: u8, data: Iter) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		Iter: IntoIterator<Item = Data>,
+		Iter::IntoIter: ExactSizeIterator,
+		Data: AsRef<SyncWriteData<Buf>>,
+		Buf: AsRef<[u8]> + 'a,
+	{
+		let data = data.into_iter();
+		let motors = data.len();
+		let stride = 1 + usize::from(count);
+		let parameter_count = 2 + motors * stride;
+		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_WRITE, parameter_count, |buffer| {
+			buffer[0] = address;
+			buffer[1] = count;
+			for (i, command) in data.enumerate() {
+				let command = command.as_ref();
+				assert_eq!(command.data.as_ref().len(), count as usize);
+				let buffer = &mut buffer[2 + i * stride..][..stride];
+				buffer[0] = command.motor_id;
+				buffer[1..].copy_from_slice(command.data.as_ref());
+			}
+			Ok(())
+		})
+	}
+
+	/// Synchronously write a value to multiple motors.
+	///
+	/// Each motor will perform the write as soon as it receives the command.
+	/// This gives much shorter delays than executing a regular [`Self::write`] for each motor individually.
+	pub fn sync_write<Iter, Data, T>(&mut self, address: u8, data: Iter) -> Result<(), WriteError<SerialPort::Error>>
+	where
+		Iter: IntoIterator<Item = Data>,
+		Iter::IntoIter: ExactSizeIterator,
+		Data: AsRef<SyncWriteData<T>>,
+		T: crate::bus::Data,
+	{
+		let data = data.into_iter();
+		let count = encoded_size::<T>();
+		let motors = data.len();
+		let stride = 1 + usize::from(count);
+		let parameter_count = 2 + motors * stride;
+		self.write_instruction(packet_id::BROADCAST, instruction_id::SYNC_WRITE, parameter_count, |buffer| {
+			buffer[0] = address;
+			buffer[1] = count;
+			for (i, command) in data.enumerate() {
+				let command = command.as_ref();
+				let buffer = &mut buffer[2 + i * stride..][..stride];
+				buffer[0] = command.motor_id;
+				command.data.encode(&mut buffer[1..])?;
+			}
+			Ok(())
+		})
+	}
+
+	/// Synchronously read arbitrary data ranges from multiple motors.
+	///
+	/// Each motor replies with a separate status packet, in the order of `reads`.
+	/// The bulk read instruction is only supported by some Protocol 1.0 motors, like the MX series.
+	///
+	/// # Panics
+	/// The protocol forbids specifying the same motor ID multiple times.
+	/// This function panics if the same motor ID is used for more than one read.
+	pub fn bulk_read_bytes<'a, T>(
+		&'a mut self,
+		reads: &'a [BulkReadData],
+	) -> Result<BulkReadBytes<'a, T, SerialPort, Buffer>, WriteError<SerialPort::Error>>
+	where
+		T: for<'b> From<&'b [u8]>,
+	{
+		for i in 0..reads.len() {
+			for j in i + 1..reads.len() {
+				if reads[i].motor_id == reads[j].motor_id {
+					panic!(
+						"bulk_read_bytes: motor ID {} used multiple at index {} and {}",
+						reads[i].motor_id, i, j
+					)
+				}
+			}
+		}
+
+		self.write_instruction(packet_id::BROADCAST, instruction_id::BULK_READ, 1 + 3 * reads.len(), |buffer| {
+			buffer[0] = 0x00;
+			for (i, read) in reads.iter().enumerate() {
+				let buffer = &mut buffer[1 + i * 3..][..3];
+				buffer[0] = read.count;
+				buffer[1] = read.motor_id;
+				buffer[2] = read.address;
+			}
+			Ok(())
+		})?;
+
+		Ok(BulkReadBytes {
+			client: self,
+			bulk_read_data: reads,
+			index: 0,
+			data: PhantomData,
+		})
+	}
+}
+
+/// Get the encoded size of a [`Data`] type as 8-bit count.
+///
+/// # Panics
+/// Panics if the encoded size does not fit in a Protocol 1.0 packet.
+fn encoded_size<T: Data>() -> u8 {
+	match u8::try_from(T::ENCODED_SIZE) {
+		Ok(size) => size,
+		Err(_) => panic!("encoded size of {} is too large for protocol 1.0: {}", core::any::type_name::<T>(), T::ENCODED_SIZE),
+	}
+}
+
+fn read_response_if_not_broadcast<SerialPort, Buffer>(
+	client: &mut Client<SerialPort, Buffer>,
+	motor_id: u8,
+) -> Result<Response<()>, ReadError<SerialPort::Error>>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	if motor_id == packet_id::BROADCAST {
+		Ok(Response {
+			motor_id: packet_id::BROADCAST,
+			alert: false,
+			data: (),
+		})
+	} else {
+		Ok(client.read_status_response(0)?.try_into()?)
+	}
+}
+
+/// A Protocol 1.0 bulk read operation that returns unparsed bytes.
+pub struct BulkReadBytes<'a, T, SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	client: &'a mut Client<SerialPort, Buffer>,
+	bulk_read_data: &'a [BulkReadData],
+	index: usize,
+	data: PhantomData<fn() -> T>,
+}
+
+impl<T, SerialPort, Buffer> core::fmt::Debug for BulkReadBytes<'_, T, SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("BulkRead")
+			.field("serial_port", self.client.serial_port())
+			.field("bulk_read_data", &self.bulk_read_data)
+			.field("index", &self.index)
+			.field("data", &format_args!("{}", core::any::type_name::<T>()))
+			.finish()
+	}
+}
+
+impl<T, SerialPort, Buffer> Drop for BulkReadBytes<'_, T, SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn drop(&mut self) {
+		for data in &self.bulk_read_data[self.index..] {
+			self.client.read_status_response(data.count).ok();
+		}
+	}
+}
+
+impl<T, SerialPort, Buffer> Iterator for BulkReadBytes<'_, T, SerialPort, Buffer>
+where
+	T: for<'b> From<&'b [u8]>,
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	type Item = Result<Response<T>, ReadError<SerialPort::Error>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.read_next()
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(0, Some(self.remaining()))
+	}
+}
+
+impl<T, SerialPort, Buffer> BulkReadBytes<'_, T, SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Get the number of responses that should still be received.
+	pub fn remaining(&self) -> usize {
+		self.bulk_read_data.len() - self.index
+	}
+
+	/// Read the next motor reply.
+	pub fn read_next(&mut self) -> Option<Result<Response<T>, ReadError<SerialPort::Error>>>
+	where
+		T: for<'b> From<&'b [u8]>,
+	{
+		let BulkReadData { motor_id, count, .. } = *self.bulk_read_data.get(self.index)?;
+		self.index += 1;
+		Some(self.next_response(motor_id, count))
+	}
+
+	fn next_response(&mut self, motor_id: u8, count: u8) -> Result<Response<T>, ReadError<SerialPort::Error>>
+	where
+		T: for<'b> From<&'b [u8]>,
+	{
+		let response = self.client.read_status_response(count)?;
+		crate::InvalidPacketId::check(response.packet_id(), motor_id)?;
+		crate::InvalidParameterCount::check(response.parameters().len(), count.into())?;
+		Ok(Response {
+			motor_id: response.packet_id(),
+			alert: response.alert(),
+			data: T::from(response.parameters()),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use super::super::calculate_checksum;
+	use crate::serial_port::reply::{ReplySerial, ResponderSerial};
+	use assert2::{assert, let_assert};
+
+	/// Build a Protocol 1.0 status packet.
+	fn status_packet(motor_id: u8, error: u8, parameters: &[u8]) -> Vec<u8> {
+		let mut packet = vec![0xFF, 0xFF, motor_id, parameters.len() as u8 + 2, error];
+		packet.extend_from_slice(parameters);
+		packet.push(calculate_checksum(&packet[2..]));
+		packet
+	}
+
+	fn make_client(response: Vec<u8>) -> Client<ReplySerial> {
+		let_assert!(Ok(client) = Client::new(ReplySerial { written: Vec::new(), response }));
+		client
+	}
+
+	#[test]
+	fn read_encodes_instruction_and_decodes_response() {
+		let mut client = make_client(status_packet(1, 0, &[0x20]));
+		let_assert!(Ok(response) = client.read::<u8>(1, 43));
+		assert!(response == Response { motor_id: 1, alert: false, data: 0x20 });
+		assert!(client.serial_port().written == [0xFF, 0xFF, 0x01, 0x04, 0x02, 0x2B, 0x01, 0xCC]);
+	}
+
+	#[test]
+	fn read_skips_garbage_and_reports_hardware_errors_as_alert() {
+		let mut response = vec![0x00, 0xFF, 0xFF, 0xFF];
+		response.extend(status_packet(7, super::super::error_bit::OVERHEATING, &[0x00, 0x02]));
+		let mut client = make_client(response);
+		let_assert!(Ok(response) = client.read::<u16>(7, 36));
+		assert!(response == Response { motor_id: 7, alert: true, data: 0x0200 });
+	}
+
+	#[test]
+	fn write_reports_instruction_errors() {
+		let mut client = make_client(status_packet(3, super::super::error_bit::RANGE, &[]));
+		let_assert!(Err(TransferError::ReadError(ReadError::MotorError(error))) = client.write(3, 30, &512u16));
+		assert!(error.raw == super::super::error_bit::RANGE);
+		assert!(client.serial_port().written == [0xFF, 0xFF, 0x03, 0x05, 0x03, 0x1E, 0x00, 0x02, 0xD4]);
+	}
+
+	#[test]
+	fn read_checks_checksum() {
+		let mut response = status_packet(1, 0, &[0x20]);
+		*response.last_mut().unwrap() ^= 0xFF;
+		let mut client = make_client(response);
+		let_assert!(Err(TransferError::ReadError(ReadError::InvalidMessage(error))) = client.read::<u8>(1, 43));
+		assert!(let crate::InvalidMessage::InvalidChecksum(_) = error);
+	}
+
+	#[test]
+	fn write_rejects_too_many_parameters() {
+		let_assert!(Ok(mut client) = Client::with_buffers(ReplySerial { written: Vec::new(), response: Vec::new() }, vec![0; 64], vec![0; 512]));
+		let_assert!(Err(WriteError::BufferTooSmall(e)) = client.write_instruction(1, instruction_id::WRITE, 254, |_| Ok(())));
+		assert!(e.required_size == 260);
+		assert!(e.total_size == 259);
+		assert!(client.serial_port().written.is_empty());
+		let_assert!(Ok(()) = client.write_instruction(1, instruction_id::WRITE, 253, |_| Ok(())));
+	}
+
+	#[test]
+	fn read_reports_timeout() {
+		let mut client = make_client(Vec::new());
+		let_assert!(Err(TransferError::ReadError(ReadError::Timeout { motor_id, expected_bytes, waited })) = client.read::<u16>(1, 36));
+		assert!(motor_id == Some(1));
+		assert!(expected_bytes == 8);
+		assert!(waited == crate::bus::response_timeout(8, 1_000_000));
+		assert!(client.stats().timeouts == 1);
+	}
+
+	#[test]
+	fn transfers_are_recorded_in_stats() {
+		let mut good = vec![0x00, 0xFF];
+		good.extend(status_packet(1, 0, &[0x20]));
+		let mut bad_checksum = status_packet(1, 0, &[0x20]);
+		*bad_checksum.last_mut().unwrap() ^= 0xFF;
+		let mut responses = vec![Vec::new(), bad_checksum, good];
+		let serial_port = ResponderSerial {
+			written: Vec::new(),
+			response: Vec::new(),
+			respond: move |_: &[u8]| responses.pop().unwrap_or_default(),
+		};
+		let_assert!(Ok(mut client) = Client::new(serial_port));
+
+		let_assert!(Ok(_) = client.read::<u8>(1, 43));
+		let_assert!(Err(_) = client.read::<u8>(1, 43));
+		let_assert!(Err(e) = client.read::<u8>(1, 43));
+		assert!(e.is_timeout());
+
+		let stats = client.stats();
+		assert!(stats.packets_sent == 3);
+		assert!(stats.bytes_sent == 24);
+		assert!(stats.packets_received == 1);
+		assert!(stats.bytes_received == 16);
+		assert!(stats.checksum_errors == 1);
+		assert!(stats.garbage_bytes == 2);
+		assert!(stats.timeouts == 1);
+
+		client.reset_stats();
+		assert!(client.stats().packets_sent == 0);
+	}
+
+	#[test]
+	fn broadcast_write_does_not_wait_for_response() {
+		let mut client = make_client(Vec::new());
+		let_assert!(Ok(response) = client.write_bytes(packet_id::BROADCAST, 24, &[1]));
+		assert!(response.motor_id == packet_id::BROADCAST);
+	}
+
+	#[test]
+	fn sync_write_encodes_all_motors() {
+		let mut client = make_client(Vec::new());
+		let data = [
+			SyncWriteData { motor_id: 1, data: 0x010u16 },
+			SyncWriteData { motor_id: 2, data: 0x220u16 },
+		];
+		let_assert!(Ok(()) = client.sync_write(30, &data));
+		let written = &client.serial_port().written;
+		assert!(written[2..5] == [packet_id::BROADCAST, 10, instruction_id::SYNC_WRITE]);
+		assert!(written[5..13] == [30, 2, 1, 0x10, 0x00, 2, 0x20, 0x02]);
+	}
+
+	#[test]
+	fn bulk_read_reads_each_motor() {
+		let mut response = status_packet(1, 0, &[1, 2]);
+		response.extend(status_packet(2, 0, &[3]));
+		let mut client = make_client(response);
+		let reads = [
+			BulkReadData { motor_id: 1, address: 30, count: 2 },
+			BulkReadData { motor_id: 2, address: 36, count: 1 },
+		];
+		let responses: Vec<_> = match client.bulk_read_bytes::<Vec<u8>>(&reads) {
+			Ok(responses) => responses.collect(),
+			Err(e) => panic!("bulk read failed: {e}"),
+		};
+		let_assert!([Ok(first), Ok(second)] = responses.as_slice());
+		assert!(first == &Response { motor_id: 1, alert: false, data: vec![1, 2] });
+		assert!(second == &Response { motor_id: 2, alert: false, data: vec![3] });
+		let written = &client.serial_port().written;
+		assert!(written[4..12] == [instruction_id::BULK_READ, 0x00, 2, 1, 30, 1, 2, 36]);
+	}
+}
@@ -0,0 +1,115 @@
+//! An implementation of the [Dynamixel Protocol 1.0].
+//!
+//! [Dynamixel Protocol 1.0]: https://emanual.robotis.com/docs/en/dxl/protocol1/
+//!
+//! Older motors, like the AX and RX series, only support Protocol 1.0.
+//! The [`Client`] in this module can be used to communicate with them.
+//! It uses the same [`SerialPort`][crate::SerialPort] and [`Data`][crate::bus::Data] traits as the Protocol 2.0 [`crate::Client`],
+//! and returns the same [`Response`][crate::Response] type.
+//!
+//! Protocol 1.0 uses 8-bit addresses and lengths, an 8-bit checksum and no byte stuffing.
+//! Instead of an alert bit, the status packet contains a set of [`error_bit`]s.
+//! If the motor reports a hardware error (input voltage, overheating or overload),
+//! the instruction was still executed and the `alert` field of the [`Response`][crate::Response] is set.
+//! All other errors are reported as [`MotorError`][crate::MotorError], with the raw error field of the status packet.
+//!
+//! Both protocols can be used on the same bus.
+//! Use [`crate::Client::scan_all_protocols()`] to find out which motor speaks which protocol.
+//!
+//! This module is only available if the `protocol1` feature is enabled.
+
+mod bus;
+pub use bus::StatusPacket;
+
+mod client;
+pub use client::*;
+
+mod instructions;
+pub use instructions::*;
+
+mod scan;
+pub use scan::*;
+
+/// Raw instruction IDs of Protocol 1.0.
+///
+/// Protocol 1.0 has no instruction ID for status packets.
+/// The broadcast packet ID is the same as for Protocol 2.0: [`crate::instructions::packet_id::BROADCAST`].
+#[rustfmt::skip]
+#[allow(missing_docs)]
+pub mod instruction_id {
+	pub const PING          : u8 = 0x01;
+	pub const READ          : u8 = 0x02;
+	pub const WRITE         : u8 = 0x03;
+	pub const REG_WRITE     : u8 = 0x04;
+	pub const ACTION        : u8 = 0x05;
+	pub const FACTORY_RESET : u8 = 0x06;
+	pub const REBOOT        : u8 = 0x08;
+	pub const SYNC_WRITE    : u8 = 0x83;
+	pub const BULK_READ     : u8 = 0x92;
+}
+
+/// Bits of the error field of a Protocol 1.0 status packet.
+pub mod error_bit {
+	/// The input voltage is out of the operating range.
+	pub const INPUT_VOLTAGE: u8 = 0x01;
+
+	/// The goal position is outside of the angle limits.
+	pub const ANGLE_LIMIT: u8 = 0x02;
+
+	/// The internal temperature is too high.
+	pub const OVERHEATING: u8 = 0x04;
+
+	/// An instruction parameter is out of range.
+	pub const RANGE: u8 = 0x08;
+
+	/// The checksum of the instruction packet is incorrect.
+	pub const CHECKSUM: u8 = 0x10;
+
+	/// The load can not be controlled with the configured maximum torque.
+	pub const OVERLOAD: u8 = 0x20;
+
+	/// The instruction is not defined, or an action was sent without a preceding reg write.
+	pub const INSTRUCTION: u8 = 0x40;
+
+	/// The bits that indicate a hardware error.
+	///
+	/// If only these bits are set, the instruction was still executed.
+	pub const HARDWARE: u8 = INPUT_VOLTAGE | OVERHEATING | OVERLOAD;
+}
+
+/// Calculate the checksum of a Protocol 1.0 packet.
+///
+/// The data must start at the packet ID and end with the last parameter.
+pub fn calculate_checksum(data: &[u8]) -> u8 {
+	!data.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
+/// Check the error field of a status packet for errors other than hardware errors.
+fn check_motor_error(raw: u8) -> Result<(), crate::MotorError> {
+	if raw & !error_bit::HARDWARE == 0 {
+		Ok(())
+	} else {
+		Err(crate::MotorError { raw })
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::assert;
+
+	#[test]
+	fn test_calculate_checksum() {
+		// Example from the online manual: read the internal temperature of motor 1.
+		assert!(calculate_checksum(&[0x01, 0x04, 0x02, 0x2B, 0x01]) == 0xCC);
+		// Example from the online manual: status packet with the internal temperature.
+		assert!(calculate_checksum(&[0x01, 0x03, 0x00, 0x20]) == 0xDB);
+	}
+
+	#[test]
+	fn hardware_errors_are_not_motor_errors() {
+		assert!(let Ok(()) = check_motor_error(0));
+		assert!(let Ok(()) = check_motor_error(error_bit::OVERHEATING | error_bit::OVERLOAD));
+		assert!(let Err(crate::MotorError { raw: 0x48 }) = check_motor_error(error_bit::INSTRUCTION | error_bit::RANGE));
+	}
+}
@@ -0,0 +1,202 @@
+use super::instruction_id;
+use crate::bus::endian::{read_u16_le, read_u8_le};
+use crate::instructions::{packet_id, Ping};
+use crate::{Client, ReadError, Response, TransferError};
+
+/// A version of the Dynamixel communication protocol.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ProtocolVersion {
+	/// Dynamixel Protocol 1.0.
+	Protocol1,
+
+	/// Dynamixel Protocol 2.0.
+	Protocol2,
+}
+
+/// A response from a motor to a ping with a specific protocol version.
+///
+/// Returned by [`crate::Client::scan_all_protocols()`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProtocolPing {
+	/// The protocol version that the motor responded to.
+	pub protocol: ProtocolVersion,
+
+	/// The model and firmware version of the motor.
+	pub ping: Ping,
+}
+
+impl<SerialPort, Buffer> Client<SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Scan the bus for motors that speak either Protocol 1.0 or Protocol 2.0.
+	///
+	/// Each motor ID is probed with a Protocol 2.0 ping and a Protocol 1.0 ping.
+	/// The returned iterator yields a response for each ping that was answered,
+	/// so a Protocol 1.0 and a Protocol 2.0 motor with the same ID are both reported.
+	///
+	/// Protocol 1.0 motors do not report their model and firmware version in response to a ping.
+	/// Instead, they are read from address 0 (model number) and address 2 (firmware version) of the control table.
+	/// This matches the control table of the AX, RX, EX and MX series.
+	///
+	/// Since each ID is pinged individually, a full scan can take several seconds.
+	/// The bus is probed lazily while the iterator is advanced.
+	pub fn scan_all_protocols(&mut self) -> ScanAllProtocols<'_, SerialPort, Buffer> {
+		ScanAllProtocols {
+			client: self,
+			next_probe: 0,
+		}
+	}
+}
+
+/// A scan of all motor IDs with both protocol versions, that returns [`Response<ProtocolPing>`] when iterated.
+pub struct ScanAllProtocols<'a, SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	client: &'a mut Client<SerialPort, Buffer>,
+	next_probe: u16,
+}
+
+impl<SerialPort, Buffer> core::fmt::Debug for ScanAllProtocols<'_, SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort + core::fmt::Debug,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("ScanAllProtocols")
+			.field("serial_port", self.client.serial_port())
+			.field("next_probe", &self.next_probe)
+			.finish()
+	}
+}
+
+impl<SerialPort, Buffer> ScanAllProtocols<'_, SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Probe motor IDs until a motor replies.
+	///
+	/// Returns `None` when all motor IDs have been probed.
+	pub fn scan_next(&mut self) -> Option<Result<Response<ProtocolPing>, TransferError<SerialPort::Error>>> {
+		// Each motor ID is probed twice: first with Protocol 2.0 and then with Protocol 1.0.
+		while self.next_probe < 2 * u16::from(packet_id::BROADCAST) {
+			let motor_id = (self.next_probe / 2) as u8;
+			let protocol = if self.next_probe % 2 == 0 {
+				ProtocolVersion::Protocol2
+			} else {
+				ProtocolVersion::Protocol1
+			};
+			self.next_probe += 1;
+
+			match self.probe(motor_id, protocol) {
+				Ok(response) => return Some(Ok(response)),
+				Err(e) if e.is_timeout() => {
+					trace!("No {:?} ping response from motor {}.", protocol, motor_id);
+				},
+				Err(e) => return Some(Err(e)),
+			}
+		}
+		None
+	}
+
+	fn probe(&mut self, motor_id: u8, protocol: ProtocolVersion) -> Result<Response<ProtocolPing>, TransferError<SerialPort::Error>> {
+		let response = match protocol {
+			ProtocolVersion::Protocol2 => self.client.ping(motor_id)?,
+			ProtocolVersion::Protocol1 => self.probe_protocol1(motor_id)?,
+		};
+		Ok(Response {
+			motor_id: response.motor_id,
+			alert: response.alert,
+			data: ProtocolPing {
+				protocol,
+				ping: response.data,
+			},
+		})
+	}
+
+	fn probe_protocol1(&mut self, motor_id: u8) -> Result<Response<Ping>, TransferError<SerialPort::Error>> {
+		let bus = &mut self.client.bus;
+		bus.transfer_protocol1_single(motor_id, instruction_id::PING, 0, 0, |_| Ok(()))?;
+		let response = bus.transfer_protocol1_single(motor_id, instruction_id::READ, 2, 3, |buffer| {
+			buffer[0] = 0;
+			buffer[1] = 3;
+			Ok(())
+		})?;
+		let parameters = response.parameters();
+		crate::InvalidParameterCount::check(parameters.len(), 3).map_err(ReadError::from)?;
+		Ok(Response {
+			motor_id: response.packet_id(),
+			alert: response.alert(),
+			data: Ping {
+				model: read_u16_le(&parameters[0..]),
+				firmware: read_u8_le(&parameters[2..]),
+			},
+		})
+	}
+}
+
+impl<SerialPort, Buffer> Iterator for ScanAllProtocols<'_, SerialPort, Buffer>
+where
+	SerialPort: crate::SerialPort,
+	Buffer: AsRef<[u8]> + AsMut<[u8]>,
+{
+	type Item = Result<Response<ProtocolPing>, TransferError<SerialPort::Error>>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.scan_next()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::serial_port::reply::ResponderSerial;
+	use assert2::{assert, let_assert};
+
+	/// Reply like a bus with a Protocol 2.0 motor with ID 1, and Protocol 1.0 motors with ID 1 and 3.
+	fn mixed_bus(instruction: &[u8]) -> Vec<u8> {
+		if instruction.starts_with(&[0xFF, 0xFF, 0xFD, 0x00]) {
+			if instruction[4] != 1 {
+				return Vec::new();
+			}
+			// Ping response of an XM430-W350 with firmware 45.
+			let mut packet = vec![0xFF, 0xFF, 0xFD, 0x00, 1, 7, 0, crate::instructions::instruction_id::STATUS, 0, 0xFC, 0x03, 45];
+			let checksum = crate::checksum::calculate_checksum(0, &packet);
+			packet.extend_from_slice(&checksum.to_le_bytes());
+			packet
+		} else {
+			let motor_id = instruction[2];
+			if motor_id != 1 && motor_id != 3 {
+				return Vec::new();
+			}
+			let parameters: &[u8] = match instruction[4] {
+				instruction_id::PING => &[],
+				// Model number and firmware of an AX-12 with firmware 24.
+				instruction_id::READ => &[12, 0, 24],
+				_ => unreachable!(),
+			};
+			let mut packet = vec![0xFF, 0xFF, motor_id, parameters.len() as u8 + 2, 0];
+			packet.extend_from_slice(parameters);
+			packet.push(super::super::calculate_checksum(&packet[2..]));
+			packet
+		}
+	}
+
+	#[test]
+	fn scan_all_protocols_reports_both_protocols() {
+		let serial_port = ResponderSerial { written: Vec::new(), response: Vec::new(), respond: mixed_bus };
+		let_assert!(Ok(mut client) = Client::new(serial_port));
+		let responses: Vec<_> = client.scan_all_protocols().collect();
+		let_assert!([Ok(first), Ok(second), Ok(third)] = responses.as_slice());
+		assert!(first.motor_id == 1);
+		assert!(first.data == ProtocolPing { protocol: ProtocolVersion::Protocol2, ping: Ping { model: 1020, firmware: 45 } });
+		assert!(second.motor_id == 1);
+		assert!(second.data == ProtocolPing { protocol: ProtocolVersion::Protocol1, ping: Ping { model: 12, firmware: 24 } });
+		assert!(third.motor_id == 3);
+		assert!(third.data.protocol == ProtocolVersion::Protocol1);
+	}
+}
@@ -0,0 +1,239 @@
+use core::time::Duration;
+
+use crate::ReadError;
+
+/// Policy for retrying idempotent instructions.
+///
+/// A retry policy can be set on a [`Client`][crate::Client] with [`Client::set_retry_policy()`][crate::Client::set_retry_policy].
+/// It only applies to instructions that can safely be repeated:
+/// [`Client::read()`][crate::Client::read], [`Client::write()`][crate::Client::write], [`Client::ping()`][crate::Client::ping] and their variants.
+/// Additionally, [`Client::sync_read()`][crate::Client::sync_read] re-issues the instruction for motors that did not respond properly.
+///
+/// Only the read part of a transfer is retried: if writing the instruction fails, the error is returned immediately.
+/// When a transfer has been attempted more than once,
+/// the final error is reported as [`TransferError::RetriesFailed`][crate::TransferError::RetriesFailed] with the number of attempts.
+///
+/// The default policy is [`RetryPolicy::NEVER`], which makes a single attempt.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RetryPolicy {
+	/// The maximum number of attempts, including the first one.
+	///
+	/// A value of 0 is treated the same as 1.
+	pub max_attempts: u32,
+
+	/// Retry when the motor did not respond before the timeout expired.
+	pub retry_timeout: bool,
+
+	/// Retry when the response was not a valid message, for example because it has an invalid checksum.
+	pub retry_invalid_message: bool,
+
+	/// Retry when the motor reported an error instead of a valid response.
+	pub retry_motor_error: bool,
+
+	/// The time to wait before each retry.
+	///
+	/// Any data received from the bus during this time is discarded.
+	pub backoff: Duration,
+}
+
+impl RetryPolicy {
+	/// A policy that never retries an instruction.
+	pub const NEVER: Self = Self {
+		max_attempts: 1,
+		retry_timeout: false,
+		retry_invalid_message: false,
+		retry_motor_error: false,
+		backoff: Duration::ZERO,
+	};
+
+	/// Create a policy that retries timeouts and invalid messages, up to a total of `max_attempts` attempts.
+	///
+	/// Motor errors are not retried, and there is no backoff between attempts.
+	pub const fn new(max_attempts: u32) -> Self {
+		Self {
+			max_attempts,
+			retry_timeout: true,
+			retry_invalid_message: true,
+			retry_motor_error: false,
+			backoff: Duration::ZERO,
+		}
+	}
+
+	/// Set the time to wait before each retry.
+	pub const fn with_backoff(self, backoff: Duration) -> Self {
+		Self { backoff, ..self }
+	}
+
+	/// Set whether errors reported by the motor should be retried.
+	pub const fn with_retry_motor_error(self, retry_motor_error: bool) -> Self {
+		Self { retry_motor_error, ..self }
+	}
+
+	/// Check if another attempt should be made after `attempts` attempts failed with the given error.
+	pub(crate) fn should_retry<E>(&self, attempts: u32, error: &ReadError<E>) -> bool {
+		if attempts >= self.max_attempts {
+			return false;
+		}
+		match error {
+			ReadError::Timeout { .. } => self.retry_timeout,
+			// Reading the same value again will not make it valid.
+			ReadError::InvalidMessage(crate::InvalidMessage::InvalidValue(_)) => false,
+			ReadError::InvalidMessage(_) => self.retry_invalid_message,
+			ReadError::MotorError(_) => self.retry_motor_error,
+			ReadError::BufferFull(_) | ReadError::Io(_) => false,
+		}
+	}
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self::NEVER
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::checksum::calculate_checksum;
+	use crate::serial_port::reply::ResponderSerial;
+	use crate::{Client, ClientError, Response, TransferError};
+	use assert2::{assert, let_assert};
+	use std::collections::VecDeque;
+
+	/// Build a status packet.
+	fn status(motor_id: u8, error: u8, parameters: &[u8]) -> Vec<u8> {
+		let mut packet = vec![0xFF, 0xFF, 0xFD, 0x00, motor_id];
+		packet.extend_from_slice(&(parameters.len() as u16 + 4).to_le_bytes());
+		packet.extend_from_slice(&[crate::instructions::instruction_id::STATUS, error]);
+		packet.extend_from_slice(parameters);
+		let checksum = calculate_checksum(0, &packet);
+		packet.extend_from_slice(&checksum.to_le_bytes());
+		packet
+	}
+
+	/// Build a status packet with an invalid checksum.
+	fn corrupt_status(motor_id: u8, parameters: &[u8]) -> Vec<u8> {
+		let mut packet = status(motor_id, 0, parameters);
+		let last = packet.len() - 1;
+		packet[last] ^= 0xFF;
+		packet
+	}
+
+	/// Create a client that receives the next scripted response for each instruction it writes.
+	fn make_client(responses: Vec<Vec<u8>>, retry_policy: RetryPolicy) -> Client<ResponderSerial<impl FnMut(&[u8]) -> Vec<u8>>> {
+		let mut responses = VecDeque::from(responses);
+		let serial_port = ResponderSerial {
+			written: Vec::new(),
+			response: Vec::new(),
+			respond: move |_: &[u8]| responses.pop_front().unwrap_or_default(),
+		};
+		let_assert!(Ok(mut client) = Client::new(serial_port));
+		client.set_retry_policy(retry_policy);
+		client
+	}
+
+	/// Get the number of instructions written to the serial port.
+	fn instruction_count<F>(client: &Client<ResponderSerial<F>>) -> usize
+	where
+		F: FnMut(&[u8]) -> Vec<u8>,
+	{
+		client.serial_port().written.windows(4).filter(|window| window == &[0xFF, 0xFF, 0xFD, 0x00]).count()
+	}
+
+	#[test]
+	fn read_is_retried_after_invalid_message() {
+		let mut client = make_client(vec![corrupt_status(1, &[0x34, 0x12]), status(1, 0, &[0x34, 0x12])], RetryPolicy::new(3));
+		let_assert!(Ok(response) = client.read::<u16>(1, 132));
+		assert!(response == Response { motor_id: 1, alert: false, data: 0x1234 });
+		assert!(instruction_count(&client) == 2);
+	}
+
+	#[test]
+	fn ping_reports_number_of_attempts() {
+		let mut client = make_client(Vec::new(), RetryPolicy::new(3));
+		let_assert!(Err(ClientError { error: TransferError::RetriesFailed { attempts, last_error }, .. }) = client.ping(1));
+		assert!(attempts == 3);
+		let_assert!(ReadError::Timeout { motor_id: Some(1), expected_bytes: 14, .. } = last_error);
+		assert!(instruction_count(&client) == 3);
+	}
+
+	#[test]
+	fn write_is_not_retried_without_policy() {
+		let mut client = make_client(vec![corrupt_status(1, &[]), status(1, 0, &[])], RetryPolicy::NEVER);
+		let_assert!(Err(ClientError { error: TransferError::ReadError(ReadError::InvalidMessage(_)), .. }) = client.write(1, 64, &1u8));
+		assert!(instruction_count(&client) == 1);
+	}
+
+	#[test]
+	fn motor_errors_are_only_retried_if_enabled() {
+		let mut client = make_client(vec![status(1, 0x04, &[]), status(1, 0, &[])], RetryPolicy::new(2));
+		let_assert!(Err(ClientError { error: TransferError::ReadError(ReadError::MotorError(_)), .. }) = client.write(1, 64, &1u8));
+
+		let mut client = make_client(vec![status(1, 0x04, &[]), status(1, 0, &[])], RetryPolicy::new(2).with_retry_motor_error(true));
+		let_assert!(Ok(_) = client.write(1, 64, &1u8));
+		assert!(instruction_count(&client) == 2);
+	}
+
+	#[test]
+	fn sync_read_is_reissued_for_missing_motors() {
+		let mut client = make_client(
+			vec![
+				[status(1, 0, &[1, 0]), corrupt_status(2, &[2, 0]), status(3, 0, &[3, 0])].concat(),
+				status(2, 0, &[2, 0]),
+			],
+			RetryPolicy::new(2),
+		);
+		{
+			let_assert!(Ok(mut responses) = client.sync_read::<u16>(&[1, 2, 3], 132));
+			assert!(responses.remaining() == 3);
+			let_assert!(Some(Ok(Response { motor_id: 1, data: 1, .. })) = responses.next());
+			let_assert!(Some(Ok(Response { motor_id: 3, data: 3, .. })) = responses.next());
+			assert!(responses.remaining() == 1);
+			assert!(responses.attempts() == 1);
+			let_assert!(Some(Ok(Response { motor_id: 2, data: 2, .. })) = responses.next());
+			assert!(responses.attempts() == 2);
+			assert!(let None = responses.next());
+		}
+
+		let written = &client.serial_port().written;
+		assert!(instruction_count(&client) == 2);
+		// The second instruction only contains the missing motor.
+		let second = &written[written.len() - 15..];
+		assert!(second[4] == crate::instructions::packet_id::BROADCAST);
+		assert!(second[7] == crate::instructions::instruction_id::SYNC_READ);
+		assert!(second[8..13] == [132, 0, 2, 0, 2]);
+	}
+
+	#[test]
+	fn sync_read_reports_last_error_after_max_attempts() {
+		let mut client = make_client(vec![status(1, 0, &[1, 0])], RetryPolicy::new(3));
+		let_assert!(Ok(mut responses) = client.sync_read::<u16>(&[1, 2], 132));
+		let_assert!(Some(Ok(Response { motor_id: 1, .. })) = responses.next());
+		let_assert!(Some(Err(ClientError { error: TransferError::ReadError(ReadError::Timeout { motor_id: Some(2), .. }), context: Some(context) })) = responses.next());
+		assert!(context.instruction_id == crate::instructions::instruction_id::SYNC_READ);
+		assert!(context.motor_id == 2);
+		assert!(context.address == Some(132));
+		assert!(responses.attempts() == 3);
+		assert!(let None = responses.next());
+	}
+
+	#[test]
+	fn should_retry_respects_max_attempts() {
+		let policy = RetryPolicy::new(3);
+		let error = ReadError::<()>::InvalidMessage(crate::InvalidPacketId { actual: 1, expected: Some(2) }.into());
+		assert!(policy.should_retry(1, &error));
+		assert!(policy.should_retry(2, &error));
+		assert!(!policy.should_retry(3, &error));
+		assert!(!RetryPolicy::NEVER.should_retry(1, &error));
+	}
+
+	#[test]
+	fn should_retry_checks_error_kind() {
+		let policy = RetryPolicy::new(2);
+		let timeout = ReadError::<()>::Timeout { motor_id: Some(1), expected_bytes: 11, waited: Duration::from_millis(1) };
+		assert!(policy.should_retry(1, &timeout));
+		assert!(!policy.should_retry(1, &ReadError::Io(())));
+		assert!(!policy.should_retry(1, &ReadError::<()>::MotorError(crate::MotorError { raw: 1 })));
+		assert!(policy.with_retry_motor_error(true).should_retry(1, &ReadError::<()>::MotorError(crate::MotorError { raw: 1 })));
+	}
+}
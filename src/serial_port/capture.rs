@@ -0,0 +1,286 @@
+//! [`SerialPort`][crate::SerialPort] wrapper that captures all traffic to a pcapng file.
+
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use super::pcapng::{self, Direction};
+use crate::bus::{find_header, HEADER_SIZE};
+
+/// A [`SerialPort`][crate::SerialPort] wrapper that records all data written and read into a pcapng file.
+///
+/// Each DYNAMIXEL packet becomes one frame in the capture, found with the same framing logic as the bus uses to read packets.
+/// Data that is not part of a packet is captured as separate frames, so no data is lost.
+/// The direction of each frame is recorded as inbound (read) or outbound (written),
+/// and the timestamp of a frame is the time when its first byte was written or read.
+///
+/// The frames use the `LINKTYPE_USER0` link type.
+///
+/// Errors while writing the capture do not interrupt the communication with the motors.
+/// Instead, the capture is stopped and the error is returned by [`Self::finish()`].
+/// Call [`Self::finish()`] when you are done, or the data of an incomplete packet at the end of the capture may be lost.
+///
+/// This struct is only available if the `std` feature is enabled.
+pub struct CaptureSerialPort<P, W: Write = std::io::BufWriter<std::fs::File>> {
+	/// The wrapped serial port.
+	serial_port: P,
+
+	/// The writer for the capture, or `None` if it failed.
+	writer: Option<W>,
+
+	/// The first error that occurred while writing the capture.
+	error: Option<std::io::Error>,
+
+	/// Data read from the serial port that has not been captured as a frame yet.
+	inbound: Framer,
+
+	/// Data written to the serial port that has not been captured as a frame yet.
+	outbound: Framer,
+}
+
+/// Buffer that splits captured data into frames.
+#[derive(Debug, Default)]
+struct Framer {
+	/// The data that has not been captured as a frame yet.
+	buffer: Vec<u8>,
+
+	/// The time when the first byte in the buffer was seen.
+	timestamp: Option<SystemTime>,
+}
+
+impl<P> CaptureSerialPort<P> {
+	/// Wrap a serial port to capture all traffic into a new pcapng file.
+	///
+	/// If the file already exists, it is truncated.
+	pub fn create(serial_port: P, path: impl AsRef<Path>) -> std::io::Result<Self> {
+		let file = std::fs::File::create(path)?;
+		Self::new(serial_port, std::io::BufWriter::new(file))
+	}
+}
+
+impl<P, W: Write> CaptureSerialPort<P, W> {
+	/// Wrap a serial port to capture all traffic into a writer.
+	///
+	/// The pcapng section header and interface description are written immediately.
+	pub fn new(serial_port: P, mut writer: W) -> std::io::Result<Self> {
+		pcapng::write_header(&mut writer)?;
+		Ok(Self {
+			serial_port,
+			writer: Some(writer),
+			error: None,
+			inbound: Framer::default(),
+			outbound: Framer::default(),
+		})
+	}
+
+	/// Get a reference to the wrapped serial port.
+	pub fn serial_port(&self) -> &P {
+		&self.serial_port
+	}
+
+	/// Get a mutable reference to the wrapped serial port.
+	///
+	/// Data written to or read from the serial port directly is not captured.
+	pub fn serial_port_mut(&mut self) -> &mut P {
+		&mut self.serial_port
+	}
+
+	/// Finish the capture and get back the serial port and the writer.
+	///
+	/// Incomplete packets are captured as they are, and the writer is flushed.
+	/// If writing the capture failed at any point, the first error is returned.
+	pub fn finish(mut self) -> std::io::Result<(P, W)> {
+		self.flush_frames();
+		if let Some(error) = self.error.take() {
+			return Err(error);
+		}
+		let mut writer = self.writer.take().expect("writer can only be missing after an error");
+		writer.flush()?;
+		Ok((self.serial_port, writer))
+	}
+
+	/// Record data that was read from or written to the serial port.
+	fn record(&mut self, direction: Direction, data: &[u8]) {
+		if data.is_empty() || self.writer.is_none() {
+			return;
+		}
+
+		// Keep the frames in chronological order: data in the other direction can not be part of the same packet anymore.
+		match direction {
+			Direction::Inbound => self.flush_direction(Direction::Outbound),
+			Direction::Outbound => self.flush_direction(Direction::Inbound),
+		}
+
+		let now = SystemTime::now();
+		let framer = self.framer(direction);
+		framer.timestamp.get_or_insert(now);
+		framer.buffer.extend_from_slice(data);
+
+		while let Some(frame_len) = next_frame_len(&self.framer(direction).buffer) {
+			let framer = self.framer(direction);
+			let frame: Vec<u8> = framer.buffer.drain(..frame_len).collect();
+			let timestamp = framer.timestamp.unwrap_or(now);
+			framer.timestamp = if framer.buffer.is_empty() { None } else { Some(now) };
+			self.write_frame(direction, timestamp, &frame);
+		}
+	}
+
+	/// Capture all remaining data as frames, even if they do not form complete packets.
+	fn flush_frames(&mut self) {
+		self.flush_direction(Direction::Outbound);
+		self.flush_direction(Direction::Inbound);
+	}
+
+	/// Capture the remaining data of one direction as a frame, even if it does not form a complete packet.
+	fn flush_direction(&mut self, direction: Direction) {
+		let framer = self.framer(direction);
+		if framer.buffer.is_empty() {
+			return;
+		}
+		let frame = core::mem::take(&mut framer.buffer);
+		let timestamp = framer.timestamp.take().unwrap_or_else(SystemTime::now);
+		self.write_frame(direction, timestamp, &frame);
+	}
+
+	/// Get the frame buffer for a direction.
+	fn framer(&mut self, direction: Direction) -> &mut Framer {
+		match direction {
+			Direction::Inbound => &mut self.inbound,
+			Direction::Outbound => &mut self.outbound,
+		}
+	}
+
+	/// Write a frame to the capture, or stop capturing if that fails.
+	fn write_frame(&mut self, direction: Direction, timestamp: SystemTime, data: &[u8]) {
+		let Some(writer) = &mut self.writer else {
+			return;
+		};
+		if let Err(e) = pcapng::write_frame(writer, direction, timestamp, data) {
+			warn!("failed to write packet capture, stopping capture: {}", e);
+			self.writer = None;
+			self.error = Some(e);
+			self.inbound = Framer::default();
+			self.outbound = Framer::default();
+		}
+	}
+}
+
+impl<P: core::fmt::Debug, W: Write> core::fmt::Debug for CaptureSerialPort<P, W> {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		f.debug_struct("CaptureSerialPort")
+			.field("serial_port", &self.serial_port)
+			.field("capturing", &self.writer.is_some())
+			.finish_non_exhaustive()
+	}
+}
+
+impl<P, W> crate::SerialPort for CaptureSerialPort<P, W>
+where
+	P: crate::SerialPort,
+	W: Write,
+{
+	type Error = P::Error;
+
+	type Instant = P::Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		self.serial_port.baud_rate()
+	}
+
+	fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+		self.serial_port.set_baud_rate(baud_rate)
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		self.serial_port.discard_input_buffer()
+	}
+
+	fn read(&mut self, buffer: &mut [u8], deadline: &Self::Instant) -> Result<usize, Self::Error> {
+		let read = self.serial_port.read(buffer, deadline)?;
+		self.record(Direction::Inbound, &buffer[..read]);
+		Ok(read)
+	}
+
+	fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+		self.serial_port.write_all(buffer)?;
+		self.record(Direction::Outbound, buffer);
+		Ok(())
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		self.serial_port.make_deadline(timeout)
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		P::is_timeout_error(error)
+	}
+
+	fn now(&self) -> Option<Self::Instant> {
+		self.serial_port.now()
+	}
+
+	fn elapsed_since(&self, instant: &Self::Instant) -> Option<Duration> {
+		self.serial_port.elapsed_since(instant)
+	}
+}
+
+/// Get the length of the first complete frame in the buffer, if there is one.
+///
+/// A frame is either a complete packet, or the garbage data before the start of a packet.
+fn next_frame_len(buffer: &[u8]) -> Option<usize> {
+	let garbage_len = find_header(buffer);
+	if garbage_len > 0 {
+		return Some(garbage_len);
+	}
+	if buffer.len() < HEADER_SIZE {
+		return None;
+	}
+	let packet_len = HEADER_SIZE + usize::from(crate::bus::endian::read_u16_le(&buffer[5..]));
+	if buffer.len() >= packet_len {
+		Some(packet_len)
+	} else {
+		None
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::serial_port::reply::ReplySerial;
+	use crate::Client;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn capture_splits_packets() {
+		// Garbage, the ping response of motor 1 from the protocol documentation, and an incomplete packet.
+		let response = vec![
+			0x00, 0x01,
+			0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x55, 0x00, 0x06, 0x04, 0x26, 0x65, 0x5D,
+			0xFF, 0xFF, 0xFD, 0x00, 0x02,
+		];
+		let serial_port = ReplySerial { written: Vec::new(), response };
+		let_assert!(Ok(capture) = CaptureSerialPort::new(serial_port, Vec::new()));
+		let_assert!(Ok(mut client) = Client::new(capture));
+		let_assert!(Ok(_) = client.ping(1));
+
+		let_assert!(Ok((_serial_port, capture)) = client.into_serial_port().finish());
+		let_assert!(Ok(frames) = pcapng::read_frames(&mut capture.as_slice()));
+		assert!(frames.len() == 4);
+
+		// The ping instruction for motor 1 from the protocol documentation.
+		assert!(frames[0] == (Direction::Outbound, vec![0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x03, 0x00, 0x01, 0x19, 0x4E]));
+		assert!(frames[1] == (Direction::Inbound, vec![0x00, 0x01]));
+		assert!(frames[2].0 == Direction::Inbound);
+		assert!(frames[2].1.len() == 14);
+		assert!(frames[3] == (Direction::Inbound, vec![0xFF, 0xFF, 0xFD, 0x00, 0x02]));
+	}
+
+	#[test]
+	fn next_frame_len_waits_for_complete_packet() {
+		assert!(next_frame_len(&[]) == None);
+		assert!(next_frame_len(&[0xFF, 0xFF]) == None);
+		assert!(next_frame_len(&[0x12, 0xFF, 0xFF]) == Some(1));
+		assert!(next_frame_len(&[0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x03, 0x00, 0x01, 0x19]) == None);
+		assert!(next_frame_len(&[0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x03, 0x00, 0x01, 0x19, 0x4E, 0xFF]) == Some(10));
+	}
+}
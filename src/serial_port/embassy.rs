@@ -0,0 +1,113 @@
+//! Async serial port using the `embedded-io-async` traits and `embassy-time` deadlines.
+
+use core::time::Duration;
+use embassy_time::Instant;
+use embedded_hal::digital::OutputPin;
+use embedded_io_async::{Read, Write};
+
+use super::embedded_io::{EmbeddedIoError, NoDirectionPin};
+
+/// The error type of an [`EmbassySerialPort`].
+pub type EmbassySerialPortError<Io, DirectionPin> =
+	EmbeddedIoError<<Io as embedded_io_async::ErrorType>::Error, <DirectionPin as embedded_hal::digital::ErrorType>::Error>;
+
+/// A serial port implementing [`embedded_io_async::Read`] and [`embedded_io_async::Write`], for use with [`crate::AsyncDevice`].
+///
+/// Read deadlines are implemented with [`embassy_time::with_deadline()`],
+/// so other tasks can run while waiting for data.
+///
+/// For half-duplex transceivers (such as an RS-485 transceiver) a direction pin can be added with [`Self::with_direction_pin()`].
+/// The pin is set high while transmitting and low while receiving.
+/// It is set low again after [`Write::flush()`] returns, so the flush implementation must wait until the last byte is fully transmitted.
+///
+/// The `embedded-io-async` traits can not change the baud rate of a serial port.
+/// The peripheral must be configured by the user, and the configured baud rate must be passed to [`Self::new()`].
+#[derive(Debug)]
+pub struct EmbassySerialPort<Io, DirectionPin = NoDirectionPin> {
+	/// The wrapped serial port.
+	io: Io,
+
+	/// The pin to select the direction of a half-duplex transceiver.
+	direction_pin: DirectionPin,
+
+	/// The baud rate that the serial port is configured for.
+	baud_rate: u32,
+}
+
+impl<Io> EmbassySerialPort<Io> {
+	/// Wrap a serial port that is configured with the given baud rate.
+	///
+	/// The serial port must be configured to use 8 bits characters, 1 stop bit, no parity and no flow control.
+	pub fn new(io: Io, baud_rate: u32) -> Self {
+		Self {
+			io,
+			direction_pin: NoDirectionPin,
+			baud_rate,
+		}
+	}
+
+	/// Add a direction pin for a half-duplex transceiver.
+	///
+	/// The pin is set high while transmitting and low while receiving.
+	pub fn with_direction_pin<DirectionPin: OutputPin>(self, direction_pin: DirectionPin) -> EmbassySerialPort<Io, DirectionPin> {
+		EmbassySerialPort {
+			io: self.io,
+			direction_pin,
+			baud_rate: self.baud_rate,
+		}
+	}
+}
+
+impl<Io, DirectionPin> EmbassySerialPort<Io, DirectionPin> {
+	/// Get a reference to the wrapped serial port.
+	pub fn get_ref(&self) -> &Io {
+		&self.io
+	}
+
+	/// Get a mutable reference to the wrapped serial port.
+	pub fn get_mut(&mut self) -> &mut Io {
+		&mut self.io
+	}
+
+	/// Consume the wrapper to get back the serial port and the direction pin.
+	pub fn into_parts(self) -> (Io, DirectionPin) {
+		(self.io, self.direction_pin)
+	}
+
+	/// Get the baud rate that the serial port is configured for.
+	pub fn baud_rate(&self) -> u32 {
+		self.baud_rate
+	}
+
+	/// Make a deadline to expire after the given timeout.
+	pub fn make_deadline(&self, timeout: Duration) -> Instant {
+		Instant::now() + embassy_time::Duration::from_micros(timeout.as_micros().try_into().unwrap_or(u64::MAX))
+	}
+}
+
+impl<Io, DirectionPin> EmbassySerialPort<Io, DirectionPin>
+where
+	Io: Read + Write,
+	DirectionPin: OutputPin,
+{
+	/// Read available bytes, waiting until at least one byte is available or the deadline expires.
+	pub async fn read(&mut self, buffer: &mut [u8], deadline: Instant) -> Result<usize, EmbassySerialPortError<Io, DirectionPin>> {
+		match embassy_time::with_deadline(deadline, self.io.read(buffer)).await {
+			Ok(result) => result.map_err(EmbeddedIoError::Io),
+			Err(embassy_time::TimeoutError) => Err(EmbeddedIoError::Timeout),
+		}
+	}
+
+	/// Write all bytes in the buffer to the serial port.
+	pub async fn write_all(&mut self, buffer: &[u8]) -> Result<(), EmbassySerialPortError<Io, DirectionPin>> {
+		self.direction_pin.set_high().map_err(EmbeddedIoError::DirectionPin)?;
+		let result = match self.io.write_all(buffer).await {
+			Ok(()) => self.io.flush().await,
+			Err(e) => Err(e),
+		};
+		// Always switch back to receiving, even if the write failed.
+		let pin_result = self.direction_pin.set_low();
+		result.map_err(EmbeddedIoError::Io)?;
+		pin_result.map_err(EmbeddedIoError::DirectionPin)
+	}
+}
@@ -0,0 +1,349 @@
+//! Trait implementation for any serial port implementing the `embedded-io` traits.
+
+use core::convert::Infallible;
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use core::time::Duration;
+use embedded_hal::digital::OutputPin;
+use embedded_io::{Read, ReadReady, Write};
+
+/// A monotonic clock used by [`EmbeddedIoSerialPort`] to implement read deadlines.
+///
+/// The `embedded-io` traits have no notion of time, so the clock must be supplied by the user.
+/// It is typically implemented on top of a hardware timer or the system tick of an RTOS.
+pub trait Clock {
+	/// A point in time as measured by the clock.
+	type Instant: Copy + PartialOrd;
+
+	/// Get the current time.
+	fn now(&self) -> Self::Instant;
+
+	/// Get the point in time that lies `duration` after `instant`.
+	fn add(&self, instant: Self::Instant, duration: Duration) -> Self::Instant;
+}
+
+/// A placeholder for [`EmbeddedIoSerialPort`] without a direction pin.
+///
+/// Setting the pin is a no-op that can not fail.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoDirectionPin;
+
+impl embedded_hal::digital::ErrorType for NoDirectionPin {
+	type Error = Infallible;
+}
+
+impl OutputPin for NoDirectionPin {
+	fn set_low(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn set_high(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+}
+
+/// A serial port implementing [`embedded_io::Read`], [`embedded_io::ReadReady`] and [`embedded_io::Write`], usable as [`crate::SerialPort`].
+///
+/// The `embedded-io` traits do not support timeouts, so reads are implemented by polling [`ReadReady::read_ready()`]
+/// until data is available or the deadline given by the [`Clock`] expires.
+///
+/// For half-duplex transceivers (such as an RS-485 transceiver) a direction pin can be added with [`Self::with_direction_pin()`].
+/// The pin is set high while transmitting and low while receiving.
+/// It is set low again after [`Write::flush()`] returns, so the flush implementation must wait until the last byte is fully transmitted.
+///
+/// The `embedded-io` traits can not change the baud rate of a serial port.
+/// The peripheral must be configured by the user, and the configured baud rate must be passed to [`Self::new()`].
+#[derive(Debug)]
+pub struct EmbeddedIoSerialPort<Io, C, DirectionPin = NoDirectionPin> {
+	/// The wrapped serial port.
+	io: Io,
+
+	/// The clock used for read deadlines.
+	clock: C,
+
+	/// The pin to select the direction of a half-duplex transceiver.
+	direction_pin: DirectionPin,
+
+	/// The baud rate that the serial port is configured for.
+	baud_rate: u32,
+}
+
+impl<Io, C> EmbeddedIoSerialPort<Io, C> {
+	/// Wrap a serial port that is configured with the given baud rate.
+	///
+	/// The serial port must be configured to use 8 bits characters, 1 stop bit, no parity and no flow control.
+	pub fn new(io: Io, clock: C, baud_rate: u32) -> Self {
+		Self {
+			io,
+			clock,
+			direction_pin: NoDirectionPin,
+			baud_rate,
+		}
+	}
+
+	/// Add a direction pin for a half-duplex transceiver.
+	///
+	/// The pin is set high while transmitting and low while receiving.
+	pub fn with_direction_pin<DirectionPin: OutputPin>(self, direction_pin: DirectionPin) -> EmbeddedIoSerialPort<Io, C, DirectionPin> {
+		EmbeddedIoSerialPort {
+			io: self.io,
+			clock: self.clock,
+			direction_pin,
+			baud_rate: self.baud_rate,
+		}
+	}
+}
+
+impl<Io, C, DirectionPin> EmbeddedIoSerialPort<Io, C, DirectionPin> {
+	/// Get a reference to the wrapped serial port.
+	pub fn get_ref(&self) -> &Io {
+		&self.io
+	}
+
+	/// Get a mutable reference to the wrapped serial port.
+	pub fn get_mut(&mut self) -> &mut Io {
+		&mut self.io
+	}
+
+	/// Get a reference to the clock.
+	pub fn clock(&self) -> &C {
+		&self.clock
+	}
+
+	/// Consume the wrapper to get back the serial port, the clock and the direction pin.
+	pub fn into_parts(self) -> (Io, C, DirectionPin) {
+		(self.io, self.clock, self.direction_pin)
+	}
+}
+
+/// An error from an [`EmbeddedIoSerialPort`].
+#[derive(Debug)]
+pub enum EmbeddedIoError<IoError, PinError> {
+	/// The serial port reported an error.
+	Io(IoError),
+
+	/// The direction pin reported an error.
+	DirectionPin(PinError),
+
+	/// No data was received before the deadline expired.
+	Timeout,
+
+	/// The baud rate can not be changed through the `embedded-io` traits.
+	UnsupportedBaudRate {
+		/// The baud rate the serial port is configured for.
+		current: u32,
+
+		/// The requested baud rate.
+		requested: u32,
+	},
+}
+
+impl<Io, C, DirectionPin> crate::SerialPort for EmbeddedIoSerialPort<Io, C, DirectionPin>
+where
+	Io: Read + ReadReady + Write,
+	C: Clock,
+	DirectionPin: OutputPin,
+{
+	type Error = EmbeddedIoError<Io::Error, DirectionPin::Error>;
+
+	type Instant = C::Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		Ok(self.baud_rate)
+	}
+
+	fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+		if baud_rate != self.baud_rate {
+			return Err(EmbeddedIoError::UnsupportedBaudRate {
+				current: self.baud_rate,
+				requested: baud_rate,
+			});
+		}
+		Ok(())
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		let mut buffer = [0; 32];
+		while self.io.read_ready().map_err(EmbeddedIoError::Io)? {
+			self.io.read(&mut buffer).map_err(EmbeddedIoError::Io)?;
+		}
+		Ok(())
+	}
+
+	fn read(&mut self, buffer: &mut [u8], deadline: &Self::Instant) -> Result<usize, Self::Error> {
+		loop {
+			if self.io.read_ready().map_err(EmbeddedIoError::Io)? {
+				return self.io.read(buffer).map_err(EmbeddedIoError::Io);
+			}
+			if self.clock.now() >= *deadline {
+				return Err(EmbeddedIoError::Timeout);
+			}
+		}
+	}
+
+	fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+		self.direction_pin.set_high().map_err(EmbeddedIoError::DirectionPin)?;
+		let result = self.io.write_all(buffer).and_then(|()| self.io.flush());
+		// Always switch back to receiving, even if the write failed.
+		let pin_result = self.direction_pin.set_low();
+		result.map_err(EmbeddedIoError::Io)?;
+		pin_result.map_err(EmbeddedIoError::DirectionPin)
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		self.clock.add(self.clock.now(), timeout)
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		matches!(error, EmbeddedIoError::Timeout)
+	}
+}
+
+impl<IoError: Debug, PinError: Debug> Display for EmbeddedIoError<IoError, PinError> {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::Io(e) => write!(f, "serial port error: {:?}", e),
+			Self::DirectionPin(e) => write!(f, "failed to set direction pin: {:?}", e),
+			Self::Timeout => write!(f, "timeout while waiting for data"),
+			Self::UnsupportedBaudRate { current, requested } => write!(
+				f,
+				"can not change baud rate from {} to {}: not supported by embedded-io serial ports",
+				current, requested
+			),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl<IoError: Debug, PinError: Debug> std::error::Error for EmbeddedIoError<IoError, PinError> {}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::SerialPort;
+	use assert2::{assert, let_assert};
+	use core::cell::Cell;
+
+	/// A clock that advances by one millisecond every time it is read.
+	#[derive(Default)]
+	struct TickClock {
+		now: Cell<u64>,
+	}
+
+	impl Clock for TickClock {
+		type Instant = u64;
+
+		fn now(&self) -> u64 {
+			let now = self.now.get();
+			self.now.set(now + 1);
+			now
+		}
+
+		fn add(&self, instant: u64, duration: Duration) -> u64 {
+			instant + duration.as_millis() as u64
+		}
+	}
+
+	/// A serial port with pre-loaded read data that records written data.
+	#[derive(Default)]
+	struct FakeIo {
+		read_data: Vec<u8>,
+		written: Vec<u8>,
+		flushed: bool,
+	}
+
+	impl embedded_io::ErrorType for FakeIo {
+		type Error = Infallible;
+	}
+
+	impl Read for FakeIo {
+		fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error> {
+			let count = buffer.len().min(self.read_data.len());
+			buffer[..count].copy_from_slice(&self.read_data[..count]);
+			self.read_data.drain(..count);
+			Ok(count)
+		}
+	}
+
+	impl ReadReady for FakeIo {
+		fn read_ready(&mut self) -> Result<bool, Self::Error> {
+			Ok(!self.read_data.is_empty())
+		}
+	}
+
+	impl Write for FakeIo {
+		fn write(&mut self, buffer: &[u8]) -> Result<usize, Self::Error> {
+			self.flushed = false;
+			self.written.extend_from_slice(buffer);
+			Ok(buffer.len())
+		}
+
+		fn flush(&mut self) -> Result<(), Self::Error> {
+			self.flushed = true;
+			Ok(())
+		}
+	}
+
+	/// A direction pin that records all state changes.
+	#[derive(Default)]
+	struct FakePin {
+		states: Vec<bool>,
+	}
+
+	impl embedded_hal::digital::ErrorType for FakePin {
+		type Error = Infallible;
+	}
+
+	impl OutputPin for FakePin {
+		fn set_low(&mut self) -> Result<(), Self::Error> {
+			self.states.push(false);
+			Ok(())
+		}
+
+		fn set_high(&mut self) -> Result<(), Self::Error> {
+			self.states.push(true);
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn read_times_out_at_deadline() {
+		let mut serial_port = EmbeddedIoSerialPort::new(FakeIo::default(), TickClock::default(), 57600);
+		let deadline = serial_port.make_deadline(Duration::from_millis(10));
+		let mut buffer = [0; 4];
+		let_assert!(Err(e) = serial_port.read(&mut buffer, &deadline));
+		assert!(EmbeddedIoSerialPort::<FakeIo, TickClock>::is_timeout_error(&e));
+		assert!(serial_port.clock().now.get() > deadline);
+	}
+
+	#[test]
+	fn read_returns_available_data() {
+		let io = FakeIo {
+			read_data: vec![1, 2, 3],
+			..Default::default()
+		};
+		let mut serial_port = EmbeddedIoSerialPort::new(io, TickClock::default(), 57600);
+		let deadline = serial_port.make_deadline(Duration::from_millis(10));
+		let mut buffer = [0; 4];
+		let_assert!(Ok(3) = serial_port.read(&mut buffer, &deadline));
+		assert!(buffer[..3] == [1, 2, 3]);
+	}
+
+	#[test]
+	fn write_toggles_direction_pin() {
+		let mut serial_port = EmbeddedIoSerialPort::new(FakeIo::default(), TickClock::default(), 57600)
+			.with_direction_pin(FakePin::default());
+		let_assert!(Ok(()) = serial_port.write_all(&[1, 2, 3]));
+		let (io, _clock, pin) = serial_port.into_parts();
+		assert!(io.written == [1, 2, 3]);
+		assert!(io.flushed);
+		assert!(pin.states == [true, false]);
+	}
+
+	#[test]
+	fn baud_rate_can_not_be_changed() {
+		let mut serial_port = EmbeddedIoSerialPort::new(FakeIo::default(), TickClock::default(), 57600);
+		assert!(let Ok(()) = serial_port.set_baud_rate(57600));
+		let_assert!(Err(EmbeddedIoError::UnsupportedBaudRate { current: 57600, requested: 115200 }) = serial_port.set_baud_rate(115200));
+		assert!(let Ok(57600) = serial_port.baud_rate());
+	}
+}
@@ -2,9 +2,33 @@
 
 use core::time::Duration;
 
+#[cfg(feature = "async")]
+use core::task::{Context, Poll};
+
 #[cfg(feature = "serial2")]
 pub mod serial2;
 
+#[cfg(feature = "serial2-tokio")]
+pub mod serial2_tokio;
+
+#[cfg(feature = "embedded-io")]
+pub mod embedded_io;
+
+#[cfg(feature = "embassy")]
+pub mod embassy;
+
+#[cfg(feature = "std")]
+pub mod capture;
+
+#[cfg(feature = "std")]
+pub mod replay;
+
+#[cfg(feature = "std")]
+mod pcapng;
+
+#[cfg(test)]
+pub(crate) mod reply;
+
 /// [`SerialPort`]s are used to communicate with the hardware by reading and writing data.
 ///
 /// The implementor of the trait must also configure the serial line to use 8 bits characters, 1 stop bit, no parity and no flow control.
@@ -35,4 +59,64 @@ pub trait SerialPort {
 
 	/// Check if an error indicates a timeout.
 	fn is_timeout_error(error: &Self::Error) -> bool;
+
+	/// Get the current time, if the serial port can measure it.
+	///
+	/// This is used together with [`Self::elapsed_since()`] to measure the time since an instruction was sent.
+	/// The default implementation returns `None`.
+	fn now(&self) -> Option<Self::Instant> {
+		None
+	}
+
+	/// Get the time that passed since the given instant, if the serial port can measure it.
+	///
+	/// The instant is obtained from [`Self::now()`].
+	/// This is used to report the time since an instruction was sent in a [`ErrorContext`][crate::ErrorContext].
+	/// The default implementation returns `None`.
+	fn elapsed_since(&self, instant: &Self::Instant) -> Option<Duration> {
+		let _ = instant;
+		None
+	}
+}
+
+/// [`AsyncSerialPort`]s are used by the [`AsyncClient`][crate::AsyncClient] to communicate with the hardware without blocking.
+///
+/// The implementor of the trait must also configure the serial line to use 8 bits characters, 1 stop bit, no parity and no flow control.
+///
+/// The I/O functions are poll based, so that they can be used from the [`Stream`][futures_core::Stream]s returned by the client.
+/// This trait is only available if the `async` feature is enabled.
+#[cfg(feature = "async")]
+pub trait AsyncSerialPort {
+	/// The error type returned by the serial port when reading, writing or setting the baud rate.
+	type Error;
+
+	/// A point in time that can be used as a deadline for a I/O operations.
+	type Instant: Copy + Unpin;
+
+	/// Get the current baud rate of the serial port.
+	fn baud_rate(&self) -> Result<u32, Self::Error>;
+
+	/// Set the baud rate of the serial port.
+	fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error>;
+
+	/// Discard the input buffer of the serial port. Maybe a no-op on some platforms.
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error>;
+
+	/// Attempt to read available bytes from the serial port.
+	///
+	/// If no data is available, this must return [`Poll::Pending`] and arrange for the task to be woken up
+	/// when data becomes available or when the deadline expires.
+	/// After the deadline expired, this must return an error for which [`Self::is_timeout_error()`] returns true.
+	fn poll_read(&mut self, cx: &mut Context<'_>, buffer: &mut [u8], deadline: &Self::Instant) -> Poll<Result<usize, Self::Error>>;
+
+	/// Attempt to write bytes from the buffer to the serial port.
+	///
+	/// Returns the number of bytes written, which must be non-zero for a non-empty buffer.
+	fn poll_write(&mut self, cx: &mut Context<'_>, buffer: &[u8]) -> Poll<Result<usize, Self::Error>>;
+
+	/// Make a deadline to expire after the given timeout.
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant;
+
+	/// Check if an error indicates a timeout.
+	fn is_timeout_error(error: &Self::Error) -> bool;
 }
@@ -0,0 +1,227 @@
+//! Minimal reading and writing of pcapng files, as used by [`CaptureSerialPort`][super::capture::CaptureSerialPort] and [`ReplaySerialPort`][super::replay::ReplaySerialPort].
+//!
+//! Only the subset of the format written by this module is supported:
+//! little endian sections with enhanced packet blocks that have an `epb_flags` option with the direction of the frame.
+
+use std::io::{Read, Write};
+use std::time::SystemTime;
+
+/// The link type of the captured frames.
+///
+/// There is no registered link type for DYNAMIXEL packets, so the first user defined link type is used (`LINKTYPE_USER0`).
+/// In Wireshark, a dissector can be assigned to it in the "DLT_USER" protocol preferences.
+const LINKTYPE_USER0: u16 = 147;
+
+/// Block type of a pcapng Section Header Block.
+const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+
+/// Block type of a pcapng Interface Description Block.
+const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+
+/// Block type of a pcapng Enhanced Packet Block.
+const ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+
+/// The byte order magic of a pcapng section header.
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+/// Option code of the `opt_endofopt` option.
+const OPT_END_OF_OPTIONS: u16 = 0;
+
+/// Option code of the `epb_flags` option of an Enhanced Packet Block.
+const EPB_FLAGS: u16 = 2;
+
+/// The direction of a captured frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Direction {
+	/// The data was read from the serial port.
+	Inbound,
+
+	/// The data was written to the serial port.
+	Outbound,
+}
+
+impl Direction {
+	/// Get the direction bits of the `epb_flags` option.
+	fn to_flags(self) -> u32 {
+		match self {
+			Self::Inbound => 0b01,
+			Self::Outbound => 0b10,
+		}
+	}
+
+	/// Get the direction from the `epb_flags` option.
+	fn from_flags(flags: u32) -> Option<Self> {
+		match flags & 0b11 {
+			0b01 => Some(Self::Inbound),
+			0b10 => Some(Self::Outbound),
+			_ => None,
+		}
+	}
+}
+
+/// Write the section header block and the interface description block of a pcapng file.
+pub(crate) fn write_header(writer: &mut impl Write) -> std::io::Result<()> {
+	// Section Header Block: byte order magic, version 1.0 and unknown section length.
+	let mut body = Vec::with_capacity(16);
+	body.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+	body.extend_from_slice(&1u16.to_le_bytes());
+	body.extend_from_slice(&0u16.to_le_bytes());
+	body.extend_from_slice(&(-1i64).to_le_bytes());
+	write_block(writer, SECTION_HEADER_BLOCK, &body)?;
+
+	// Interface Description Block: link type, reserved field and unlimited snap length.
+	// Without an `if_tsresol` option, timestamps are in microseconds.
+	let mut body = Vec::with_capacity(8);
+	body.extend_from_slice(&LINKTYPE_USER0.to_le_bytes());
+	body.extend_from_slice(&0u16.to_le_bytes());
+	body.extend_from_slice(&0u32.to_le_bytes());
+	write_block(writer, INTERFACE_DESCRIPTION_BLOCK, &body)
+}
+
+/// Write a captured frame as an enhanced packet block.
+pub(crate) fn write_frame(writer: &mut impl Write, direction: Direction, timestamp: SystemTime, data: &[u8]) -> std::io::Result<()> {
+	let micros = timestamp.duration_since(SystemTime::UNIX_EPOCH)
+		.unwrap_or_default()
+		.as_micros() as u64;
+
+	let mut body = Vec::with_capacity(20 + data.len() + 3 + 12);
+	body.extend_from_slice(&0u32.to_le_bytes()); // interface ID
+	body.extend_from_slice(&((micros >> 32) as u32).to_le_bytes());
+	body.extend_from_slice(&(micros as u32).to_le_bytes());
+	body.extend_from_slice(&(data.len() as u32).to_le_bytes()); // captured length
+	body.extend_from_slice(&(data.len() as u32).to_le_bytes()); // original length
+	body.extend_from_slice(data);
+	body.resize(body.len().next_multiple_of(4), 0);
+
+	// The `epb_flags` option holds the direction in the lowest two bits.
+	body.extend_from_slice(&EPB_FLAGS.to_le_bytes());
+	body.extend_from_slice(&4u16.to_le_bytes());
+	body.extend_from_slice(&direction.to_flags().to_le_bytes());
+	body.extend_from_slice(&OPT_END_OF_OPTIONS.to_le_bytes());
+	body.extend_from_slice(&0u16.to_le_bytes());
+	write_block(writer, ENHANCED_PACKET_BLOCK, &body)
+}
+
+/// Write a pcapng block with the given type and body.
+///
+/// The body must already be padded to a multiple of 4 bytes.
+fn write_block(writer: &mut impl Write, block_type: u32, body: &[u8]) -> std::io::Result<()> {
+	debug_assert!(body.len() % 4 == 0);
+	let total_len = (body.len() + 12) as u32;
+	writer.write_all(&block_type.to_le_bytes())?;
+	writer.write_all(&total_len.to_le_bytes())?;
+	writer.write_all(body)?;
+	writer.write_all(&total_len.to_le_bytes())
+}
+
+/// Read all frames from a pcapng file, in the order they appear in the file.
+///
+/// Blocks other than enhanced packet blocks are skipped.
+pub(crate) fn read_frames(reader: &mut impl Read) -> std::io::Result<Vec<(Direction, Vec<u8>)>> {
+	let mut data = Vec::new();
+	reader.read_to_end(&mut data)?;
+
+	let mut frames = Vec::new();
+	let mut remaining = data.as_slice();
+	let mut seen_section_header = false;
+	while !remaining.is_empty() {
+		if remaining.len() < 12 {
+			return Err(invalid_data("truncated block header"));
+		}
+		let block_type = read_u32(remaining, 0);
+		let total_len = read_u32(remaining, 4) as usize;
+		if total_len < 12 || total_len % 4 != 0 || total_len > remaining.len() {
+			return Err(invalid_data("invalid block length"));
+		}
+		if read_u32(remaining, total_len - 4) as usize != total_len {
+			return Err(invalid_data("mismatch between leading and trailing block length"));
+		}
+		let body = &remaining[8..total_len - 4];
+
+		match block_type {
+			SECTION_HEADER_BLOCK => {
+				if body.len() < 4 || read_u32(body, 0) != BYTE_ORDER_MAGIC {
+					return Err(invalid_data("only little endian pcapng files are supported"));
+				}
+				seen_section_header = true;
+			},
+			_ if !seen_section_header => return Err(invalid_data("missing section header block")),
+			ENHANCED_PACKET_BLOCK => frames.push(read_enhanced_packet(body)?),
+			_ => (),
+		}
+		remaining = &remaining[total_len..];
+	}
+	Ok(frames)
+}
+
+/// Parse the body of an enhanced packet block.
+fn read_enhanced_packet(body: &[u8]) -> std::io::Result<(Direction, Vec<u8>)> {
+	if body.len() < 20 {
+		return Err(invalid_data("truncated enhanced packet block"));
+	}
+	let captured_len = read_u32(body, 12) as usize;
+	let options_start = 20 + captured_len.next_multiple_of(4);
+	if options_start > body.len() {
+		return Err(invalid_data("truncated enhanced packet block"));
+	}
+	let data = body[20..][..captured_len].to_vec();
+
+	let mut options = &body[options_start..];
+	while options.len() >= 4 {
+		let code = u16::from_le_bytes([options[0], options[1]]);
+		let len = usize::from(u16::from_le_bytes([options[2], options[3]]));
+		if code == OPT_END_OF_OPTIONS {
+			break;
+		}
+		let value = options.get(4..4 + len).ok_or_else(|| invalid_data("truncated option"))?;
+		if code == EPB_FLAGS && len == 4 {
+			let flags = read_u32(value, 0);
+			let direction = Direction::from_flags(flags).ok_or_else(|| invalid_data("frame has no direction"))?;
+			return Ok((direction, data));
+		}
+		options = options.get(4 + len.next_multiple_of(4)..).unwrap_or_default();
+	}
+	Err(invalid_data("frame has no direction"))
+}
+
+/// Read a little endian u32 at the given offset.
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+	u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// Create an error for an invalid pcapng file.
+fn invalid_data(message: &str) -> std::io::Error {
+	std::io::Error::new(std::io::ErrorKind::InvalidData, format!("invalid pcapng file: {message}"))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn read_written_frames() {
+		let mut file = Vec::new();
+		let_assert!(Ok(()) = write_header(&mut file));
+		let_assert!(Ok(()) = write_frame(&mut file, Direction::Outbound, SystemTime::now(), &[1, 2, 3]));
+		let_assert!(Ok(()) = write_frame(&mut file, Direction::Inbound, SystemTime::now(), &[4, 5, 6, 7, 8]));
+		assert!(file[0..4] == SECTION_HEADER_BLOCK.to_le_bytes());
+
+		let_assert!(Ok(frames) = read_frames(&mut file.as_slice()));
+		assert!(frames == [
+			(Direction::Outbound, vec![1, 2, 3]),
+			(Direction::Inbound, vec![4, 5, 6, 7, 8]),
+		]);
+	}
+
+	#[test]
+	fn reject_invalid_files() {
+		let_assert!(Err(e) = read_frames(&mut [0u8; 4].as_slice()));
+		assert!(e.kind() == std::io::ErrorKind::InvalidData);
+
+		let mut file = Vec::new();
+		let_assert!(Ok(()) = write_frame(&mut file, Direction::Outbound, SystemTime::now(), &[1, 2, 3]));
+		let_assert!(Err(e) = read_frames(&mut file.as_slice()));
+		assert!(e.to_string() == "invalid pcapng file: missing section header block");
+	}
+}
@@ -0,0 +1,233 @@
+//! [`SerialPort`][crate::SerialPort] that replays a recorded session.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::pcapng::{self, Direction};
+
+/// A [`SerialPort`][crate::SerialPort] that replays a session recorded with [`CaptureSerialPort`][crate::CaptureSerialPort].
+///
+/// Data written to the serial port is compared against the recorded outbound data,
+/// and reads return the recorded inbound data.
+/// This allows you to run code that talks to motors as a deterministic regression test,
+/// without the motors being connected.
+///
+/// If a write does not match the recording, it fails with [`ReplayError::UnexpectedWrite`].
+/// A read fails with [`ReplayError::Timeout`] when the next recorded data is a write, or when the recording is exhausted.
+/// Discarding the input buffer drops all recorded inbound data up to the next recorded write.
+///
+/// Deadlines are ignored: reads never block, so a replay runs as fast as possible.
+///
+/// This struct is only available if the `std` feature is enabled.
+///
+/// # Example
+/// ```no_run
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use dynamixel2::{CaptureSerialPort, Client, ReplaySerialPort};
+///
+/// // Record a session against real motors.
+/// let serial_port = serial2::SerialPort::open("/dev/ttyUSB0", 57600)?;
+/// let mut client = Client::new(CaptureSerialPort::create(serial_port, "session.pcapng")?)?;
+/// client.ping(1)?;
+/// client.into_serial_port().finish()?;
+///
+/// // Replay the session later, without the motors.
+/// let mut client = Client::new(ReplaySerialPort::open("session.pcapng")?)?;
+/// client.ping(1)?;
+/// assert!(client.serial_port().is_finished());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ReplaySerialPort {
+	/// The recorded frames that have not been replayed yet.
+	frames: VecDeque<(Direction, Vec<u8>)>,
+
+	/// The baud rate reported by the serial port.
+	baud_rate: u32,
+}
+
+/// An error from a [`ReplaySerialPort`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ReplayError {
+	/// The recording has no inbound data for a read.
+	Timeout,
+
+	/// A write did not match the recording.
+	UnexpectedWrite {
+		/// The recorded data, up to the length of the write.
+		///
+		/// This is empty if the recording has no more data to be written.
+		expected: Vec<u8>,
+
+		/// The data that was written.
+		actual: Vec<u8>,
+	},
+}
+
+impl ReplaySerialPort {
+	/// The baud rate reported by a new replay serial port.
+	const DEFAULT_BAUD_RATE: u32 = 57600;
+
+	/// Load a recording from a pcapng file written by [`CaptureSerialPort`][crate::CaptureSerialPort].
+	pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+		let file = std::fs::File::open(path)?;
+		Self::from_reader(std::io::BufReader::new(file))
+	}
+
+	/// Load a recording in pcapng format from a reader.
+	pub fn from_reader(mut reader: impl std::io::Read) -> std::io::Result<Self> {
+		let frames = pcapng::read_frames(&mut reader)?;
+		Ok(Self {
+			frames: frames.into(),
+			baud_rate: Self::DEFAULT_BAUD_RATE,
+		})
+	}
+
+	/// Check if all recorded data has been replayed.
+	pub fn is_finished(&self) -> bool {
+		self.frames.is_empty()
+	}
+
+	/// Get the number of recorded frames that have not been replayed yet.
+	pub fn remaining_frames(&self) -> usize {
+		self.frames.len()
+	}
+}
+
+impl crate::SerialPort for ReplaySerialPort {
+	type Error = ReplayError;
+
+	type Instant = Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		Ok(self.baud_rate)
+	}
+
+	fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+		self.baud_rate = baud_rate;
+		Ok(())
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		while let Some((Direction::Inbound, _)) = self.frames.front() {
+			self.frames.pop_front();
+		}
+		Ok(())
+	}
+
+	fn read(&mut self, buffer: &mut [u8], _deadline: &Self::Instant) -> Result<usize, Self::Error> {
+		let Some((Direction::Inbound, data)) = self.frames.front_mut() else {
+			return Err(ReplayError::Timeout);
+		};
+		let len = data.len().min(buffer.len());
+		buffer[..len].copy_from_slice(&data[..len]);
+		data.drain(..len);
+		if data.is_empty() {
+			self.frames.pop_front();
+		}
+		Ok(len)
+	}
+
+	fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+		// Collect the expected data first, so a mismatch leaves the recording untouched.
+		let expected: Vec<u8> = self.frames.iter()
+			.map_while(|(direction, data)| (*direction == Direction::Outbound).then_some(data))
+			.flatten()
+			.take(buffer.len())
+			.copied()
+			.collect();
+		if expected != buffer {
+			return Err(ReplayError::UnexpectedWrite {
+				expected,
+				actual: buffer.to_vec(),
+			});
+		}
+
+		let mut remaining = buffer.len();
+		while remaining > 0 {
+			let Some((_, data)) = self.frames.front_mut() else {
+				break;
+			};
+			let len = data.len().min(remaining);
+			data.drain(..len);
+			remaining -= len;
+			if data.is_empty() {
+				self.frames.pop_front();
+			}
+		}
+		Ok(())
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		Instant::now() + timeout
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		*error == ReplayError::Timeout
+	}
+}
+
+impl std::error::Error for ReplayError {}
+
+impl std::fmt::Display for ReplayError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Timeout => write!(f, "no recorded data to read"),
+			Self::UnexpectedWrite { expected, actual } if expected.is_empty() => {
+				write!(f, "unexpected write: recording has no more data to write, but got {actual:02X?}")
+			},
+			Self::UnexpectedWrite { expected, actual } => {
+				write!(f, "unexpected write: expected {expected:02X?}, got {actual:02X?}")
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::serial_port::reply::ReplySerial;
+	use crate::{CaptureSerialPort, Client, TransferError, WriteError};
+	use assert2::{assert, let_assert};
+
+	/// Record a ping to motor 1 and a ping to motor 2 that times out.
+	fn record_session() -> Vec<u8> {
+		// The ping response of motor 1 from the protocol documentation.
+		let response = vec![0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x55, 0x00, 0x06, 0x04, 0x26, 0x65, 0x5D];
+		let serial_port = ReplySerial { written: Vec::new(), response };
+		let_assert!(Ok(capture) = CaptureSerialPort::new(serial_port, Vec::new()));
+		let_assert!(Ok(mut client) = Client::new(capture));
+		let_assert!(Ok(_) = client.ping(1));
+		let_assert!(Err(_) = client.ping(2));
+		let_assert!(Ok((_serial_port, recording)) = client.into_serial_port().finish());
+		recording
+	}
+
+	#[test]
+	fn replay_recorded_session() {
+		let_assert!(Ok(serial_port) = ReplaySerialPort::from_reader(record_session().as_slice()));
+		assert!(serial_port.remaining_frames() == 3);
+		let_assert!(Ok(mut client) = Client::new(serial_port));
+
+		let_assert!(Ok(response) = client.ping(1));
+		assert!(response.motor_id == 1);
+		assert!(response.data.model == 1030);
+		let_assert!(Err(e) = client.ping(2));
+		assert!(e.is_timeout());
+		assert!(client.serial_port().is_finished());
+	}
+
+	#[test]
+	fn replay_rejects_unexpected_write() {
+		let_assert!(Ok(serial_port) = ReplaySerialPort::from_reader(record_session().as_slice()));
+		let_assert!(Ok(mut client) = Client::new(serial_port));
+
+		let_assert!(Err(e) = client.ping(3));
+		let_assert!(TransferError::WriteError(WriteError::Write(ReplayError::UnexpectedWrite { expected, actual })) = e.error);
+		assert!(expected == [0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x03, 0x00, 0x01, 0x19, 0x4E]);
+		assert!(actual[4] == 3);
+		assert!(client.serial_port().remaining_frames() == 3);
+	}
+}
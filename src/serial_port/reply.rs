@@ -0,0 +1,196 @@
+//! Scripted [`SerialPort`][crate::SerialPort] for unit tests.
+
+use core::time::Duration;
+
+use crate::checksum::calculate_checksum;
+use crate::instructions::{instruction_id, packet_id};
+use crate::Client;
+
+/// Serial port that records written data and replies with a fixed response.
+pub(crate) struct ReplySerial {
+	pub(crate) written: Vec<u8>,
+	pub(crate) response: Vec<u8>,
+}
+
+impl crate::SerialPort for ReplySerial {
+	type Error = std::io::Error;
+	type Instant = std::time::Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		Ok(1_000_000)
+	}
+
+	fn set_baud_rate(&mut self, _baud_rate: u32) -> Result<(), Self::Error> {
+		unimplemented!("not used in this test")
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn read(&mut self, buffer: &mut [u8], _deadline: &Self::Instant) -> Result<usize, Self::Error> {
+		if self.response.is_empty() {
+			return Err(std::io::ErrorKind::TimedOut.into());
+		}
+		let len = self.response.len().min(buffer.len());
+		buffer[..len].copy_from_slice(&self.response[..len]);
+		self.response.drain(..len);
+		Ok(len)
+	}
+
+	fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+		self.written.extend_from_slice(buffer);
+		Ok(())
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		std::time::Instant::now() + timeout
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		error.kind() == std::io::ErrorKind::TimedOut
+	}
+
+	fn now(&self) -> Option<Self::Instant> {
+		Some(std::time::Instant::now())
+	}
+
+	fn elapsed_since(&self, instant: &Self::Instant) -> Option<Duration> {
+		Some(instant.elapsed())
+	}
+}
+
+#[cfg(feature = "async")]
+impl crate::AsyncSerialPort for ReplySerial {
+	type Error = std::io::Error;
+	type Instant = std::time::Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		Ok(1_000_000)
+	}
+
+	fn set_baud_rate(&mut self, _baud_rate: u32) -> Result<(), Self::Error> {
+		unimplemented!("not used in this test")
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn poll_read(&mut self, _cx: &mut core::task::Context<'_>, buffer: &mut [u8], deadline: &Self::Instant) -> core::task::Poll<Result<usize, Self::Error>> {
+		core::task::Poll::Ready(crate::SerialPort::read(self, buffer, deadline))
+	}
+
+	fn poll_write(&mut self, _cx: &mut core::task::Context<'_>, buffer: &[u8]) -> core::task::Poll<Result<usize, Self::Error>> {
+		self.written.extend_from_slice(buffer);
+		core::task::Poll::Ready(Ok(buffer.len()))
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		std::time::Instant::now() + timeout
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		error.kind() == std::io::ErrorKind::TimedOut
+	}
+}
+
+/// Serial port that records written data and generates a response for each write.
+pub(crate) struct ResponderSerial<F> {
+	pub(crate) written: Vec<u8>,
+	pub(crate) response: Vec<u8>,
+	pub(crate) respond: F,
+}
+
+impl<F: FnMut(&[u8]) -> Vec<u8>> crate::SerialPort for ResponderSerial<F> {
+	type Error = std::io::Error;
+	type Instant = std::time::Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		Ok(1_000_000)
+	}
+
+	fn set_baud_rate(&mut self, _baud_rate: u32) -> Result<(), Self::Error> {
+		unimplemented!("not used in this test")
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn read(&mut self, buffer: &mut [u8], _deadline: &Self::Instant) -> Result<usize, Self::Error> {
+		if self.response.is_empty() {
+			return Err(std::io::ErrorKind::TimedOut.into());
+		}
+		let len = self.response.len().min(buffer.len());
+		buffer[..len].copy_from_slice(&self.response[..len]);
+		self.response.drain(..len);
+		Ok(len)
+	}
+
+	fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+		self.written.extend_from_slice(buffer);
+		let response = (self.respond)(buffer);
+		self.response.extend(response);
+		Ok(())
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		std::time::Instant::now() + timeout
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		error.kind() == std::io::ErrorKind::TimedOut
+	}
+}
+
+/// Build a combined fast read response from (error, motor ID, data) segments.
+pub(crate) fn fast_read_response(segments: &[(u8, u8, &[u8])]) -> Vec<u8> {
+	fast_read_response_with_bad_crc(segments, None)
+}
+
+/// Build a combined fast read response, with an invalid CRC for one of the segments.
+///
+/// The body is byte-stuffed and the CRCs are computed over the stuffed bytes,
+/// but the length field does not include the stuffing bytes, like the response of multiple devices.
+pub(crate) fn fast_read_response_with_bad_crc(segments: &[(u8, u8, &[u8])], bad_crc: Option<usize>) -> Vec<u8> {
+	let length: usize = segments.iter().map(|(_, _, data)| data.len() + 4).sum::<usize>() + 1;
+	let mut packet = vec![0xFF, 0xFF, 0xFD, 0x00, packet_id::BROADCAST];
+	packet.extend_from_slice(&(length as u16).to_le_bytes());
+	let mut state = 0;
+	push_stuffed(&mut packet, &mut state, &[instruction_id::STATUS]);
+	for (i, &(error, motor_id, data)) in segments.iter().enumerate() {
+		push_stuffed(&mut packet, &mut state, &[error, motor_id]);
+		push_stuffed(&mut packet, &mut state, data);
+		let mut checksum = calculate_checksum(0, &packet);
+		if bad_crc == Some(i) {
+			checksum ^= 0xFFFF;
+		}
+		// The CRC of the last segment is the packet CRC, which is not stuffed.
+		if i + 1 == segments.len() {
+			packet.extend_from_slice(&checksum.to_le_bytes());
+		} else {
+			push_stuffed(&mut packet, &mut state, &checksum.to_le_bytes());
+		}
+	}
+	packet
+}
+
+/// Append data to a packet with byte-stuffing, continuing from the stuffing state of the preceding data.
+fn push_stuffed(packet: &mut Vec<u8>, state: &mut usize, data: &[u8]) {
+	use crate::bus::bytestuff::PATTERN;
+	for &byte in data {
+		packet.push(byte);
+		*state = if byte == PATTERN[*state] { *state + 1 } else { 0 };
+		if *state == PATTERN.len() - 1 {
+			packet.push(PATTERN[3]);
+			*state = 0;
+		}
+	}
+}
+
+/// Create a client that replies with a fixed response.
+pub(crate) fn make_client(response: Vec<u8>) -> Client<ReplySerial> {
+	assert2::let_assert!(Ok(client) = Client::new(ReplySerial { written: Vec::new(), response }));
+	client
+}
@@ -41,4 +41,12 @@ impl crate::SerialPort for serial2::SerialPort {
 	fn is_timeout_error(error: &Self::Error) -> bool {
 		error.kind() == std::io::ErrorKind::TimedOut
 	}
+
+	fn now(&self) -> Option<Self::Instant> {
+		Some(Instant::now())
+	}
+
+	fn elapsed_since(&self, instant: &Self::Instant) -> Option<Duration> {
+		Some(instant.elapsed())
+	}
 }
@@ -0,0 +1,142 @@
+//! Async trait implementation using the `serial2-tokio` crate.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::time::{Instant, Sleep};
+
+/// A [`serial2_tokio::SerialPort`] that implements [`crate::AsyncSerialPort`].
+///
+/// The serial port is combined with a tokio timer to implement the read deadlines.
+/// The timer is created on the first read, so it must be used from within a tokio runtime.
+#[derive(Debug)]
+pub struct TokioSerialPort {
+	/// The wrapped serial port.
+	serial_port: serial2_tokio::SerialPort,
+
+	/// The timer for the deadline of the current read.
+	sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl TokioSerialPort {
+	/// Wrap an open serial port.
+	///
+	/// The serial port must already be configured in raw mode with the correct baud rate,
+	/// character size (8), parity (disabled) and stop bits (1).
+	pub fn new(serial_port: serial2_tokio::SerialPort) -> Self {
+		Self {
+			serial_port,
+			sleep: None,
+		}
+	}
+
+	/// Open a serial port with the given baud rate.
+	///
+	/// This must be called from within a tokio runtime.
+	pub fn open(path: impl AsRef<Path>, baud_rate: u32) -> std::io::Result<Self> {
+		let serial_port = serial2_tokio::SerialPort::open(path, baud_rate)?;
+		Ok(Self::new(serial_port))
+	}
+
+	/// Get a reference to the wrapped serial port.
+	pub fn get_ref(&self) -> &serial2_tokio::SerialPort {
+		&self.serial_port
+	}
+
+	/// Consume the wrapper to get ownership of the serial port.
+	pub fn into_inner(self) -> serial2_tokio::SerialPort {
+		self.serial_port
+	}
+
+	/// Poll the timer for the given deadline, resetting it if the deadline changed.
+	fn poll_deadline(&mut self, cx: &mut Context<'_>, deadline: Instant) -> Poll<()> {
+		let sleep = match &mut self.sleep {
+			Some(sleep) => {
+				if sleep.deadline() != deadline {
+					sleep.as_mut().reset(deadline);
+				}
+				sleep
+			},
+			None => self.sleep.insert(Box::pin(tokio::time::sleep_until(deadline))),
+		};
+		sleep.as_mut().poll(cx)
+	}
+}
+
+impl crate::AsyncSerialPort for TokioSerialPort {
+	type Error = std::io::Error;
+
+	type Instant = Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		self.serial_port.get_configuration()?
+			.get_baud_rate()
+	}
+
+	fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+		let mut settings = self.serial_port.get_configuration()?;
+		settings.set_baud_rate(baud_rate)?;
+		self.serial_port.set_configuration(&settings)?;
+		Ok(())
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		self.serial_port.discard_input_buffer()
+	}
+
+	fn poll_read(&mut self, cx: &mut Context<'_>, buffer: &mut [u8], deadline: &Self::Instant) -> Poll<Result<usize, Self::Error>> {
+		let mut buffer = ReadBuf::new(buffer);
+		if let Poll::Ready(result) = Pin::new(&mut self.serial_port).poll_read(cx, &mut buffer) {
+			return Poll::Ready(result.map(|()| buffer.filled().len()));
+		}
+		match self.poll_deadline(cx, *deadline) {
+			Poll::Ready(()) => Poll::Ready(Err(std::io::ErrorKind::TimedOut.into())),
+			Poll::Pending => Poll::Pending,
+		}
+	}
+
+	fn poll_write(&mut self, cx: &mut Context<'_>, buffer: &[u8]) -> Poll<Result<usize, Self::Error>> {
+		Pin::new(&mut self.serial_port).poll_write(cx, buffer)
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		Instant::now() + timeout
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		error.kind() == std::io::ErrorKind::TimedOut
+	}
+}
+
+// Pseudo-terminal pairs are only available on Unix.
+#[cfg(all(test, unix))]
+mod test {
+	use super::*;
+	use crate::AsyncSerialPort;
+	use assert2::{assert, let_assert};
+
+	async fn read(serial_port: &mut TokioSerialPort, buffer: &mut [u8], timeout: Duration) -> std::io::Result<usize> {
+		let deadline = serial_port.make_deadline(timeout);
+		core::future::poll_fn(|cx| serial_port.poll_read(cx, buffer, &deadline)).await
+	}
+
+	#[tokio::test]
+	async fn read_times_out_at_deadline() {
+		let_assert!(Ok((a, b)) = serial2_tokio::SerialPort::pair());
+		let mut a = TokioSerialPort::new(a);
+		let mut buffer = [0; 8];
+
+		let start = Instant::now();
+		let_assert!(Err(e) = read(&mut a, &mut buffer, Duration::from_millis(20)).await);
+		assert!(TokioSerialPort::is_timeout_error(&e));
+		assert!(start.elapsed() >= Duration::from_millis(20));
+
+		// A later read with a new deadline must not be affected by the expired timer.
+		let_assert!(Ok(()) = b.write_all(&[1, 2, 3]).await);
+		let_assert!(Ok(3) = read(&mut a, &mut buffer, Duration::from_secs(1)).await);
+		assert!(buffer[..3] == [1, 2, 3]);
+	}
+}
@@ -0,0 +1,296 @@
+//! Communication statistics of a bus.
+
+use core::time::Duration;
+
+/// The number of motor IDs that can be used for a single motor (0 to 252).
+#[cfg(feature = "motor-stats")]
+const MOTOR_ID_COUNT: usize = 253;
+
+/// The number of buckets in a [`LatencyHistogram`].
+const LATENCY_BUCKETS: usize = 16;
+
+/// The upper bound of the first bucket of a [`LatencyHistogram`].
+const FIRST_LATENCY_BUCKET: Duration = Duration::from_micros(125);
+
+/// Statistics about the communication on a bus.
+///
+/// The counters are updated by the [`Client`][crate::Client] or [`Device`][crate::Device] that owns the bus.
+/// They can be used to detect a flaky connection,
+/// for example by looking for an increasing number of checksum errors, garbage bytes or timeouts.
+///
+/// Use [`Client::stats()`][crate::Client::stats] or [`Device::stats()`][crate::Device::stats] to get the statistics.
+///
+/// The number of errors per motor is only counted if the `motor-stats` feature is enabled,
+/// since it adds about 1 KiB to every bus.
+#[derive(Clone)]
+pub struct Stats {
+	/// The number of packets written to the bus.
+	pub packets_sent: u64,
+
+	/// The number of packets with a valid checksum read from the bus.
+	pub packets_received: u64,
+
+	/// The number of bytes written to the bus.
+	pub bytes_sent: u64,
+
+	/// The number of bytes read from the bus.
+	pub bytes_received: u64,
+
+	/// The number of received packets with an invalid checksum.
+	pub checksum_errors: u64,
+
+	/// The number of garbage bytes that were skipped while looking for the start of a packet.
+	pub garbage_bytes: u64,
+
+	/// The number of reads that timed out before a complete packet was received.
+	pub timeouts: u64,
+
+	/// The time between sending an instruction and receiving a status packet.
+	///
+	/// Only available if the serial port can measure time, see [`SerialPort::elapsed_since()`][crate::SerialPort::elapsed_since].
+	pub latency: LatencyHistogram,
+
+	/// The number of errors per motor ID.
+	#[cfg(feature = "motor-stats")]
+	motor_errors: [u32; MOTOR_ID_COUNT],
+}
+
+impl Stats {
+	/// Create new statistics with all counters set to zero.
+	pub const fn new() -> Self {
+		Self {
+			packets_sent: 0,
+			packets_received: 0,
+			bytes_sent: 0,
+			bytes_received: 0,
+			checksum_errors: 0,
+			garbage_bytes: 0,
+			timeouts: 0,
+			latency: LatencyHistogram::new(),
+			#[cfg(feature = "motor-stats")]
+			motor_errors: [0; MOTOR_ID_COUNT],
+		}
+	}
+
+	/// Get the number of errors for a motor.
+	///
+	/// This counts the status packets from the motor that reported an error,
+	/// and the timeouts while waiting for a status packet from the motor.
+	///
+	/// Always returns 0 for the broadcast ID and the reserved IDs.
+	///
+	/// This function is only available if the `motor-stats` feature is enabled.
+	#[cfg(feature = "motor-stats")]
+	pub fn motor_errors(&self, motor_id: u8) -> u32 {
+		self.motor_errors.get(usize::from(motor_id)).copied().unwrap_or(0)
+	}
+
+	/// Iterate over the motor IDs with at least one error, together with their number of errors.
+	///
+	/// This function is only available if the `motor-stats` feature is enabled.
+	#[cfg(feature = "motor-stats")]
+	pub fn motors_with_errors(&self) -> impl Iterator<Item = (u8, u32)> + '_ {
+		self.motor_errors.iter()
+			.enumerate()
+			.filter(|(_, &count)| count > 0)
+			.map(|(motor_id, &count)| (motor_id as u8, count))
+	}
+
+	/// Record an error for a motor.
+	///
+	/// Does nothing if the `motor-stats` feature is disabled.
+	pub(crate) fn record_motor_error(&mut self, motor_id: u8) {
+		#[cfg(feature = "motor-stats")]
+		if let Some(count) = self.motor_errors.get_mut(usize::from(motor_id)) {
+			*count = count.saturating_add(1);
+		}
+		#[cfg(not(feature = "motor-stats"))]
+		let _ = motor_id;
+	}
+}
+
+impl Default for Stats {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl core::fmt::Debug for Stats {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		#[cfg(feature = "motor-stats")]
+		struct MotorErrors<'a>(&'a Stats);
+
+		#[cfg(feature = "motor-stats")]
+		impl core::fmt::Debug for MotorErrors<'_> {
+			fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+				f.debug_map().entries(self.0.motors_with_errors()).finish()
+			}
+		}
+
+		let mut debug = f.debug_struct("Stats");
+		debug
+			.field("packets_sent", &self.packets_sent)
+			.field("packets_received", &self.packets_received)
+			.field("bytes_sent", &self.bytes_sent)
+			.field("bytes_received", &self.bytes_received)
+			.field("checksum_errors", &self.checksum_errors)
+			.field("garbage_bytes", &self.garbage_bytes)
+			.field("timeouts", &self.timeouts)
+			.field("latency", &self.latency);
+		#[cfg(feature = "motor-stats")]
+		debug.field("motor_errors", &MotorErrors(self));
+		debug.finish()
+	}
+}
+
+/// A histogram of latencies with exponentially growing buckets.
+///
+/// The first bucket holds latencies below 125 microseconds.
+/// Every next bucket has twice the upper bound of the previous bucket.
+/// The last bucket holds all latencies that do not fit in the other buckets (2.048 seconds and more).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LatencyHistogram {
+	/// The number of samples in each bucket.
+	counts: [u32; LATENCY_BUCKETS],
+
+	/// The sum of all samples.
+	total: Duration,
+
+	/// The largest sample.
+	max: Option<Duration>,
+}
+
+impl LatencyHistogram {
+	/// Create an empty histogram.
+	pub const fn new() -> Self {
+		Self {
+			counts: [0; LATENCY_BUCKETS],
+			total: Duration::ZERO,
+			max: None,
+		}
+	}
+
+	/// Get the total number of samples in the histogram.
+	pub fn count(&self) -> u64 {
+		self.counts.iter().map(|&count| u64::from(count)).sum()
+	}
+
+	/// Get the mean of all samples, or `None` if the histogram is empty.
+	pub fn mean(&self) -> Option<Duration> {
+		let count = self.count();
+		if count == 0 {
+			return None;
+		}
+		let nanos = self.total.as_nanos() / u128::from(count);
+		Some(Duration::from_nanos(nanos as u64))
+	}
+
+	/// Get the largest sample, or `None` if the histogram is empty.
+	pub fn max(&self) -> Option<Duration> {
+		self.max
+	}
+
+	/// Iterate over the buckets of the histogram.
+	///
+	/// Each item holds the exclusive upper bound of the bucket, and the number of samples in it.
+	/// The upper bound of the last bucket is `None`.
+	pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u32)> + '_ {
+		self.counts.iter()
+			.enumerate()
+			.map(|(i, &count)| (bucket_upper_bound(i), count))
+	}
+
+	/// Add a sample to the histogram.
+	pub(crate) fn record(&mut self, latency: Duration) {
+		let bucket = (0..LATENCY_BUCKETS)
+			.find(|&i| bucket_upper_bound(i).is_none_or(|bound| latency < bound))
+			.unwrap_or(LATENCY_BUCKETS - 1);
+		self.counts[bucket] = self.counts[bucket].saturating_add(1);
+		self.total = self.total.saturating_add(latency);
+		self.max = Some(self.max.map_or(latency, |max| max.max(latency)));
+	}
+}
+
+impl Default for LatencyHistogram {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Get the exclusive upper bound of a latency bucket, or `None` for the last bucket.
+fn bucket_upper_bound(index: usize) -> Option<Duration> {
+	if index + 1 < LATENCY_BUCKETS {
+		Some(FIRST_LATENCY_BUCKET * (1 << index))
+	} else {
+		None
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::serial_port::reply::ReplySerial;
+	use crate::Client;
+	use assert2::{assert, let_assert};
+
+	#[test]
+	fn client_records_stats() {
+		// Two bytes of garbage, followed by the ping response of motor 1 from the protocol documentation.
+		let response = vec![0x00, 0x01, 0xFF, 0xFF, 0xFD, 0x00, 0x01, 0x07, 0x00, 0x55, 0x00, 0x06, 0x04, 0x26, 0x65, 0x5D];
+		let_assert!(Ok(mut client) = Client::new(ReplySerial { written: Vec::new(), response }));
+		let_assert!(Ok(_) = client.ping(1));
+
+		let stats = client.stats();
+		assert!(stats.packets_sent == 1);
+		assert!(stats.bytes_sent == 10);
+		assert!(stats.packets_received == 1);
+		assert!(stats.bytes_received == 16);
+		assert!(stats.garbage_bytes == 2);
+		assert!(stats.checksum_errors == 0);
+		assert!(stats.latency.count() == 1);
+
+		let_assert!(Err(_) = client.ping(2));
+		let stats = client.stats();
+		assert!(stats.packets_sent == 2);
+		assert!(stats.timeouts == 1);
+		#[cfg(feature = "motor-stats")]
+		{
+			assert!(stats.motor_errors(2) == 1);
+			assert!(stats.motor_errors(1) == 0);
+		}
+
+		client.reset_stats();
+		assert!(client.stats().packets_sent == 0);
+	}
+
+	#[test]
+	fn latency_histogram() {
+		let mut histogram = LatencyHistogram::new();
+		assert!(histogram.mean() == None);
+		histogram.record(Duration::from_micros(100));
+		histogram.record(Duration::from_micros(300));
+		histogram.record(Duration::from_secs(10));
+
+		assert!(histogram.count() == 3);
+		assert!(histogram.max() == Some(Duration::from_secs(10)));
+		let buckets: Vec<_> = histogram.buckets().collect();
+		assert!(buckets.len() == LATENCY_BUCKETS);
+		assert!(buckets[0] == (Some(Duration::from_micros(125)), 1));
+		assert!(buckets[2] == (Some(Duration::from_micros(500)), 1));
+		assert!(buckets[14] == (Some(Duration::from_millis(2048)), 0));
+		assert!(buckets[15] == (None, 1));
+	}
+
+	#[test]
+	#[cfg(feature = "motor-stats")]
+	fn motor_errors() {
+		let mut stats = Stats::new();
+		stats.record_motor_error(7);
+		stats.record_motor_error(7);
+		stats.record_motor_error(2);
+		stats.record_motor_error(crate::instructions::packet_id::BROADCAST);
+		assert!(stats.motor_errors(7) == 2);
+		assert!(stats.motor_errors(crate::instructions::packet_id::BROADCAST) == 0);
+		assert!(stats.motors_with_errors().collect::<Vec<_>>() == [(2, 1), (7, 2)]);
+	}
+}
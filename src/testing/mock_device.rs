@@ -0,0 +1,347 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use super::mock_serial::lock;
+use super::MockSerial;
+use crate::{Clear, ControlTable, Device, DeviceServer, FactoryReset, MemoryControlTable, MotorErrorKind, ReadError};
+
+/// How long a device waits for an instruction before checking if it should stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The address of the ID register in the control table.
+const ID_ADDRESS: u16 = 7;
+
+/// The address of the Return Delay Time register in the control table.
+const RETURN_DELAY_TIME_ADDRESS: u16 = 9;
+
+/// The configuration of a simulated device on a [`MockBus`][super::MockBus].
+///
+/// The device is a [`DeviceServer`] with a [`MemoryControlTable`]:
+/// every address can be read and written, and writes have no side effects.
+/// The model number, firmware version, ID and return delay time are stored at the addresses used by the X-series motors.
+#[derive(Debug, Clone)]
+pub struct MockDevice {
+	/// The ID of the device.
+	id: u8,
+
+	/// The model number reported in response to a ping.
+	model_number: u16,
+
+	/// The firmware version reported in response to a ping.
+	firmware_version: u8,
+
+	/// The size of the control table in bytes.
+	control_table_size: usize,
+
+	/// The initial value of the Return Delay Time register, in units of 2 microseconds.
+	return_delay_time: u8,
+}
+
+/// A fault to inject in the communication of a simulated device.
+///
+/// Faults are injected with [`MockDeviceHandle::inject_fault()`].
+/// Each fault applies to a single instruction addressed to the device, in the order they were injected.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Fault {
+	/// Ignore the instruction, as if it was never received.
+	///
+	/// This will cause the client to time out while waiting for a response.
+	NoResponse,
+
+	/// Do not execute the instruction, and report an error in the status packet instead.
+	///
+	/// The value is the raw error field of the status packet, including the hardware error alert bit (0x80).
+	Error(u8),
+
+	/// Execute the instruction, but corrupt the checksum of the status packet.
+	CorruptChecksum,
+}
+
+/// A handle to inspect and control a running simulated device.
+///
+/// Handles can be obtained from a [`MockBus`][super::MockBus].
+#[derive(Debug, Clone)]
+pub struct MockDeviceHandle {
+	/// The ID of the device.
+	id: u8,
+
+	/// The state shared with the device thread.
+	state: Arc<SharedState>,
+}
+
+/// The state of a simulated device that is shared between the device thread and its handles.
+#[derive(Debug)]
+struct SharedState {
+	/// The control table of the device.
+	control_table: Mutex<MemoryControlTable>,
+
+	/// The faults to inject for the next instructions.
+	faults: Mutex<VecDeque<Fault>>,
+
+	/// Flag to set the hardware error alert bit in the status packets for the current instruction.
+	alert: AtomicBool,
+}
+
+/// The control table of a simulated device, shared with the handles of the device.
+struct SharedControlTable {
+	/// The state shared with the handles.
+	state: Arc<SharedState>,
+}
+
+/// A simulated device that runs in a background thread.
+struct MockDeviceRunner {
+	/// The ID of the device.
+	id: u8,
+
+	/// The server answering the instructions for the device.
+	server: DeviceServer<MockSerial>,
+
+	/// The state shared with the handles of the device.
+	state: Arc<SharedState>,
+
+	/// Flag to corrupt the next status packet.
+	corrupt_next_write: Arc<AtomicBool>,
+}
+
+impl MockDevice {
+	/// The default model number, reported in response to a ping.
+	pub const DEFAULT_MODEL_NUMBER: u16 = 5130;
+
+	/// The default firmware version, reported in response to a ping.
+	pub const DEFAULT_FIRMWARE_VERSION: u8 = 46;
+
+	/// The default size of the control table in bytes.
+	pub const DEFAULT_CONTROL_TABLE_SIZE: usize = 200;
+
+	/// Create the configuration for a new device with the given ID.
+	///
+	/// The device uses the default model number, firmware version and control table size,
+	/// and it responds to instructions without delay.
+	pub fn new(id: u8) -> Self {
+		Self {
+			id,
+			model_number: Self::DEFAULT_MODEL_NUMBER,
+			firmware_version: Self::DEFAULT_FIRMWARE_VERSION,
+			control_table_size: Self::DEFAULT_CONTROL_TABLE_SIZE,
+			return_delay_time: 0,
+		}
+	}
+
+	/// Set the model number reported by the device.
+	pub fn with_model_number(self, model_number: u16) -> Self {
+		Self { model_number, ..self }
+	}
+
+	/// Set the firmware version reported by the device.
+	pub fn with_firmware_version(self, firmware_version: u8) -> Self {
+		Self { firmware_version, ..self }
+	}
+
+	/// Set the size of the control table in bytes.
+	///
+	/// Reads and writes outside of the control table are answered with an access error.
+	pub fn with_control_table_size(self, control_table_size: usize) -> Self {
+		Self { control_table_size, ..self }
+	}
+
+	/// Set the initial value of the Return Delay Time register (address 9), in units of 2 microseconds.
+	///
+	/// The device waits this long before sending a status packet.
+	/// The register can be changed later by writing to it, just like on a real motor.
+	pub fn with_return_delay_time(self, return_delay_time: u8) -> Self {
+		Self { return_delay_time, ..self }
+	}
+
+	/// Get the ID of the device.
+	pub fn id(&self) -> u8 {
+		self.id
+	}
+
+	/// Create the initial control table of the device.
+	fn control_table(&self) -> MemoryControlTable {
+		let mut control_table = MemoryControlTable::new(self.model_number, self.firmware_version, self.control_table_size);
+		for (address, value) in [(ID_ADDRESS, self.id), (RETURN_DELAY_TIME_ADDRESS, self.return_delay_time)] {
+			if usize::from(address) < self.control_table_size {
+				control_table = control_table.with_default(address, &[value]);
+			}
+		}
+		control_table
+	}
+
+	/// Start a thread to simulate the device on a serial port.
+	pub(crate) fn spawn(self, serial_port: MockSerial, kill: Arc<AtomicBool>) -> std::io::Result<(MockDeviceHandle, JoinHandle<()>)> {
+		let corrupt_next_write = serial_port.corrupt_next_write.clone();
+		let state = Arc::new(SharedState {
+			control_table: Mutex::new(self.control_table()),
+			faults: Mutex::new(VecDeque::new()),
+			alert: AtomicBool::new(false),
+		});
+		let handle = MockDeviceHandle {
+			id: self.id,
+			state: state.clone(),
+		};
+		let device = Device::new(serial_port)?;
+		let thread = std::thread::Builder::new()
+			.name(format!("mock device {}", self.id))
+			.spawn(move || {
+				let control_table = SharedControlTable { state: state.clone() };
+				let runner = MockDeviceRunner {
+					id: self.id,
+					server: DeviceServer::new(device, self.id, control_table),
+					state,
+					corrupt_next_write,
+				};
+				runner.run(&kill)
+			})?;
+		Ok((handle, thread))
+	}
+}
+
+impl MockDeviceHandle {
+	/// Get the ID of the device.
+	pub fn id(&self) -> u8 {
+		self.id
+	}
+
+	/// Read from the control table of the device.
+	///
+	/// Returns `None` if the range is outside of the control table.
+	pub fn read_control_table(&self, address: u16, length: u16) -> Option<Vec<u8>> {
+		self.state.read_control_table(address, length)
+	}
+
+	/// Write to the control table of the device.
+	///
+	/// Returns `false` if the range is outside of the control table.
+	pub fn write_control_table(&self, address: u16, data: &[u8]) -> bool {
+		self.state.write_control_table(address, data)
+	}
+
+	/// Inject a fault for the next instruction addressed to the device.
+	///
+	/// If multiple faults are injected, they apply to consecutive instructions.
+	pub fn inject_fault(&self, fault: Fault) {
+		lock(&self.state.faults).push_back(fault);
+	}
+
+	/// Remove all injected faults that have not been triggered yet.
+	pub fn clear_faults(&self) {
+		lock(&self.state.faults).clear();
+	}
+}
+
+impl SharedState {
+	/// Read from the control table.
+	fn read_control_table(&self, address: u16, length: u16) -> Option<Vec<u8>> {
+		let control_table = lock(&self.control_table);
+		let range = control_table_range(address, length.into(), control_table.data().len())?;
+		Some(control_table.data()[range].to_vec())
+	}
+
+	/// Write to the control table.
+	fn write_control_table(&self, address: u16, data: &[u8]) -> bool {
+		let mut control_table = lock(&self.control_table);
+		let Some(range) = control_table_range(address, data.len(), control_table.data().len()) else {
+			return false;
+		};
+		control_table.data_mut()[range].copy_from_slice(data);
+		true
+	}
+}
+
+impl ControlTable for SharedControlTable {
+	fn model_number(&self) -> u16 {
+		lock(&self.state.control_table).model_number()
+	}
+
+	fn firmware_version(&self) -> u8 {
+		lock(&self.state.control_table).firmware_version()
+	}
+
+	fn read(&mut self, address: u16, buffer: &mut [u8]) -> Result<(), MotorErrorKind> {
+		lock(&self.state.control_table).read(address, buffer)
+	}
+
+	fn write(&mut self, address: u16, data: &[u8]) -> Result<(), MotorErrorKind> {
+		lock(&self.state.control_table).write(address, data)
+	}
+
+	fn factory_reset(&mut self, kind: FactoryReset) -> Result<(), MotorErrorKind> {
+		lock(&self.state.control_table).factory_reset(kind)
+	}
+
+	fn clear(&mut self, kind: Clear) -> Result<(), MotorErrorKind> {
+		lock(&self.state.control_table).clear(kind)
+	}
+
+	fn backup(&mut self) -> Result<(), MotorErrorKind> {
+		lock(&self.state.control_table).backup()
+	}
+
+	fn restore(&mut self) -> Result<(), MotorErrorKind> {
+		lock(&self.state.control_table).restore()
+	}
+
+	fn alert(&self) -> bool {
+		self.state.alert.load(Ordering::Relaxed)
+	}
+}
+
+impl MockDeviceRunner {
+	/// Handle instructions until `kill` is set.
+	fn run(mut self, kill: &AtomicBool) {
+		while !kill.load(Ordering::Relaxed) {
+			self.update_return_delay_time();
+			let instruction = match self.server.device_mut().read_owned(POLL_INTERVAL) {
+				Ok(instruction) => instruction,
+				Err(ReadError::Timeout { .. }) => continue,
+				Err(e) => {
+					debug!("mock device {}: ignoring invalid packet: {}", self.id, e);
+					continue;
+				},
+			};
+			if !self.server.is_addressed(&instruction) {
+				continue;
+			}
+
+			let fault = lock(&self.state.faults).pop_front();
+			let accepted = match fault {
+				None => Ok(()),
+				Some(Fault::NoResponse) => continue,
+				Some(Fault::Error(error)) => {
+					self.state.alert.store(error & 0x80 != 0, Ordering::Relaxed);
+					Err(MotorErrorKind::from_error_number(error & 0x7F))
+				},
+				Some(Fault::CorruptChecksum) => {
+					self.corrupt_next_write.store(true, Ordering::Relaxed);
+					Ok(())
+				},
+			};
+			let result = self.server.answer(instruction, accepted);
+			self.state.alert.store(false, Ordering::Relaxed);
+			if let Err(e) = result {
+				error!("mock device {}: failed to answer instruction: {}", self.id, e);
+			}
+		}
+	}
+
+	/// Apply the Return Delay Time register from the control table to the device.
+	fn update_return_delay_time(&mut self) {
+		let return_delay_time = lock(&self.state.control_table)
+			.data()
+			.get(usize::from(RETURN_DELAY_TIME_ADDRESS))
+			.copied()
+			.unwrap_or(0);
+		self.server.device_mut().set_return_delay_time(return_delay_time);
+	}
+}
+
+/// Get the range of a read or write in a control table, or `None` if it is out of bounds.
+fn control_table_range(address: u16, length: usize, size: usize) -> Option<core::ops::Range<usize>> {
+	let start = usize::from(address);
+	let end = start.checked_add(length)?;
+	(end <= size).then_some(start..end)
+}
@@ -0,0 +1,149 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Data written by a [`MockSerial`] that has not been read yet by another [`MockSerial`] on the same bus.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedBuffer {
+	buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl SharedBuffer {
+	/// Append data to the buffer.
+	fn write(&self, data: &[u8]) {
+		lock(&self.buffer).extend_from_slice(data);
+	}
+
+	/// Move as much data as possible from the shared buffer into `buffer`.
+	fn read(&self, buffer: &mut [u8]) -> usize {
+		let mut data = lock(&self.buffer);
+		let len = data.len().min(buffer.len());
+		buffer[..len].copy_from_slice(&data[..len]);
+		data.drain(..len);
+		len
+	}
+}
+
+/// A simulated serial port connected to a shared bus.
+///
+/// Everything written by one [`MockSerial`] is received by all other [`MockSerial`]s on the same bus.
+/// A client side [`MockSerial`] is created by [`MockBus::start()`][super::MockBus::start].
+///
+/// Reads block until data is available or the deadline expires, just like a real serial port.
+#[derive(Debug)]
+pub struct MockSerial {
+	/// The name used in log messages.
+	name: String,
+
+	/// The data written to this port by the other ports on the bus.
+	read_buffer: SharedBuffer,
+
+	/// The read buffers of the other ports on the bus.
+	peers: Vec<SharedBuffer>,
+
+	/// The configured baud rate.
+	baud_rate: u32,
+
+	/// If set, the next write will have its last byte corrupted.
+	pub(crate) corrupt_next_write: Arc<AtomicBool>,
+}
+
+impl MockSerial {
+	/// The default baud rate of a new mock serial port.
+	const DEFAULT_BAUD_RATE: u32 = 57600;
+
+	/// Create a new mock serial port that is not connected to other ports yet.
+	pub(crate) fn new(name: impl Into<String>) -> Self {
+		Self {
+			name: name.into(),
+			read_buffer: SharedBuffer::default(),
+			peers: Vec::new(),
+			baud_rate: Self::DEFAULT_BAUD_RATE,
+			corrupt_next_write: Arc::new(AtomicBool::new(false)),
+		}
+	}
+
+	/// Connect a set of mock serial ports, so that each of them receives the data written by all the others.
+	pub(crate) fn connect(ports: &mut [&mut Self]) {
+		let buffers: Vec<_> = ports.iter().map(|port| port.read_buffer.clone()).collect();
+		for (i, port) in ports.iter_mut().enumerate() {
+			port.peers.extend(buffers.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, buffer)| buffer.clone()));
+		}
+	}
+
+	/// Get the name of the serial port, as used in log messages.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+}
+
+impl crate::SerialPort for MockSerial {
+	type Error = std::io::Error;
+
+	type Instant = Instant;
+
+	fn baud_rate(&self) -> Result<u32, Self::Error> {
+		Ok(self.baud_rate)
+	}
+
+	fn set_baud_rate(&mut self, baud_rate: u32) -> Result<(), Self::Error> {
+		self.baud_rate = baud_rate;
+		Ok(())
+	}
+
+	fn discard_input_buffer(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn read(&mut self, buffer: &mut [u8], deadline: &Self::Instant) -> Result<usize, Self::Error> {
+		loop {
+			let read = self.read_buffer.read(buffer);
+			if read > 0 {
+				trace!("{} read: {:02X?}", self.name, &buffer[..read]);
+				return Ok(read);
+			}
+			if Instant::now() > *deadline {
+				return Err(std::io::ErrorKind::TimedOut.into());
+			}
+			std::thread::yield_now();
+		}
+	}
+
+	fn write_all(&mut self, buffer: &[u8]) -> Result<(), Self::Error> {
+		let mut data = buffer.to_vec();
+		if self.corrupt_next_write.swap(false, Ordering::Relaxed) {
+			if let Some(last) = data.last_mut() {
+				*last = !*last;
+			}
+		}
+		trace!("{} write: {:02X?}", self.name, data);
+		for peer in &self.peers {
+			peer.write(&data);
+		}
+		Ok(())
+	}
+
+	fn make_deadline(&self, timeout: Duration) -> Self::Instant {
+		Instant::now() + timeout
+	}
+
+	fn is_timeout_error(error: &Self::Error) -> bool {
+		error.kind() == std::io::ErrorKind::TimedOut
+	}
+
+	fn now(&self) -> Option<Self::Instant> {
+		Some(Instant::now())
+	}
+
+	fn elapsed_since(&self, instant: &Self::Instant) -> Option<Duration> {
+		Some(instant.elapsed())
+	}
+}
+
+/// Lock a mutex, ignoring poisoning.
+///
+/// A panic in a device thread is reported when the [`MockBus`][super::MockBus] is dropped,
+/// so the data behind the lock is still good enough for a test.
+pub(crate) fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+	mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
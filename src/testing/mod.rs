@@ -0,0 +1,244 @@
+//! Simulated serial bus and devices for testing code that uses a [`Client`].
+//!
+//! A [`MockBus`] connects a [`Client<MockSerial>`][Client] to a set of simulated devices,
+//! each running in a background thread.
+//! This allows you to test code that talks to motors without any hardware.
+//!
+//! The simulated devices are [`DeviceServer`][crate::DeviceServer]s with a plain [`MemoryControlTable`][crate::MemoryControlTable],
+//! and you can inspect or modify the control table and inject faults through a [`MockDeviceHandle`].
+//!
+//! This module is only available if the `testing` feature is enabled.
+//!
+//! # Example
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use dynamixel2::testing::{Fault, MockBus, MockDevice};
+//!
+//! let (mut client, bus) = MockBus::start([
+//!     MockDevice::new(1).with_model_number(1060),
+//!     MockDevice::new(2),
+//! ])?;
+//!
+//! let response = client.ping(1)?;
+//! assert_eq!(response.data.model, 1060);
+//!
+//! bus.device(2).unwrap().write_control_table(132, &1234u32.to_le_bytes());
+//! assert_eq!(client.read::<u32>(2, 132)?.data, 1234);
+//!
+//! bus.device(2).unwrap().inject_fault(Fault::NoResponse);
+//! assert!(client.read::<u32>(2, 132).is_err());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use crate::Client;
+
+mod mock_device;
+pub use mock_device::{Fault, MockDevice, MockDeviceHandle};
+
+mod mock_serial;
+pub use mock_serial::MockSerial;
+
+/// A simulated bus with devices running in background threads.
+///
+/// The devices keep running until the [`MockBus`] is dropped.
+/// If a device thread panicked, dropping the [`MockBus`] panics too, so the failure is not lost.
+#[derive(Debug)]
+pub struct MockBus {
+	/// The handles of the simulated devices.
+	devices: Vec<MockDeviceHandle>,
+
+	/// Flag to stop the device threads.
+	kill: Arc<AtomicBool>,
+
+	/// The device threads.
+	threads: Vec<JoinHandle<()>>,
+}
+
+impl MockBus {
+	/// Start the simulated devices and create a client connected to them.
+	pub fn start(devices: impl IntoIterator<Item = MockDevice>) -> std::io::Result<(Client<MockSerial>, Self)> {
+		let devices: Vec<_> = devices.into_iter().collect();
+		let mut client_port = MockSerial::new("client");
+		let mut device_ports: Vec<_> = devices.iter()
+			.map(|device| MockSerial::new(format!("device {}", device.id())))
+			.collect();
+		let mut ports: Vec<_> = core::iter::once(&mut client_port).chain(device_ports.iter_mut()).collect();
+		MockSerial::connect(&mut ports);
+
+		let mut bus = Self {
+			devices: Vec::with_capacity(devices.len()),
+			kill: Arc::new(AtomicBool::new(false)),
+			threads: Vec::with_capacity(devices.len()),
+		};
+		for (device, serial_port) in devices.into_iter().zip(device_ports) {
+			let (handle, thread) = device.spawn(serial_port, bus.kill.clone())?;
+			bus.devices.push(handle);
+			bus.threads.push(thread);
+		}
+
+		let client = Client::new(client_port)?;
+		Ok((client, bus))
+	}
+
+	/// Get the handle of the device with the given ID.
+	///
+	/// If multiple devices have the same ID, the first one is returned.
+	pub fn device(&self, id: u8) -> Option<&MockDeviceHandle> {
+		self.devices.iter().find(|device| device.id() == id)
+	}
+
+	/// Get the handles of all devices on the bus.
+	pub fn devices(&self) -> &[MockDeviceHandle] {
+		&self.devices
+	}
+}
+
+impl Drop for MockBus {
+	fn drop(&mut self) {
+		self.kill.store(true, Ordering::Relaxed);
+		for thread in self.threads.drain(..) {
+			if thread.join().is_err() && !std::thread::panicking() {
+				panic!("mock device thread panicked");
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{MotorErrorKind, ReadError, TransferError};
+	use assert2::{assert, let_assert};
+	use std::time::Duration;
+
+	#[test]
+	fn device_configuration() {
+		let_assert!(Ok((mut client, bus)) = MockBus::start([
+			MockDevice::new(3).with_model_number(1060).with_firmware_version(52).with_control_table_size(16),
+		]));
+		let_assert!(Ok(response) = client.ping(3));
+		assert!(response.data.model == 1060);
+		assert!(response.data.firmware == 52);
+		let_assert!(Ok(response) = client.read::<u8>(3, 7));
+		assert!(response.data == 3);
+
+		let_assert!(Err(e) = client.read::<u32>(3, 14));
+		let_assert!(TransferError::ReadError(ReadError::MotorError(e)) = e.error);
+		assert!(e.kind() == MotorErrorKind::AccessError);
+		assert!(bus.device(3).unwrap().read_control_table(14, 2) == Some(vec![0, 0]));
+		assert!(bus.device(4).is_none());
+	}
+
+	#[test]
+	fn inject_faults() {
+		let_assert!(Ok((mut client, bus)) = MockBus::start([MockDevice::new(1)]));
+		let device = bus.device(1).unwrap();
+		device.inject_fault(Fault::NoResponse);
+		device.inject_fault(Fault::Error(0x80 | 0x01));
+		device.inject_fault(Fault::CorruptChecksum);
+
+		let_assert!(Err(e) = client.write(1, 64, &1u8));
+		assert!(e.is_timeout());
+		let_assert!(Err(e) = client.write(1, 64, &1u8));
+		let_assert!(TransferError::ReadError(ReadError::MotorError(e)) = e.error);
+		assert!(e.kind() == MotorErrorKind::ResultFail);
+		assert!(e.alert());
+		assert!(device.read_control_table(64, 1) == Some(vec![0]));
+
+		// The write is executed, but the status packet is corrupted.
+		let_assert!(Err(e) = client.write(1, 64, &1u8));
+		let_assert!(TransferError::ReadError(ReadError::InvalidMessage(_)) = e.error);
+		assert!(device.read_control_table(64, 1) == Some(vec![1]));
+		let_assert!(Ok(_) = client.write(1, 64, &0u8));
+	}
+
+	#[test]
+	fn scan_multiple_devices() {
+		let_assert!(Ok((mut client, _bus)) = MockBus::start([MockDevice::new(5), MockDevice::new(1)]));
+		let_assert!(Ok(scan) = client.scan());
+		let responses: Vec<_> = scan.collect();
+		let_assert!([Ok(first), Ok(second)] = responses.as_slice());
+		assert!(first.motor_id == 1);
+		assert!(second.motor_id == 5);
+	}
+
+	#[test]
+	fn scan_reports_errors_with_context() {
+		let_assert!(Ok((mut client, bus)) = MockBus::start([MockDevice::new(1), MockDevice::new(2)]));
+		bus.device(1).unwrap().inject_fault(Fault::Error(0x01));
+		let_assert!(Ok(scan) = client.scan());
+		let responses: Vec<_> = scan.collect();
+		let_assert!([Err(first), Ok(second)] = responses.as_slice());
+		let_assert!(TransferError::ReadError(ReadError::MotorError(e)) = &first.error);
+		assert!(e.kind() == MotorErrorKind::ResultFail);
+		let_assert!(Some(context) = &first.context);
+		assert!(context.instruction_id == crate::instructions::instruction_id::PING);
+		assert!(context.motor_id == crate::instructions::packet_id::BROADCAST);
+		assert!(second.motor_id == 2);
+	}
+
+	#[test]
+	fn fast_read_byte_stuffing() {
+		let_assert!(Ok((mut client, bus)) = MockBus::start([MockDevice::new(1), MockDevice::new(2), MockDevice::new(3)]));
+		assert!(bus.device(1).unwrap().write_control_table(64, &[0xFF, 0xFF, 0xFD, 0xFD]));
+		assert!(bus.device(2).unwrap().write_control_table(64, &[0x00, 0xFF, 0xFF, 0xFD]));
+		assert!(bus.device(3).unwrap().write_control_table(64, &[0xFD, 0xFF, 0xFF, 0xFD]));
+
+		let_assert!(Ok(responses) = client.fast_sync_read::<u32>(&[1, 2, 3], 64));
+		let responses: Vec<_> = responses.collect();
+		let_assert!([Ok(first), Ok(second), Ok(third)] = responses.as_slice());
+		assert!(first.data == 0xFDFD_FFFF);
+		assert!(second.data == 0xFDFF_FF00);
+		assert!(third.data == 0xFDFF_FFFD);
+
+		let reads = [
+			crate::instructions::BulkReadData { motor_id: 2, address: 65, count: 3 },
+			crate::instructions::BulkReadData { motor_id: 1, address: 64, count: 4 },
+		];
+		let_assert!(Ok(responses) = client.fast_bulk_read_bytes::<Vec<u8>>(&reads));
+		let responses: Vec<_> = responses.collect();
+		let_assert!([Ok(first), Ok(second)] = responses.as_slice());
+		assert!(first.data == [0xFF, 0xFF, 0xFD]);
+		assert!(second.data == [0xFF, 0xFF, 0xFD, 0xFD]);
+	}
+
+	#[test]
+	fn action_without_reg_write() {
+		let_assert!(Ok((mut client, _bus)) = MockBus::start([MockDevice::new(1)]));
+		let_assert!(Err(e) = client.action(1));
+		let_assert!(TransferError::ReadError(ReadError::MotorError(e)) = e.error);
+		assert!(e.kind() == MotorErrorKind::InstructionError);
+	}
+
+	#[test]
+	fn return_delay() {
+		let_assert!(Ok((mut client, bus)) = MockBus::start([MockDevice::new(1).with_return_delay_time(250)]));
+		assert!(bus.device(1).unwrap().read_control_table(9, 1) == Some(vec![250]));
+		let start = std::time::Instant::now();
+		let_assert!(Ok(_) = client.ping(1));
+		assert!(start.elapsed() >= Duration::from_micros(500));
+	}
+
+	#[test]
+	fn write_return_delay_time() {
+		let_assert!(Ok((mut client, _bus)) = MockBus::start([MockDevice::new(1)]));
+		let_assert!(Ok(_) = client.write(1, 9, &254u8));
+
+		// The new value applies from the next instruction on.
+		for _ in 0..3 {
+			let start = std::time::Instant::now();
+			let_assert!(Ok(_) = client.ping(1));
+			assert!(start.elapsed() >= Duration::from_micros(508));
+		}
+
+		let_assert!(Ok(_) = client.write(1, 9, &0u8));
+		let_assert!(Ok(_) = client.ping(1));
+		let_assert!(Ok(response) = client.read::<u8>(1, 9));
+		assert!(response.data == 0);
+	}
+}
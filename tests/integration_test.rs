@@ -60,7 +60,20 @@ fn test_reg_write_bytes() {
 }
 
 #[test]
-#[cfg_attr(not(feature = "integration_test"), should_panic)] // test panics as Mock Serial doesn't currently support sync_read
+fn test_control_table_backup() {
+	run(|ids, mut client| {
+		let_assert!(Ok(_) = client.write(ids[0], 65, &0u8));
+		let_assert!(Ok(response) = client.control_table_backup(ids[0]));
+		assert!(response.motor_id == ids[0]);
+		let_assert!(Ok(_) = client.write(ids[0], 65, &1u8));
+		let_assert!(Ok(response) = client.control_table_restore(ids[0]));
+		assert!(response.motor_id == ids[0]);
+		let_assert!(Ok(response) = client.read::<u8>(ids[0], 65));
+		assert!(response.data == 0);
+	})
+}
+
+#[test]
 fn test_sync_read() {
 	run(|ids, mut client| {
 		let response = client.sync_read::<u32>(ids, 132).unwrap();
@@ -76,7 +89,6 @@ fn test_sync_read() {
 }
 
 #[test]
-#[cfg_attr(not(feature = "integration_test"), should_panic)] // test panics as Mock Serial doesn't currently support sync_read
 fn test_sync_read_bytes() {
 	run(|ids, mut client| {
 		let response = client.sync_read_bytes::<Vec<u8>>(ids, 132, 4).unwrap();
@@ -92,7 +104,6 @@ fn test_sync_read_bytes() {
 }
 
 #[test]
-#[cfg_attr(not(feature = "integration_test"), should_panic)] // test panics as Mock Serial doesn't currently support bulk_read
 fn test_bulk_read_bytes() {
 	run(|ids, mut client| {
 		let bulk_read_data: Vec<_> = ids
@@ -115,6 +126,47 @@ fn test_bulk_read_bytes() {
 	})
 }
 
+#[test]
+fn test_fast_sync_read() {
+	run(|ids, mut client| {
+		let response = client.fast_sync_read::<u32>(ids, 132).unwrap();
+		assert!(response.len() == ids.len());
+		for (r, id) in response.zip(ids) {
+			match r {
+				Err(e) => panic!("id {id} {e}"),
+				Ok(r) => {
+					assert!(r.motor_id == *id)
+				},
+			}
+		}
+	})
+}
+
+#[test]
+fn test_fast_bulk_read_bytes() {
+	run(|ids, mut client| {
+		let bulk_read_data: Vec<_> = ids
+			.iter()
+			.enumerate()
+			.map(|(i, id)| BulkReadData {
+				motor_id: *id,
+				address: 132,
+				count: 4 - (i % 4) as u16,
+			})
+			.collect();
+		let response = client.fast_bulk_read_bytes::<Vec<u8>>(&bulk_read_data).unwrap();
+		for (r, read) in response.zip(&bulk_read_data) {
+			match r {
+				Err(e) => panic!("id {} {e}", read.motor_id),
+				Ok(r) => {
+					assert!(r.motor_id == read.motor_id);
+					assert!(r.data.len() == read.count as usize);
+				},
+			}
+		}
+	})
+}
+
 #[test]
 fn test_ping() {
 	run(|ids, mut client| {